@@ -0,0 +1,137 @@
+use std::time::Instant;
+
+use mos::mos::block::BlockCache;
+use mos::mos::bus::FastBus;
+use mos::mos::rp2a03::Rp2a03;
+
+const CYCLES: u64 = 20_000_000;
+const ORIGIN: u16 = 0x8000;
+
+struct Ram {
+    mem: Vec<u8>,
+}
+
+impl Ram {
+    fn with_program(program: &[u8]) -> Ram {
+        let mut mem = vec![0; 0x10000];
+        let start = ORIGIN as usize;
+        mem[start..start + program.len()].copy_from_slice(program);
+        Ram { mem }
+    }
+}
+
+impl FastBus for Ram {
+    fn read(&mut self, address: u16) -> u8 {
+        self.mem[address as usize]
+    }
+
+    fn write(&mut self, address: u16, data: u8) {
+        self.mem[address as usize] = data;
+    }
+}
+
+// counter loop: lda/adc/sta zero page, dex, bne
+const ARITHMETIC: &[u8] = &[
+    0xA2, 0x00,         // ldx #$00
+    0xA5, 0x10,         // lda $10
+    0x18,               // clc
+    0x69, 0x03,         // adc #$03
+    0x85, 0x10,         // sta $10
+    0xCA,               // dex
+    0xD0, 0xF6,         // bne -10
+    0x4C, 0x00, 0x80,   // jmp $8000
+];
+
+// page copy through an indirect pointer
+const MEMCPY: &[u8] = &[
+    0xA9, 0x00,         // lda #$00
+    0x85, 0x20,         // sta $20
+    0xA9, 0x80,         // lda #$80
+    0x85, 0x21,         // sta $21
+    0xA0, 0x00,         // ldy #$00
+    0xB1, 0x20,         // lda ($20),y
+    0x99, 0x00, 0x03,   // sta $0300,y
+    0xC8,               // iny
+    0xD0, 0xF8,         // bne -8
+    0x4C, 0x00, 0x80,   // jmp $8000
+];
+
+// subroutine calls with stack traffic
+const SUBROUTINE: &[u8] = &[
+    0x20, 0x06, 0x80,   // jsr $8006
+    0x4C, 0x00, 0x80,   // jmp $8000
+    0x48,               // pha
+    0x08,               // php
+    0x28,               // plp
+    0x68,               // pla
+    0x60,               // rts
+];
+
+// read-modify-write with indexed addressing
+const MODIFY: &[u8] = &[
+    0xA2, 0x00,         // ldx #$00
+    0xFE, 0x00, 0x04,   // inc $0400,x
+    0x1E, 0x00, 0x05,   // asl $0500,x
+    0x56, 0x40,         // lsr $40,x
+    0xE8,               // inx
+    0xD0, 0xF5,         // bne -11
+    0x4C, 0x00, 0x80,   // jmp $8000
+];
+
+fn run(name: &str, program: &[u8]) {
+    let mut bus = Ram::with_program(program);
+    let (mut cpu, _) = Rp2a03::from_power_on();
+    let mut pinout = cpu.debug_reset(ORIGIN, &mut bus);
+
+    let start = Instant::now();
+    for _ in 0..CYCLES {
+        pinout = cpu.tick(&mut bus, pinout);
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    println!("{:<12} {:>8.2} Mcycles/s", name, CYCLES as f64 / elapsed / 1_000_000.0);
+}
+
+fn run_fast(name: &str, program: &[u8]) {
+    let mut bus = Ram::with_program(program);
+    let (mut cpu, _) = Rp2a03::from_power_on();
+    let mut pinout = cpu.debug_reset(ORIGIN, &mut bus);
+
+    let start = Instant::now();
+    while cpu.cycle_count() < CYCLES {
+        pinout = cpu.execute_instruction(&mut bus, pinout);
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    println!("{:<12} {:>8.2} Mcycles/s (instruction)", name, cpu.cycle_count() as f64 / elapsed / 1_000_000.0);
+}
+
+fn run_block(name: &str, program: &[u8]) {
+    let mut cache = BlockCache::new(Ram::with_program(program));
+    cache.map_memory(0x0000, 0xFFFF);
+    let (mut cpu, _) = Rp2a03::from_power_on();
+    let pinout = cpu.debug_reset(ORIGIN, cache.bus_mut());
+
+    let start = Instant::now();
+    cache.run(&mut cpu, pinout, CYCLES);
+    let elapsed = start.elapsed().as_secs_f64();
+
+    println!("{:<12} {:>8.2} Mcycles/s (block)", name, cpu.cycle_count() as f64 / elapsed / 1_000_000.0);
+}
+
+fn main() {
+    run("arithmetic", ARITHMETIC);
+    run("memcpy", MEMCPY);
+    run("subroutine", SUBROUTINE);
+    run("modify", MODIFY);
+
+    run_fast("arithmetic", ARITHMETIC);
+    run_fast("memcpy", MEMCPY);
+    run_fast("subroutine", SUBROUTINE);
+    run_fast("modify", MODIFY);
+
+    run_block("arithmetic", ARITHMETIC);
+    run_block("memcpy", MEMCPY);
+    run_block("subroutine", SUBROUTINE);
+    run_block("modify", MODIFY);
+}
@@ -0,0 +1,89 @@
+use std::env;
+use std::fs::File;
+use std::io::BufWriter;
+use std::process;
+
+use mos::mos::clock::Region;
+use mos::mos::nsf::{self, Nsf, NsfPlayer};
+
+/*
+Render a track of an NSF or NSFe file to a wav file
+
+mos-nsf <file> <out.wav> [--track n] [--seconds s] [--rate hz] [--pal | --ntsc]
+
+Tracks count from 1 and default to the starting track of the file. The length defaults to
+the NSFe track time plus its fade, or 150 seconds.
+*/
+
+const USAGE: &str = "usage: mos-nsf <file> <out.wav> [--track n] [--seconds s] [--rate hz] [--pal | --ntsc]";
+const DEFAULT_SECONDS: f64 = 150.0;
+
+struct Options {
+    input: String,
+    output: String,
+    track: Option<u8>,
+    seconds: Option<f64>,
+    rate: u32,
+    region: Option<Region>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut args = env::args().skip(1);
+    let mut paths = Vec::new();
+    let mut options = Options { input: String::new(), output: String::new(), track: None, seconds: None, rate: 44100, region: None };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--track" => {
+                let track: u8 = value("--track")?.parse().map_err(|_| "--track is not a number".to_string())?;
+                options.track = Some(track.checked_sub(1).ok_or("tracks count from 1")?);
+            }
+            "--seconds" => options.seconds = Some(value("--seconds")?.parse().map_err(|_| "--seconds is not a number".to_string())?),
+            "--rate" => options.rate = value("--rate")?.parse().map_err(|_| "--rate is not a number".to_string())?,
+            "--pal" => options.region = Some(Region::Pal),
+            "--ntsc" => options.region = Some(Region::Ntsc),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => paths.push(arg),
+        }
+    }
+
+    if paths.len() != 2 {
+        return Err(USAGE.to_string());
+    }
+    options.output = paths.pop().unwrap_or_default();
+    options.input = paths.pop().unwrap_or_default();
+    Ok(options)
+}
+
+fn run(options: Options) -> Result<(), Box<dyn std::error::Error>> {
+    let nsf = Nsf::load(&options.input)?;
+    let track = options.track.unwrap_or(nsf.starting_song);
+    let seconds = options.seconds.unwrap_or_else(|| {
+        let time = nsf.track_times.get(track as usize).cloned().flatten();
+        let fade = nsf.track_fades.get(track as usize).cloned().flatten().unwrap_or(0);
+        time.map(|time| (time + fade) as f64 / 1000.0).unwrap_or(DEFAULT_SECONDS)
+    });
+
+    let mut player = NsfPlayer::new(&nsf, track, options.region)?;
+    let mut samples = vec![0.0; (seconds * options.rate as f64) as usize];
+    player.render(options.rate, &mut samples);
+
+    let writer = BufWriter::new(File::create(&options.output)?);
+    nsf::write_wav(writer, options.rate, &samples)?;
+    Ok(())
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(2);
+        }
+    };
+    if let Err(err) = run(options) {
+        eprintln!("mos-nsf: {}", err);
+        process::exit(1);
+    }
+}
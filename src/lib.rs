@@ -6,12 +6,1709 @@ pub mod mos;
 
 #[cfg(test)]
 mod tests {
-    use super::mos::{rp2a03, Ctrl, Pinout};
-    
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::mos::{rp2a03, rp2a07, Ctrl, InterruptState, Pinout};
+    use super::mos::block::BlockCache;
+    use super::mos::clock::{Region, Scheduler};
+    use super::mos::timing::{self, ApuTiming};
+    use super::mos::asm::{self, AsmError};
+    use super::mos::stats::{BranchStats, InterruptStats};
+    use super::mos::AddressingMode;
+    use super::mos::disasm;
+    use super::mos::symbols::{SymbolError, SymbolTable};
+    use super::mos::apu::{self, Apu};
+    use super::mos::nsf::{self, Nsf, NsfError, NsfFormat, NsfPlayer};
+    use super::mos::cdl::{self, CdlBus, CodeDataLog};
+    use super::mos::cartridge::{Cartridge, CartridgeError, Format, Header, Mirroring, Timing};
+    use super::mos::memory_map::{Drive, MemoryMap};
+    use super::mos::loader::{LoadError, Program, Segment};
+    use super::mos::irq::{IrqLine, IrqSource};
+    use super::mos::bus::{self, FastBus};
+    use super::mos::power::{self, PowerOnProfile};
+    use super::mos::profiler::{Entry, InterruptProfile, ProfilerBus};
+    use super::mos::unstable::UnstableOpcodeConfig;
+
+    struct Ram {
+        mem: Vec<u8>,
+    }
+
+    impl Ram {
+        fn with_program(origin: u16, program: &[u8]) -> Ram {
+            let mut mem = vec![0; 0x10000];
+            mem[origin as usize..origin as usize + program.len()].copy_from_slice(program);
+            Ram { mem }
+        }
+    }
+
+    impl FastBus for Ram {
+        fn read(&mut self, address: u16) -> u8 {
+            self.mem[address as usize]
+        }
+
+        fn write(&mut self, address: u16, data: u8) {
+            self.mem[address as usize] = data;
+        }
+    }
+
+    struct Run {
+        cpu: rp2a03::Rp2a03,
+        bus: Ram,
+        pinout: Pinout,
+    }
+
+    /*
+    Runs a program from origin twice. run drives the first cpu on the instruction level path,
+    then a second cpu is ticked up to the same cycle and both must agree on the log, the
+    address bus and memory. setup configures either cpu and its memory before the reset.
+    */
+    fn run_both<S, R>(origin: u16, program: &[u8], setup: S, run: R) -> (Run, Run)
+    where
+        S: Fn(&mut rp2a03::Rp2a03, &mut Ram),
+        R: FnOnce(&mut rp2a03::Rp2a03, &mut Ram, Pinout) -> Pinout,
+    {
+        let start = |setup: &S| {
+            let mut bus = Ram::with_program(origin, program);
+            let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+            setup(&mut cpu, &mut bus);
+            let pinout = cpu.debug_reset(origin, &mut bus);
+            Run { cpu, bus, pinout }
+        };
+
+        let mut fast = start(&setup);
+        fast.pinout = run(&mut fast.cpu, &mut fast.bus, fast.pinout);
+
+        let mut tick = start(&setup);
+        while tick.cpu.cycle_count() < fast.cpu.cycle_count() {
+            tick.pinout = tick.cpu.tick(&mut tick.bus, tick.pinout);
+        }
+
+        assert_eq!(tick.cpu.debug_log(), fast.cpu.debug_log());
+        assert_eq!(tick.pinout.address, fast.pinout.address);
+        assert!(tick.bus.mem == fast.bus.mem);
+        (tick, fast)
+    }
+
+    fn instructions(count: usize) -> impl FnOnce(&mut rp2a03::Rp2a03, &mut Ram, Pinout) -> Pinout {
+        move |cpu, bus, mut pinout| {
+            for _ in 0..count {
+                pinout = cpu.execute_instruction(bus, pinout);
+            }
+            pinout
+        }
+    }
+
     #[test]
+    #[allow(unused_variables, unused_mut, clippy::bool_assert_comparison)]
     fn it_works() {
         let (mut cpu, mut cpu_pinout) = rp2a03::Rp2a03::from_power_on();
         
         assert_eq!(cpu_pinout.ctrl.contains(Ctrl::RDY), true);
     }
+
+    #[test]
+    fn interrupt_pushes_to_stack() {
+        // brk then a cli, jmp * loop taking an irq, both handled by an rti at $9000
+        let mut bus = Ram::with_program(0x8000, &[0x00, 0xEA, 0x58, 0x4C, 0x03, 0x80]);
+        bus.mem[0x9000] = 0x40;
+        bus.mem[0xFFFE..].copy_from_slice(&[0x00, 0x90]);
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+
+        let mut writes = Vec::new();
+        for cycle in 0..60 {
+            if cycle >= 30 {
+                pinout.ctrl.set(Ctrl::IRQ, false);
+            }
+            pinout = cpu.tick(&mut bus, pinout);
+            if !pinout.ctrl.contains(Ctrl::RW) {
+                writes.push(pinout.address);
+            }
+        }
+        // pc high, pc low and status for the brk and again for the irq, nothing in zero page
+        assert_eq!(&writes[..6], &[0x01FD, 0x01FC, 0x01FB, 0x01FD, 0x01FC, 0x01FB]);
+        assert!(bus.mem[0xFB..=0xFD].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn indirect_y_store_crosses_page() {
+        // ldy #$10, lda #$42, sta ($20),y with the pointer at $02F8, the store lands on $0308
+        let program = [0xA0, 0x10, 0xA9, 0x42, 0x91, 0x20, 0x4C, 0x06, 0x80];
+        let pointer = |_: &mut rp2a03::Rp2a03, bus: &mut Ram| bus.mem[0x20..0x22].copy_from_slice(&[0xF8, 0x02]);
+        let (_, fast) = run_both(0x8000, &program, pointer, instructions(6));
+
+        assert_eq!(fast.bus.mem[0x0308], 0x42);
+        assert_eq!(fast.bus.mem[0x0208], 0x00);
+    }
+
+    #[test]
+    fn opcode_table_dispatch() {
+        let program = [
+            0xA2, 0x05,         // ldx #$05
+            0xA9, 0x00,         // lda #$00
+            0x18,               // clc
+            0x69, 0x03,         // adc #$03
+            0xCA,               // dex
+            0xD0, 0xFA,         // bne -6
+            0x85, 0x10,         // sta $10
+            0xFE, 0x00, 0x02,   // inc $0200,x
+            0x4C, 0x0F, 0x80,   // jmp $800F
+        ];
+        let mut bus = Ram::with_program(0x8000, &program);
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+
+        // reset(9) + ldx(2) + lda(2) + 5 * (clc(2) + adc(2) + dex(2) + bne(3)) - 1 + sta(3) + inc(7)
+        for _ in 0..(9 + 2 + 2 + 5 * 9 - 1 + 3 + 7) {
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+
+        assert_eq!(bus.mem[0x10], 15);
+        assert_eq!(bus.mem[0x200], 1);
+        assert!(cpu.debug_log().contains("JMP"));
+    }
+
+    #[test]
+    fn instruction_level_execution() {
+        let program = [
+            0xA2, 0x10,         // ldx #$10
+            0xBD, 0xF8, 0x80,   // lda $80F8,x
+            0x9D, 0x00, 0x03,   // sta $0300,x
+            0x48,               // pha
+            0x68,               // pla
+            0x3E, 0x00, 0x03,   // rol $0300,x
+            0xCA,               // dex
+            0xD0, 0xF2,         // bne -14
+            0x4C, 0x10, 0x80,   // jmp $8010
+        ];
+        run_both(0x8000, &program, |_, _| {}, |cpu, bus, mut pinout| {
+            for i in 0..200 {
+                // switch paths on instruction boundaries
+                if i % 4 == 0 {
+                    pinout = cpu.tick(bus, pinout);
+                    while !pinout.ctrl.contains(Ctrl::SYNC) {
+                        pinout = cpu.tick(bus, pinout);
+                    }
+                }
+                else {
+                    pinout = cpu.execute_instruction(bus, pinout);
+                }
+            }
+            pinout
+        });
+    }
+
+    #[test]
+    fn pc_wraps_at_ffff() {
+        // lda #$42 with the opcode at $FFFF and its operand at $0000, then sta $10, jmp *
+        let code = |_: &mut rp2a03::Rp2a03, bus: &mut Ram| bus.mem[0x00..0x06].copy_from_slice(&[0x42, 0x85, 0x10, 0x4C, 0x03, 0x00]);
+        let (_, fast) = run_both(0xFFFF, &[0xA9], code, instructions(4));
+
+        assert_eq!(fast.bus.mem[0x10], 0x42);
+        assert_eq!(fast.pinout.address, 0x0003);
+    }
+
+    #[test]
+    fn block_cache_self_modifying_code() {
+        let program = [
+            0xA2, 0x00,         // ldx #$00
+            0xA9, 0x00,         // lda #$00
+            0x9D, 0x00, 0x02,   // sta $0200,x
+            0xEE, 0x03, 0x80,   // inc $8003
+            0xE8,               // inx
+            0xE0, 0x10,         // cpx #$10
+            0xD0, 0xF3,         // bne -13
+            0x4C, 0x0F, 0x80,   // jmp $800F
+        ];
+        let (_, block) = run_both(0x8000, &program, |_, _| {}, |cpu, bus, pinout| {
+            let mut cache = BlockCache::new(Ram { mem: bus.mem.clone() });
+            cache.map_memory(0x0000, 0x07FF);
+            cache.map_memory(0x8000, 0xFFFF);
+            let pinout = cache.run(cpu, pinout, 1000);
+            assert!(cache.block_count() > 0);
+            bus.mem = cache.into_bus().mem;
+            pinout
+        });
+
+        for i in 0..0x10 {
+            assert_eq!(block.bus.mem[0x200 + i], i as u8);
+        }
+    }
+
+    #[test]
+    fn unstable_opcode_profiles() {
+        let program = [
+            0xA2, 0x0F,         // ldx #$0F
+            0xA9, 0xF0,         // lda #$F0
+            0x8B, 0xFF,         // xaa #$FF
+            0x85, 0x10,         // sta $10
+            0xA9, 0x11,         // lda #$11
+            0xAB, 0xF3,         // lax #$F3
+            0x86, 0x11,         // stx $11
+            0xA0, 0x01,         // ldy #$01
+            0xA2, 0x05,         // ldx #$05
+            0x9E, 0xFF, 0x02,   // shx $02FF,y
+            0x4C, 0x15, 0x80,   // jmp $8015
+        ];
+        let profiles = [
+            (UnstableOpcodeConfig::rp2a03(), 0x0F, 0xF3),
+            (UnstableOpcodeConfig::mos6502(), 0x0E, 0xF3),
+            (UnstableOpcodeConfig::zero_magic(), 0x00, 0x11),
+        ];
+
+        for &(config, xaa, lax) in profiles.iter() {
+            let configure = |cpu: &mut rp2a03::Rp2a03, _: &mut Ram| cpu.set_unstable_opcodes(config);
+            let (_, fast) = run_both(0x8000, &program, configure, instructions(20));
+
+            assert_eq!(fast.cpu.unstable_opcodes(), config);
+            assert_eq!(fast.bus.mem[0x10], xaa);
+            assert_eq!(fast.bus.mem[0x11], lax);
+            // x & (0x02 + 1) lands on (0x01, 0x00) once the index crosses the page
+            assert_eq!(fast.bus.mem[0x0100], 0x01);
+            assert_eq!(fast.bus.mem[0x0300], 0x00);
+        }
+    }
+
+    // a cpu state in the layout of the SingleStepTests 6502 suite
+    struct StepState {
+        pc: u16,
+        s: u8,
+        a: u8,
+        x: u8,
+        y: u8,
+        p: u8,
+        ram: &'static [(u16, u8)],
+    }
+
+    // name, initial and final state of one instruction
+    type StepCase = (&'static str, StepState, StepState);
+
+    const fn step(pc: u16, s: u8, a: u8, x: u8, y: u8, p: u8, ram: &'static [(u16, u8)]) -> StepState {
+        StepState { pc, s, a, x, y, p, ram }
+    }
+
+    // SingleStepTests cases for the unstable opcodes, the suite follows the mos6502 profile
+    const UNSTABLE_STEPS: [StepCase; 16] = [
+        ("8b ane", step(0x3A10, 0x8E, 0x5C, 0xB7, 0x21, 0x25, &[(0x3A10, 0x8B), (0x3A11, 0x7D)]),
+                   step(0x3A12, 0x8E, 0x34, 0xB7, 0x21, 0x25, &[(0x3A10, 0x8B), (0x3A11, 0x7D)])),
+        ("8b ane zero", step(0x0C42, 0x3F, 0x01, 0x10, 0x9A, 0xA4, &[(0x0C42, 0x8B), (0x0C43, 0xEF)]),
+                        step(0x0C44, 0x3F, 0x00, 0x10, 0x9A, 0x26, &[(0x0C42, 0x8B), (0x0C43, 0xEF)])),
+        ("8b ane negative", step(0x7701, 0xC2, 0x00, 0xFF, 0x03, 0x66, &[(0x7701, 0x8B), (0x7702, 0xC3)]),
+                            step(0x7703, 0xC2, 0xC2, 0xFF, 0x03, 0xE4, &[(0x7701, 0x8B), (0x7702, 0xC3)])),
+        ("ab lxa", step(0x2B00, 0x51, 0x13, 0x90, 0x44, 0x24, &[(0x2B00, 0xAB), (0x2B01, 0x5A)]),
+                   step(0x2B02, 0x51, 0x5A, 0x5A, 0x44, 0x24, &[(0x2B00, 0xAB), (0x2B01, 0x5A)])),
+        ("ab lxa zero", step(0x5E9C, 0x07, 0x00, 0x6B, 0xD0, 0xA5, &[(0x5E9C, 0xAB), (0x5E9D, 0x11)]),
+                        step(0x5E9E, 0x07, 0x00, 0x00, 0xD0, 0x27, &[(0x5E9C, 0xAB), (0x5E9D, 0x11)])),
+        ("ab lxa negative", step(0x6133, 0xE9, 0x00, 0x02, 0x5F, 0x26, &[(0x6133, 0xAB), (0x6134, 0xF1)]),
+                            step(0x6135, 0xE9, 0xE0, 0xE0, 0x5F, 0xA4, &[(0x6133, 0xAB), (0x6134, 0xF1)])),
+        ("9f sha", step(0x5000, 0x9D, 0xF7, 0x3B, 0x05, 0x24, &[(0x5000, 0x9F), (0x5001, 0x40), (0x5002, 0x12), (0x1245, 0xAA)]),
+                   step(0x5003, 0x9D, 0xF7, 0x3B, 0x05, 0x24, &[(0x5000, 0x9F), (0x5001, 0x40), (0x5002, 0x12), (0x1245, 0x13)])),
+        ("9f sha page cross", step(0x5100, 0x9D, 0xFF, 0x1E, 0x20, 0x25, &[(0x5100, 0x9F), (0x5101, 0xF0), (0x5102, 0x12), (0x1210, 0xAA), (0x1310, 0xBB)]),
+                              step(0x5103, 0x9D, 0xFF, 0x1E, 0x20, 0x25, &[(0x5100, 0x9F), (0x5101, 0xF0), (0x5102, 0x12), (0x1210, 0x12), (0x1310, 0xBB)])),
+        ("93 sha", step(0x6000, 0xF3, 0xCF, 0x7E, 0x10, 0x64, &[(0x6000, 0x93), (0x6001, 0x80), (0x0080, 0x30), (0x0081, 0x07), (0x0740, 0xAA)]),
+                   step(0x6002, 0xF3, 0xCF, 0x7E, 0x10, 0x64, &[(0x6000, 0x93), (0x6001, 0x80), (0x0080, 0x30), (0x0081, 0x07), (0x0740, 0x08)])),
+        ("93 sha page cross", step(0x6100, 0xF3, 0xFF, 0x01, 0x40, 0xE4, &[(0x6100, 0x93), (0x6101, 0x80), (0x0080, 0xE8), (0x0081, 0x04), (0x0128, 0xAA), (0x0528, 0xBB)]),
+                              step(0x6102, 0xF3, 0xFF, 0x01, 0x40, 0xE4, &[(0x6100, 0x93), (0x6101, 0x80), (0x0080, 0xE8), (0x0081, 0x04), (0x0128, 0x01), (0x0528, 0xBB)])),
+        ("9e shx", step(0x7300, 0x2C, 0x90, 0xE6, 0x10, 0xA5, &[(0x7300, 0x9E), (0x7301, 0x80), (0x7302, 0x22), (0x2290, 0xAA)]),
+                   step(0x7303, 0x2C, 0x90, 0xE6, 0x10, 0xA5, &[(0x7300, 0x9E), (0x7301, 0x80), (0x7302, 0x22), (0x2290, 0x22)])),
+        ("9e shx page cross", step(0x7000, 0x2C, 0x90, 0x21, 0x0C, 0x24, &[(0x7000, 0x9E), (0x7001, 0xF8), (0x7002, 0x22), (0x2104, 0xAA), (0x2304, 0xBB)]),
+                              step(0x7003, 0x2C, 0x90, 0x21, 0x0C, 0x24, &[(0x7000, 0x9E), (0x7001, 0xF8), (0x7002, 0x22), (0x2104, 0x21), (0x2304, 0xBB)])),
+        ("9c shy", step(0x7100, 0x6A, 0x3D, 0x02, 0xF5, 0x27, &[(0x7100, 0x9C), (0x7101, 0x40), (0x7102, 0x33), (0x3342, 0xAA)]),
+                   step(0x7103, 0x6A, 0x3D, 0x02, 0xF5, 0x27, &[(0x7100, 0x9C), (0x7101, 0x40), (0x7102, 0x33), (0x3342, 0x34)])),
+        ("9c shy page cross", step(0x7200, 0x6A, 0x3D, 0x01, 0x16, 0x24, &[(0x7200, 0x9C), (0x7201, 0xFF), (0x7202, 0x33), (0x1400, 0xAA), (0x3400, 0xBB)]),
+                              step(0x7203, 0x6A, 0x3D, 0x01, 0x16, 0x24, &[(0x7200, 0x9C), (0x7201, 0xFF), (0x7202, 0x33), (0x1400, 0x14), (0x3400, 0xBB)])),
+        ("9b tas", step(0x4E00, 0xD8, 0xB3, 0x6D, 0x03, 0x24, &[(0x4E00, 0x9B), (0x4E01, 0x10), (0x4E02, 0x44), (0x4413, 0xAA)]),
+                   step(0x4E03, 0x21, 0xB3, 0x6D, 0x03, 0x24, &[(0x4E00, 0x9B), (0x4E01, 0x10), (0x4E02, 0x44), (0x4413, 0x01)])),
+        ("9b tas page cross", step(0x4F00, 0xD8, 0xFF, 0x44, 0x80, 0x24, &[(0x4F00, 0x9B), (0x4F01, 0xC0), (0x4F02, 0x44), (0x4440, 0xAA), (0x4540, 0xBB)]),
+                              step(0x4F03, 0x44, 0xFF, 0x44, 0x80, 0x24, &[(0x4F00, 0x9B), (0x4F01, 0xC0), (0x4F02, 0x44), (0x4440, 0x44), (0x4540, 0xBB)])),
+    ];
+
+    #[test]
+    fn single_step_unstable_opcodes() {
+        for (name, initial, expected) in UNSTABLE_STEPS.iter() {
+            // ldx #s, txs, lda #p, pha, lda #a, ldx #x, ldy #y, plp, jmp pc
+            let preamble = [
+                0xA2, initial.s, 0x9A, 0xA9, initial.p, 0x48, 0xA9, initial.a, 0xA2, initial.x,
+                0xA0, initial.y, 0x28, 0x4C, initial.pc as u8, (initial.pc >> 8) as u8,
+            ];
+            let ram = |cpu: &mut rp2a03::Rp2a03, bus: &mut Ram| {
+                cpu.set_unstable_opcodes(UnstableOpcodeConfig::mos6502());
+                for &(address, data) in initial.ram.iter() {
+                    bus.mem[address as usize] = data;
+                }
+            };
+            // the reset sequence, nine preamble instructions and the one under test
+            let (_, fast) = run_both(0xF000, &preamble, ram, instructions(11));
+
+            let state = (fast.pinout.address, fast.cpu.sp(), fast.cpu.a(), fast.cpu.x(), fast.cpu.y(), fast.cpu.p());
+            assert_eq!(state, (expected.pc, expected.s, expected.a, expected.x, expected.y, expected.p), "{}", name);
+            for &(address, data) in expected.ram.iter() {
+                assert_eq!(fast.bus.mem[address as usize], data, "{} ${:04X}", name, address);
+            }
+        }
+    }
+
+    #[test]
+    fn kil_jams_until_reset() {
+        let program = [
+            0xA9, 0x01,         // lda #$01
+            0x02,               // kil
+            0xA9, 0x02,         // lda #$02
+        ];
+        let mut bus = Ram::with_program(0x8000, &program);
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let jammed_at = Rc::new(Cell::new(None));
+        let callback_jammed_at = jammed_at.clone();
+        cpu.set_jam_callback(move |address| callback_jammed_at.set(Some(address)));
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+
+        // reset(9) + lda(2), the kil opcode has been fetched
+        for _ in 0..(9 + 2) {
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+        assert_eq!(pinout.address, 0x8002);
+        assert!(!cpu.is_jammed());
+
+        let addresses = [0x8003, 0xFFFF, 0xFFFE, 0xFFFE, 0xFFFF, 0xFFFF, 0xFFFF];
+        for &address in addresses.iter() {
+            // interrupts are ignored once jammed
+            pinout.ctrl.set(Ctrl::IRQ, false);
+            pinout.ctrl.set(Ctrl::NMI, false);
+            pinout = cpu.tick(&mut bus, pinout);
+            assert_eq!(pinout.address, address);
+            assert!(pinout.ctrl.contains(Ctrl::RW));
+            assert!(cpu.is_jammed());
+        }
+        assert_eq!(jammed_at.get(), Some(0x8002));
+
+        // the instruction level path advances a jammed cpu one cycle at a time
+        let cycle = cpu.cycle_count();
+        pinout = cpu.execute_instruction(&mut bus, pinout);
+        assert_eq!(cpu.cycle_count(), cycle + 1);
+        assert_eq!(pinout.address, 0xFFFF);
+
+        cpu.debug_reset(0x8003, &mut bus);
+        assert!(!cpu.is_jammed());
+    }
+
+    #[test]
+    fn res_pin_warm_reset() {
+        let program = [
+            0x58,               // cli
+            0xA2, 0x12,         // ldx #$12
+            0xA0, 0x34,         // ldy #$34
+            0xA9, 0x56,         // lda #$56
+            0x4C, 0x07, 0x80,   // jmp $8007
+        ];
+        let mut bus = Ram::with_program(0x8000, &program);
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+
+        for _ in 0..40 {
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+        let cycle = cpu.cycle_count();
+
+        for _ in 0..3 {
+            pinout.ctrl.set(Ctrl::RES, false);
+            pinout = cpu.tick(&mut bus, pinout);
+            assert!(pinout.ctrl.contains(Ctrl::RW));
+        }
+
+        // reads at pc, then the stack pushes with writes suppressed, then the vector
+        let mut addresses = Vec::new();
+        for _ in 0..8 {
+            pinout = cpu.tick(&mut bus, pinout);
+            assert!(pinout.ctrl.contains(Ctrl::RW));
+            addresses.push(pinout.address);
+        }
+        assert_eq!(&addresses[3..], &[0x01FD, 0x01FC, 0x01FB, 0xFFFC, 0xFFFD]);
+
+        pinout = cpu.tick(&mut bus, pinout);
+        assert!(pinout.ctrl.contains(Ctrl::SYNC));
+        assert_eq!(pinout.address, 0x8000);
+        assert_eq!(cpu.cycle_count(), cycle + 3 + 9);
+        assert!(bus.mem[0x100..0x200].iter().all(|&b| b == 0));
+
+        let log = cpu.debug_log();
+        assert!(log.contains("A:0x56 X:0x12 Y:0x34"));
+        assert!(log.contains("SP:0xFA"));
+        // i flag set again after the cli
+        assert!(log.contains("P:0x24"));
+    }
+
+    #[test]
+    fn power_on_profiles() {
+        let mut ram = bus::Ram::from_power_on(0x800, PowerOnProfile::Sample(&power::NES_FRONT_LOADER));
+        assert_eq!(&ram.memory()[..8], &[0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF]);
+        // mirrored every 2k
+        assert_eq!(FastBus::read(&mut ram, 0x0804), 0xFF);
+
+        let first = bus::Ram::from_power_on(0x800, PowerOnProfile::Random(7));
+        let again = bus::Ram::from_power_on(0x800, PowerOnProfile::Random(7));
+        let other = bus::Ram::from_power_on(0x800, PowerOnProfile::Random(8));
+        assert!(first.memory() == again.memory());
+        assert!(first.memory() != other.memory());
+
+        let profile = PowerOnProfile::Random(7);
+        let registers = profile.registers();
+        let mut bus = Ram::with_program(0x8000, &[0x4C, 0x00, 0x80]);
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on_with(profile);
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+        for _ in 0..9 {
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+
+        assert_eq!(cpu.power_on_profile(), profile);
+        let log = cpu.debug_log();
+        let expected = format!("A:{:#X} X:{:#X} Y:{:#X} P:{:#X} SP:0xFD", registers.a, registers.x, registers.y,
+            (registers.p | 0x24) & !0x10);
+        assert!(log.contains(&expected), "{}", log);
+    }
+
+    // run with irq held low, returns the address and flags pushed by the first interrupt
+    fn irq_pushed(program: &[u8], fast: bool) -> (u16, u8) {
+        let mut bus = Ram::with_program(0x8000, program);
+        // irq handler at $9000 loops forever
+        bus.mem[0x9000..0x9003].copy_from_slice(&[0x4C, 0x00, 0x90]);
+        bus.mem[0xFFFE] = 0x00;
+        bus.mem[0xFFFF] = 0x90;
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+
+        for _ in 0..200 {
+            pinout.ctrl.set(Ctrl::IRQ, false);
+            pinout = if fast { cpu.execute_instruction(&mut bus, pinout) } else { cpu.tick(&mut bus, pinout) };
+        }
+
+        (u16::from(bus.mem[0x1FD]) << 8 | u16::from(bus.mem[0x1FC]), bus.mem[0x1FB])
+    }
+
+    #[test]
+    fn interrupt_latency() {
+        // cli only takes effect after the next instruction
+        let cli = [0x58, 0xA9, 0x01, 0xA9, 0x02, 0x4C, 0x05, 0x80];
+        // sei lets an irq through, the pushed flags have i set
+        let sei = [0x58, 0x78, 0xA9, 0x01, 0x4C, 0x04, 0x80];
+        // plp clearing i only takes effect after the next instruction
+        let plp = [0xA9, 0x00, 0x48, 0x28, 0xA9, 0x01, 0xA9, 0x02, 0x4C, 0x08, 0x80];
+        // rti clearing i takes effect immediately, returns to $8020
+        let mut rti = vec![0xA9, 0x80, 0x48, 0xA9, 0x20, 0x48, 0xA9, 0x20, 0x48, 0x40];
+        rti.resize(0x20, 0xEA);
+        rti.extend_from_slice(&[0xA9, 0x01, 0x4C, 0x22, 0x80]);
+
+        for &fast in [false, true].iter() {
+            assert_eq!(irq_pushed(&cli, fast).0, 0x8003);
+            let (address, p) = irq_pushed(&sei, fast);
+            assert_eq!(address, 0x8002);
+            assert!(p & 0x04 != 0);
+            assert_eq!(irq_pushed(&plp, fast).0, 0x8006);
+            assert_eq!(irq_pushed(&rti, fast).0, 0x8020);
+        }
+    }
+
+    /*
+    Runs cli, nop, brk, jmp * with the given lines pulled low from a cycle counted from the brk
+    opcode fetch, cycle 1. /IRQ is released once an irq has been taken, like a device
+    acknowledged by its handler. Every handler is an rti. Returns the vector, pushed flags and
+    pushed return address of each interrupt sequence in order.
+    */
+    fn interrupts_around_brk(lines: &[(Ctrl, i32)]) -> Vec<(u16, u8, u16)> {
+        let mut bus = Ram::with_program(0x8000, &[0x58, 0xEA, 0x00, 0x00, 0x4C, 0x04, 0x80]);
+        bus.mem[0x9000] = 0x40;
+        bus.mem[0xA000] = 0x40;
+        bus.mem[0xFFFA..].copy_from_slice(&[0x00, 0x90, 0x00, 0x00, 0x00, 0xA0]);
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+        while !pinout.ctrl.contains(Ctrl::SYNC) || pinout.address != 0x8000 {
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+
+        let mut sequences = Vec::new();
+        let mut pushes = Vec::new();
+        let mut irq_taken = false;
+        for cycle in -2..40 {
+            for &(line, low_from) in lines.iter() {
+                let low = cycle >= low_from && !(line == Ctrl::IRQ && irq_taken);
+                pinout.ctrl.set(line, !low);
+            }
+            pinout = cpu.tick(&mut bus, pinout);
+            if !pinout.ctrl.contains(Ctrl::RW) {
+                pushes.push(pinout.data);
+            }
+            if pinout.ctrl.contains(Ctrl::RW) && (pinout.address == 0xFFFA || pinout.address == 0xFFFE) {
+                let pushed = &pushes[pushes.len() - 3..];
+                let p = pushed[2];
+                irq_taken |= pinout.address == 0xFFFE && p & 0x10 == 0;
+                sequences.push((pinout.address, p, (pushed[0] as u16) << 8 | pushed[1] as u16));
+            }
+        }
+        sequences
+    }
+
+    #[test]
+    fn brk_interrupt_timing() {
+        let nmi_first = vec![(0xFFFA, 0x20, 0x8002), (0xFFFE, 0x30, 0x8004)];
+        let nmi_hijacks = vec![(0xFFFA, 0x30, 0x8004)];
+        let nmi_after = vec![(0xFFFE, 0x30, 0x8004), (0xFFFA, 0x20, 0x8004)];
+        let irq_first = vec![(0xFFFE, 0x20, 0x8002), (0xFFFE, 0x30, 0x8004)];
+        let irq_after = vec![(0xFFFE, 0x30, 0x8004), (0xFFFE, 0x20, 0x8004)];
+        let irq_hijacked = vec![(0xFFFA, 0x20, 0x8002), (0xFFFE, 0x20, 0x8002), (0xFFFE, 0x30, 0x8004)];
+        let nmi_after_irq = vec![(0xFFFE, 0x20, 0x8002), (0xFFFA, 0x20, 0x8002), (0xFFFE, 0x30, 0x8004)];
+
+        for cycle in -2..9 {
+            // nmi before the nop's poll is taken first, during the first four cycles of the brk
+            // it takes the vector and the brk is lost, later it follows the brk handler's rti
+            let nmi = match cycle {
+                -2..=-1 => &nmi_first,
+                0..=4 => &nmi_hijacks,
+                _ => &nmi_after,
+            };
+            assert_eq!(&interrupts_around_brk(&[(Ctrl::NMI, cycle)]), nmi, "nmi on cycle {}", cycle);
+
+            // an irq during the brk finds i set and is taken once rti clears it
+            let irq = if cycle < 0 { &irq_first } else { &irq_after };
+            assert_eq!(&interrupts_around_brk(&[(Ctrl::IRQ, cycle)]), irq, "irq on cycle {}", cycle);
+
+            // an irq taken in place of the brk, the irq sequence is hijacked the same way
+            if cycle >= -1 {
+                let both = if cycle <= 4 { &irq_hijacked } else { &nmi_after_irq };
+                assert_eq!(&interrupts_around_brk(&[(Ctrl::IRQ, -1), (Ctrl::NMI, cycle)]), both, "irq and nmi on cycle {}", cycle);
+            }
+        }
+    }
+
+    #[test]
+    fn branch_delays_irq() {
+        let program = [
+            0x58,               // cli
+            0xA9, 0x00,         // lda #$00
+            0xF0, 0x00,         // beq +0
+            0xA9, 0x01,         // lda #$01
+            0x4C, 0x07, 0x80,   // jmp $8007
+        ];
+        let mut bus = Ram::with_program(0x8000, &program);
+        bus.mem[0x9000..0x9003].copy_from_slice(&[0x4C, 0x00, 0x90]);
+        bus.mem[0xFFFE] = 0x00;
+        bus.mem[0xFFFF] = 0x90;
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+
+        while !pinout.ctrl.contains(Ctrl::SYNC) || pinout.address != 0x8003 {
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+        // irq first seen on the operand cycle of a taken branch without a page cross
+        for _ in 0..100 {
+            pinout.ctrl.set(Ctrl::IRQ, false);
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+
+        assert_eq!(bus.mem[0x1FD], 0x80);
+        assert_eq!(bus.mem[0x1FC], 0x07);
+    }
+
+    #[test]
+    fn shared_irq_line() {
+        let mut line = IrqLine::new();
+        line.assert(IrqSource::APU_FRAME);
+        line.assert(IrqSource::MAPPER);
+        line.release(IrqSource::APU_FRAME);
+        assert_eq!(line.asserting(), IrqSource::MAPPER);
+        assert!(!line.drive(Default::default()).ctrl.contains(Ctrl::IRQ));
+
+        line.set(IrqSource::MAPPER, false);
+        assert!(!line.is_asserted());
+        assert!(line.drive(Default::default()).ctrl.contains(Ctrl::IRQ));
+
+        // the line is held by the mapper from the start, the irq is taken once cli has run
+        line.assert(IrqSource::MAPPER);
+        let mut bus = Ram::with_program(0x8000, &[0x58, 0xEA, 0xEA, 0x4C, 0x03, 0x80]);
+        bus.mem[0xFFFE] = 0x00;
+        bus.mem[0xFFFF] = 0x90;
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+        for _ in 0..20 {
+            pinout = cpu.tick(&mut bus, line.drive(pinout));
+        }
+
+        assert!(cpu.debug_log().starts_with("0x9001"));
+    }
+
+    #[test]
+    fn nmi_edge_triggers_once() {
+        for &fast in [false, true].iter() {
+            // nmi handler at $9000 counts into $10
+            let mut bus = Ram::with_program(0x8000, &[0x4C, 0x00, 0x80]);
+            bus.mem[0x9000..0x9003].copy_from_slice(&[0xE6, 0x10, 0x40]);
+            bus.mem[0xFFFA] = 0x00;
+            bus.mem[0xFFFB] = 0x90;
+            let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+            let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+
+            // held low for many instructions, then released and pulled low again
+            for &(nmi_low, steps) in [(true, 200), (false, 1), (true, 200)].iter() {
+                for _ in 0..steps {
+                    pinout.ctrl.set(Ctrl::NMI, !nmi_low);
+                    pinout = if fast { cpu.execute_instruction(&mut bus, pinout) } else { cpu.tick(&mut bus, pinout) };
+                }
+            }
+
+            assert_eq!(bus.mem[0x10], 2);
+        }
+    }
+
+    // pull nmi low on the given cycle of a brk, returns the pushed flags and where the brk went
+    fn brk_with_nmi(nmi_cycle: u32) -> (u8, u16, InterruptState) {
+        let mut bus = Ram::with_program(0x8000, &[0x00, 0x00]);
+        bus.mem[0x9000..0x9003].copy_from_slice(&[0x4C, 0x00, 0x90]);
+        bus.mem[0xA000..0xA003].copy_from_slice(&[0x4C, 0x00, 0xA0]);
+        bus.mem[0xFFFA] = 0x00;
+        bus.mem[0xFFFB] = 0x90;
+        bus.mem[0xFFFE] = 0x00;
+        bus.mem[0xFFFF] = 0xA0;
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+        // run the reset until the brk opcode has been fetched, the next tick is its second cycle
+        while !pinout.ctrl.contains(Ctrl::SYNC) {
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+
+        let mut state = InterruptState::None;
+        for cycle in 2..9 {
+            pinout.ctrl.set(Ctrl::NMI, cycle != nmi_cycle);
+            pinout = cpu.tick(&mut bus, pinout);
+            if cycle == 6 { state = cpu.interrupt_state(); }
+        }
+
+        (bus.mem[0x1FB], pinout.address, state)
+    }
+
+    #[test]
+    fn nmi_hijacks_brk() {
+        // detected by the end of the fourth cycle, the brk jumps through the nmi vector with b set
+        for nmi_cycle in 2..5 {
+            let (p, address, state) = brk_with_nmi(nmi_cycle);
+            assert!(p & 0x10 != 0);
+            assert_eq!(address, 0x9000);
+            assert_eq!(state, InterruptState::BrkHijack);
+        }
+
+        // too late to hijack, the nmi is taken after the first instruction of the brk handler
+        let (p, address, state) = brk_with_nmi(5);
+        assert!(p & 0x10 != 0);
+        assert_eq!(address, 0xA000);
+        assert_eq!(state, InterruptState::None);
+    }
+
+    #[test]
+    fn master_clock_scheduler() {
+        for &(region, ppu_per_frame) in [(Region::Ntsc, 36), (Region::Pal, 39), (Region::Dendy, 36)].iter() {
+            let mut bus = Ram::with_program(0x8000, &[0xEA, 0x4C, 0x00, 0x80]);
+            let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+            let (mut scheduler, cpu_id, ppu_id) = Scheduler::for_region(region);
+            let mut order = Vec::new();
+            let mut ticks = 0;
+
+            // 12 cpu cycles and the ppu dots that fall between them, 3.2 per cpu cycle on pal
+            scheduler.run_for(u64::from(region.cpu_divider()) * 12, |id| {
+                if id == cpu_id { pinout = cpu.tick(&mut bus, pinout); ticks += 1; }
+                order.push(id);
+            });
+
+            assert_eq!(ticks, 12);
+            assert_eq!(scheduler.cycles(cpu_id), 12);
+            assert_eq!(scheduler.cycles(ppu_id), ppu_per_frame);
+            // both are due on master cycle 0, the cpu was added first
+            assert_eq!(&order[..2], &[cpu_id, ppu_id]);
+        }
+
+        // a phase offset delays the first clock, the divider is kept
+        let mut scheduler = Scheduler::new();
+        let a = scheduler.add(12, 0);
+        let b = scheduler.add(12, 5);
+        assert_eq!(scheduler.next_due(), Some(a));
+        assert_eq!(scheduler.next_due(), Some(b));
+        assert_eq!(scheduler.master_cycles(), 5);
+        assert_eq!(scheduler.next_due(), Some(a));
+        assert_eq!(scheduler.master_cycles(), 12);
+    }
+
+    #[test]
+    fn pal_variant() {
+        let (mut pal, _) = rp2a07::Rp2a07::from_power_on();
+        let (ntsc, _) = rp2a03::Rp2a03::from_power_on();
+        assert_eq!(pal.region(), Region::Pal);
+        assert_eq!(ntsc.region(), Region::Ntsc);
+        assert_eq!(pal.apu_timing(), &timing::PAL_APU);
+        assert_eq!(ntsc.apu_timing(), &timing::NTSC_APU);
+        // the dendy keeps the ntsc apu
+        assert_eq!(ApuTiming::for_region(Region::Dendy), &timing::NTSC_APU);
+        assert_eq!(pal.apu_timing().noise_periods[15], 3778);
+        assert_eq!(pal.apu_timing().dmc_rates[0], 398);
+
+        // the core is shared, the 2a07 runs code like the 2a03
+        let mut bus = Ram::with_program(0x8000, &[0xA9, 0x42, 0x85, 0x10, 0x4C, 0x04, 0x80]);
+        let mut pinout = pal.debug_reset(0x8000, &mut bus);
+        for _ in 0..30 {
+            pinout = pal.tick(&mut bus, pinout);
+        }
+        assert_eq!(bus.mem[0x10], 0x42);
+    }
+
+    #[test]
+    fn peek_disassembly() {
+        let program = [0xA9, 0x42, 0x0A, 0x6C, 0x34, 0x12, 0xB1, 0x10, 0xD0, 0xF6, 0x02];
+        let mut ram = bus::Ram::new(0x10000);
+        ram.memory_mut()[0x8000..0x8000 + program.len()].copy_from_slice(&program);
+
+        let lines: Vec<String> = disasm::disassemble(&ram, 0x8000, 6).iter().map(|l| l.to_string()).collect();
+        assert_eq!(lines, ["LDA #$42", "ASL A", "JMP ($1234)", "LDA ($10),Y", "BNE $8000", "*KIL*"]);
+
+        // poke changes memory without a bus cycle, a bus without peek gives nothing
+        assert!(bus::Bus::poke(&mut ram, 0x8001, 0x24));
+        assert_eq!(disasm::disassemble_one(&ram, 0x8000).unwrap().operand, "#$24");
+        let plain = Ram::with_program(0x8000, &program);
+        assert!(disasm::disassemble(&plain, 0x8000, 6).is_empty());
+    }
+
+    #[test]
+    fn open_bus() {
+        // LDA $5000, STA $10, LDA $4016, STA $11, JMP *
+        let mut rom = vec![0; 0x8000];
+        rom[..13].copy_from_slice(&[0xAD, 0x00, 0x50, 0x85, 0x10, 0xAD, 0x16, 0x40, 0x85, 0x11, 0x4C, 0x0A, 0x80]);
+        rom[0x7FFD] = 0x80;
+        let mut map = MemoryMap::builder()
+            .ram(0x0000, 0x07FF, 0x0800)
+            .rom(0x8000, 0xFFFF, rom)
+            // controller port, only the low 5 bits are driven
+            .device(0x4016, 0x4017, |mut p: Pinout| { p.data = 0x01; p }, |p| p)
+            .drive(Drive::Partial(0x1F))
+            .build();
+
+        let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+        for _ in 0..40 {
+            pinout = cpu.tick(&mut map, pinout);
+        }
+
+        // the last byte on the bus was the high byte of the address
+        assert_eq!(bus::Bus::peek(&map, 0x10), Some(0x50));
+        assert_eq!(bus::Bus::peek(&map, 0x11), Some(0x41));
+        assert_eq!(bus::Bus::peek(&map, 0x4016), None);
+
+        // floating bits decay once the bus has gone undriven long enough
+        map.set_decay(Some(2));
+        let mut pinout = Pinout::new();
+        pinout.address = 0x5000;
+        pinout.data = 0x50;
+        let reads: Vec<u8> = (0..3).map(|_| { pinout = bus::Bus::read(&mut map, pinout); pinout.data }).collect();
+        assert_eq!(reads, [0x50, 0x50, 0x00]);
+    }
+
+    #[test]
+    fn memory_map_builder() {
+        let writes = Rc::new(Cell::new(0u16));
+        let seen = writes.clone();
+        let mut map = MemoryMap::builder()
+            .ram(0x0000, 0x1FFF, 0x0800)
+            // 16k of rom mirrored over 32k
+            .rom(0x8000, 0xFFFF, vec![0xEA; 0x4000])
+            // registers repeat every 8 bytes, the handler sees the pinout as the cpu put it out
+            .device(0x2000, 0x3FFF, |mut p: Pinout| { p.data = (p.address & 0x7) as u8; p }, move |p: Pinout| { seen.set(p.address); p })
+            // a hole in the ram, and a higher priority region added before the one it overrides
+            .unmapped(0x1800, 0x18FF)
+            .ram(0x8000, 0x80FF, 0x100).priority(1)
+            .rom(0x8000, 0x8FFF, vec![0x00; 0x1000])
+            .build();
+
+        bus::Bus::poke(&mut map, 0x0001, 0x42);
+        assert_eq!(bus::Bus::peek(&map, 0x0801), Some(0x42));
+        assert_eq!(bus::Bus::peek(&map, 0x1001), Some(0x42));
+        assert_eq!(bus::Bus::peek(&map, 0x1801), None);
+        assert_eq!(bus::Bus::peek(&map, 0xC123), Some(0xEA));
+        assert_eq!(bus::Bus::peek(&map, 0x8010), Some(0x00));
+        assert_eq!(bus::Bus::peek(&map, 0x9000), Some(0xEA));
+
+        let mut pinout = Pinout::new();
+        pinout.address = 0x8010;
+        pinout.data = 0x99;
+        pinout = bus::Bus::write(&mut map, pinout);
+        assert_eq!(bus::Bus::peek(&map, 0x8010), Some(0x99));
+        pinout.address = 0x2FFD;
+        pinout = bus::Bus::read(&mut map, pinout);
+        assert_eq!(pinout.data, 0x05);
+        bus::Bus::write(&mut map, pinout);
+        assert_eq!(writes.get(), 0x2FFD);
+    }
+
+    // ines image with 16k of prg holding program at $8000 and 8k of chr
+    fn ines_image(flags6: u8, program: &[u8]) -> Vec<u8> {
+        let mut image = vec![b'N', b'E', b'S', 0x1A, 1, 1, flags6, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let mut prg = vec![0; 0x4000];
+        prg[..program.len()].copy_from_slice(program);
+        prg[0x3FFD] = 0x80;
+        image.extend_from_slice(&prg);
+        image.extend_from_slice(&[0; 0x2000]);
+        image
+    }
+
+    #[test]
+    fn cartridge_boots() {
+        // LDA #$42, STA $6000, JMP *
+        let image = ines_image(0x03, &[0xA9, 0x42, 0x8D, 0x00, 0x60, 0x4C, 0x05, 0x80]);
+        let cartridge = Cartridge::from_bytes(&image).unwrap();
+        assert_eq!(cartridge.header().mirroring, Mirroring::Vertical);
+        assert!(cartridge.header().battery);
+        assert_eq!(cartridge.header().prg_nvram_size, 0x2000);
+        assert_eq!(cartridge.header().timing, None);
+
+        let mut map = MemoryMap::builder()
+            .ram(0x0000, 0x1FFF, 0x0800)
+            .bus(0x4020, 0xFFFF, cartridge)
+            .build();
+        let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+        for _ in 0..20 {
+            pinout = cpu.tick(&mut map, pinout);
+        }
+        // 16k of prg is mirrored, the reset vector comes from the end of the rom
+        assert_eq!(bus::Bus::peek(&map, 0xC000), Some(0xA9));
+        assert_eq!(bus::Bus::peek(&map, 0x6000), Some(0x42));
+    }
+
+    #[test]
+    fn cartridge_headers() {
+        let image = ines_image(0, &[]);
+        assert!(matches!(Cartridge::from_bytes(&image[..8]), Err(CartridgeError::TooShort)));
+        assert!(matches!(Cartridge::from_bytes(&image[1..]), Err(CartridgeError::BadMagic)));
+        assert!(matches!(Cartridge::from_bytes(&image[..0x1000]), Err(CartridgeError::Truncated { expected: 0x6010, found: 0x1000 })));
+        assert!(matches!(Cartridge::from_bytes(&ines_image(0x40, &[])), Err(CartridgeError::UnsupportedMapper { mapper: 4, submapper: 0 })));
+
+        // nes 2.0, mapper 0x123 submapper 4, prg in exponent notation 2^14 * 3, pal
+        let header = [b'N', b'E', b'S', 0x1A, 0x39, 0x00, 0x32, 0x28, 0x41, 0x0F, 0x07, 0x70, 0x01, 0, 0, 0];
+        let header = Header::parse(&header).unwrap();
+        assert_eq!(header.format, Format::Nes2);
+        assert_eq!((header.mapper, header.submapper), (0x123, 4));
+        assert_eq!(header.prg_rom_size, 0xC000);
+        assert_eq!(header.chr_rom_size, 0);
+        assert_eq!((header.prg_ram_size, header.chr_nvram_size), (0x2000, 0x2000));
+        assert_eq!(header.timing.map(|t| t.region()), Some(Region::Pal));
+        assert_eq!(header.timing, Some(Timing::Pal));
+    }
+
+    // every 16k prg bank and 8k chr bank filled with its number
+    fn banked_image(mapper: u8, flags6: u8, prg_banks: u8, chr_banks: u8) -> Vec<u8> {
+        let mut image = vec![b'N', b'E', b'S', 0x1A, prg_banks, chr_banks, (mapper << 4) | flags6, mapper & 0xF0, 0, 0, 0, 0, 0, 0, 0, 0];
+        for bank in 0..prg_banks {
+            image.extend_from_slice(&[bank; 0x4000]);
+        }
+        for bank in 0..chr_banks {
+            image.extend_from_slice(&[bank; 0x2000]);
+        }
+        image
+    }
+
+    // a cpu write and a cycle elsewhere after it
+    fn cartridge_write(cartridge: &mut Cartridge, address: u16, data: u8) {
+        let mut pinout = Pinout::new();
+        pinout.address = address;
+        pinout.data = data;
+        pinout = bus::Bus::write(cartridge, pinout);
+        pinout = bus::Bus::cycle(cartridge, pinout);
+        bus::Bus::cycle(cartridge, pinout);
+    }
+
+    #[test]
+    fn cartridge_mappers() {
+        // uxrom, the last bank is fixed at $C000
+        let mut uxrom = Cartridge::from_bytes(&banked_image(2, 0, 4, 0)).unwrap();
+        cartridge_write(&mut uxrom, 0x8000, 2);
+        assert_eq!((bus::Bus::peek(&uxrom, 0x8000), bus::Bus::peek(&uxrom, 0xFFFF)), (Some(2), Some(3)));
+
+        // gxrom switches prg and chr together
+        let mut gxrom = Cartridge::from_bytes(&banked_image(66, 0, 4, 4)).unwrap();
+        cartridge_write(&mut gxrom, 0x8000, 0x12);
+        assert_eq!((bus::Bus::peek(&gxrom, 0x8000), gxrom.ppu_read(0x1000)), (Some(2), 2));
+
+        // color dreams has bus conflicts, the rom holds 0x00 so nothing switches
+        let mut dreams = Cartridge::from_bytes(&banked_image(11, 0, 4, 4)).unwrap();
+        cartridge_write(&mut dreams, 0x8000, 0x21);
+        assert_eq!((bus::Bus::peek(&dreams, 0x8000), dreams.ppu_read(0x0000)), (Some(0), 0));
+
+        // axrom picks a single screen
+        let mut axrom = Cartridge::from_bytes(&banked_image(7, 0, 8, 0)).unwrap();
+        cartridge_write(&mut axrom, 0x8000, 0x11);
+        assert_eq!(bus::Bus::peek(&axrom, 0x8000), Some(2));
+        assert_eq!(axrom.mirroring(), Mirroring::SingleScreenUpper);
+
+        // mmc2 flips the chr bank of the left half on a fetch of tile $FE
+        let mut mmc2 = Cartridge::from_bytes(&banked_image(9, 0, 8, 4)).unwrap();
+        cartridge_write(&mut mmc2, 0xB000, 2);
+        cartridge_write(&mut mmc2, 0xC000, 4);
+        assert_eq!(mmc2.ppu_read(0x0000), 2);
+        mmc2.ppu_read(0x0FD8);
+        assert_eq!(mmc2.ppu_read(0x0000), 1);
+        mmc2.ppu_read(0x0FE9);
+        assert_eq!(mmc2.ppu_read(0x0000), 1);
+        mmc2.ppu_read(0x0FE8);
+        assert_eq!(mmc2.ppu_read(0x0000), 2);
+    }
+
+    #[test]
+    fn mmc1_serial_writes() {
+        let mut mmc1 = Cartridge::from_bytes(&banked_image(1, 0x02, 8, 2)).unwrap();
+        assert_eq!(bus::Bus::peek(&mmc1, 0xC000), Some(7));
+
+        // five writes of bit 0 into the prg register
+        for bit in 0..5 {
+            cartridge_write(&mut mmc1, 0xE000, (5 >> bit) & 0x01);
+        }
+        assert_eq!(bus::Bus::peek(&mmc1, 0x8000), Some(5));
+
+        // the second of two writes on consecutive cycles is ignored, like the dummy write of INC
+        let mut pinout = Pinout::new();
+        pinout.address = 0xE000;
+        for &data in [0x80, 0x01].iter() {
+            pinout.data = data;
+            pinout = bus::Bus::write(&mut mmc1, pinout);
+            pinout = bus::Bus::cycle(&mut mmc1, pinout);
+        }
+        bus::Bus::cycle(&mut mmc1, pinout);
+        for bit in 0..5 {
+            cartridge_write(&mut mmc1, 0xE000, (3 >> bit) & 0x01);
+        }
+        assert_eq!(bus::Bus::peek(&mmc1, 0x8000), Some(3));
+
+        // battery backed prg ram goes to and from save files
+        cartridge_write(&mut mmc1, 0x6010, 0x42);
+        assert_eq!(mmc1.save_ram().map(|ram| ram[0x10]), Some(0x42));
+        let mut save = vec![0; 0x2000];
+        save[0x20] = 0x24;
+        mmc1.load_save_ram(&save).unwrap();
+        assert_eq!(bus::Bus::peek(&mmc1, 0x6020), Some(0x24));
+        assert!(matches!(mmc1.load_save_ram(&save[..16]), Err(CartridgeError::SaveSize { expected: 0x2000, found: 16 })));
+    }
+
+    #[test]
+    fn mapper_irq_counters() {
+        // vrc4 in cycle mode, latch $FD overflows on the third cycle and pulls /IRQ low
+        let mut vrc4 = Cartridge::from_bytes(&banked_image(21, 0, 8, 8)).unwrap();
+        cartridge_write(&mut vrc4, 0xF000, 0x0D);
+        cartridge_write(&mut vrc4, 0xF002, 0x0F);
+        cartridge_write(&mut vrc4, 0xF004, 0x06);
+        assert!(!vrc4.irq());
+        let pinout = bus::Bus::cycle(&mut vrc4, Pinout::new());
+        assert!(vrc4.irq());
+        assert!(!pinout.ctrl.contains(Ctrl::IRQ));
+        // acknowledging through the vrc4c wiring of the same mapper number
+        cartridge_write(&mut vrc4, 0xF0C0, 0);
+        assert!(!vrc4.irq());
+
+        // scanline mode, the prescaler runs out after 114 cycles
+        cartridge_write(&mut vrc4, 0xF000, 0x0F);
+        cartridge_write(&mut vrc4, 0xF004, 0x02);
+        for _ in 2..113 {
+            bus::Bus::cycle(&mut vrc4, Pinout::new());
+        }
+        assert!(!vrc4.irq());
+        bus::Bus::cycle(&mut vrc4, Pinout::new());
+        assert!(vrc4.irq());
+
+        // fme-7 counts down from 3 and fires on the wrap to $FFFF
+        let mut fme7 = Cartridge::from_bytes(&banked_image(69, 0, 2, 1)).unwrap();
+        for &(command, parameter) in [(0x0E, 3), (0x0F, 0), (0x0D, 0x81)].iter() {
+            cartridge_write(&mut fme7, 0x8000, command);
+            cartridge_write(&mut fme7, 0xA000, parameter);
+        }
+        bus::Bus::cycle(&mut fme7, Pinout::new());
+        assert!(!fme7.irq());
+        bus::Bus::cycle(&mut fme7, Pinout::new());
+        assert!(fme7.irq());
+
+        // with prg rom at $6000
+        cartridge_write(&mut fme7, 0x8000, 0x08);
+        cartridge_write(&mut fme7, 0xA000, 0x02);
+        assert_eq!(bus::Bus::peek(&fme7, 0x6000), Some(1));
+
+        // n163 counts up to $7FFF, the counter reads back
+        let mut n163 = Cartridge::from_bytes(&banked_image(19, 0, 2, 1)).unwrap();
+        cartridge_write(&mut n163, 0x5000, 0xFD);
+        cartridge_write(&mut n163, 0x5800, 0xFF);
+        assert!(n163.irq());
+        let mut pinout = Pinout::new();
+        pinout.address = 0x5800;
+        assert_eq!(bus::Bus::read(&mut n163, pinout).data, 0xFF);
+        cartridge_write(&mut n163, 0x5800, 0x00);
+        assert!(!n163.irq());
+    }
+
+    #[test]
+    fn mapper_irq_interrupts_cpu() {
+        // the fme-7 counter interrupts a JMP * loop, the handler stores $42 to $00
+        let mut image = banked_image(69, 0, 2, 1);
+        let program = [
+            0xA9, 0x0E, 0x8D, 0x00, 0x80, 0xA9, 0x00, 0x8D, 0x00, 0xA0,
+            0xA9, 0x0F, 0x8D, 0x00, 0x80, 0xA9, 0x01, 0x8D, 0x00, 0xA0,
+            0xA9, 0x0D, 0x8D, 0x00, 0x80, 0xA9, 0x81, 0x8D, 0x00, 0xA0,
+            0x58, 0x4C, 0x1F, 0xE0,
+        ];
+        let handler = [0xA9, 0x42, 0x85, 0x00, 0xA9, 0x00, 0x8D, 0x00, 0xA0, 0x40];
+        let prg = 16 + 0x6000;
+        image[prg..prg + program.len()].copy_from_slice(&program);
+        image[prg + 0x100..prg + 0x100 + handler.len()].copy_from_slice(&handler);
+        image[prg + 0x1FFC..prg + 0x2000].copy_from_slice(&[0x00, 0xE0, 0x00, 0xE1]);
+
+        let mut map = MemoryMap::builder()
+            .ram(0x0000, 0x1FFF, 0x0800)
+            .bus(0x4020, 0xFFFF, Cartridge::from_bytes(&image).unwrap())
+            .build();
+        let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+        for _ in 0..200 {
+            pinout = cpu.tick(&mut map, pinout);
+        }
+        assert_eq!(bus::Bus::peek(&map, 0x0000), Some(0x00));
+        for _ in 0..200 {
+            pinout = cpu.tick(&mut map, pinout);
+        }
+        assert_eq!(bus::Bus::peek(&map, 0x0000), Some(0x42));
+    }
+
+    #[test]
+    fn expansion_audio() {
+        // vrc6 pulse at constant volume 15, mixed over a silent apu
+        let mut vrc6 = Cartridge::from_bytes(&banked_image(24, 0, 8, 8)).unwrap();
+        let apu = Apu::new(Region::Ntsc);
+        assert_eq!(vrc6.audio(), 0.0);
+        assert_eq!(vrc6.mix(&apu), apu.output());
+        cartridge_write(&mut vrc6, 0x9000, 0x8F);
+        cartridge_write(&mut vrc6, 0x9002, 0x80);
+        assert_eq!(vrc6.audio(), 15.0 / 61.0);
+        assert_eq!(vrc6.mix(&apu), apu.output() + 15.0 / 61.0 * apu::EXPANSION_LEVEL);
+
+        // sunsoft 5b, tone a alone toggles every 16 cycles at period 1
+        let mut fme7 = Cartridge::from_bytes(&banked_image(69, 0, 2, 1)).unwrap();
+        for &(register, data) in [(0, 1), (7, 0x3E), (8, 0x0F)].iter() {
+            cartridge_write(&mut fme7, 0xC000, register);
+            cartridge_write(&mut fme7, 0xE000, data);
+        }
+        let mut levels = Vec::new();
+        for _ in 0..64 {
+            bus::Bus::cycle(&mut fme7, Pinout::new());
+            levels.push(fme7.audio());
+        }
+        assert!(levels.contains(&0.0));
+        assert!(levels.contains(&(1.0 / 3.0)));
+
+        // n163, one channel of a wave held at 15 with volume 15
+        let mut n163 = Cartridge::from_bytes(&banked_image(19, 0, 2, 1)).unwrap();
+        cartridge_write(&mut n163, 0xF800, 0x80);
+        for _ in 0..4 {
+            cartridge_write(&mut n163, 0x4800, 0xFF);
+        }
+        cartridge_write(&mut n163, 0xF800, 0x7C);
+        cartridge_write(&mut n163, 0x4800, 0xF8);
+        cartridge_write(&mut n163, 0xF800, 0x7F);
+        cartridge_write(&mut n163, 0x4800, 0x0F);
+        for _ in 0..15 {
+            bus::Bus::cycle(&mut n163, Pinout::new());
+        }
+        assert_eq!(n163.audio(), 1.0);
+    }
+
+    #[test]
+    fn apu_frame_counter() {
+        let mut apu = Apu::new(Region::Ntsc);
+        apu.write(0x4015, 0x01);
+        apu.write(0x4000, 0x10);
+        apu.write(0x4003, 0x08);
+        assert_eq!(apu.peek_status(), 0x01);
+
+        // the four step sequence raises the frame irq at 29828 and holds /IRQ low
+        let mut pinout = Pinout::new();
+        for _ in 0..29827 {
+            pinout = bus::Bus::cycle(&mut apu, Pinout::new());
+        }
+        assert!(pinout.ctrl.contains(Ctrl::IRQ));
+        pinout = bus::Bus::cycle(&mut apu, Pinout::new());
+        assert!(!pinout.ctrl.contains(Ctrl::IRQ));
+        // two half frames took 2 off the length of 20
+        assert_eq!(apu.read_status(), 0x41);
+        assert_eq!(apu.peek_status(), 0x01);
+
+        // the irq inhibit flag clears it
+        bus::Bus::cycle(&mut apu, Pinout::new());
+        assert!(apu.irq());
+        apu.write(0x4017, 0x40);
+        assert!(!apu.irq());
+
+        // the 2A07 steps, its four step irq comes at 33252
+        let pal = ApuTiming::for_region(Region::Pal);
+        assert_eq!(pal.four_step, [8313, 16627, 24939, 33253]);
+        assert_eq!(pal.five_step, [8313, 16627, 24939, 41565]);
+        let mut apu = Apu::new(Region::Pal);
+        let mut pinout = Pinout::new();
+        for _ in 0..33251 {
+            pinout = bus::Bus::cycle(&mut apu, Pinout::new());
+        }
+        assert!(pinout.ctrl.contains(Ctrl::IRQ));
+        pinout = bus::Bus::cycle(&mut apu, Pinout::new());
+        assert!(!pinout.ctrl.contains(Ctrl::IRQ));
+    }
+
+    // an nsf whose init starts pulse 1 at 440hz and whose play counts its calls in $00
+    fn nsf_image() -> Vec<u8> {
+        let mut image = vec![0; 0x80];
+        image[0..5].copy_from_slice(b"NESM\x1A");
+        image[5] = 1;
+        image[6] = 2;
+        image[7] = 1;
+        image[8..14].copy_from_slice(&[0x00, 0x80, 0x00, 0x80, 0x15, 0x80]);
+        image[0x0E..0x12].copy_from_slice(b"Test");
+        image[0x6E..0x70].copy_from_slice(&16639u16.to_le_bytes());
+        image.extend_from_slice(&[
+            0xA9, 0xBF, 0x8D, 0x00, 0x40, 0xA9, 0x00, 0x8D, 0x01, 0x40, 0xA9, 0xFD,
+            0x8D, 0x02, 0x40, 0xA9, 0x00, 0x8D, 0x03, 0x40, 0x60, 0xE6, 0x00, 0x60,
+        ]);
+        image
+    }
+
+    #[test]
+    fn nsf_files() {
+        let nsf = Nsf::from_bytes(&nsf_image()).unwrap();
+        assert_eq!(nsf.format, NsfFormat::Nsf);
+        assert_eq!((nsf.songs, nsf.starting_song), (2, 0));
+        assert_eq!((nsf.load_address, nsf.init_address, nsf.play_address), (0x8000, 0x8000, 0x8015));
+        assert_eq!(nsf.title, "Test");
+        assert_eq!(nsf.banks, None);
+        assert_eq!(nsf.region(), Region::Ntsc);
+        assert_eq!(nsf.play_period(Region::Ntsc), 29780);
+        assert!(matches!(Nsf::from_bytes(&nsf_image()[..0x40]), Err(NsfError::TooShort)));
+        assert!(matches!(NsfPlayer::new(&nsf, 2, None), Err(NsfError::BadTrack { track: 2, songs: 2 })));
+
+        // data below $8000 only loads with bank switching
+        let mut low = nsf_image();
+        low[0x08..0x0A].copy_from_slice(&0x7000u16.to_le_bytes());
+        assert!(matches!(Nsf::from_bytes(&low), Err(NsfError::BadLoadAddress(0x7000))));
+        low[0x70..0x78].copy_from_slice(&[0, 1, 2, 3, 4, 5, 6, 7]);
+        let banked = Nsf::from_bytes(&low).unwrap();
+        assert_eq!(banked.load_address, 0x7000);
+        assert!(NsfPlayer::new(&banked, 0, None).is_ok());
+
+        // the same tune as NSFe, with a track time and bank switching
+        let chunk = |id: &[u8], body: &[u8]| {
+            let mut chunk = (body.len() as u32).to_le_bytes().to_vec();
+            chunk.extend_from_slice(id);
+            chunk.extend_from_slice(body);
+            chunk
+        };
+        let mut image = b"NSFE".to_vec();
+        image.extend(chunk(b"INFO", &[0x00, 0x80, 0x00, 0x80, 0x15, 0x80, 0x01, 0x00, 0x03, 0x01]));
+        image.extend(chunk(b"BANK", &[0, 1, 2, 3, 4, 5, 6, 7]));
+        image.extend(chunk(b"DATA", &nsf_image()[0x80..]));
+        image.extend(chunk(b"auth", b"Title\0Artist\0"));
+        image.extend(chunk(b"time", &[0x10, 0x27, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF]));
+        image.extend(chunk(b"NEND", &[]));
+        let nsfe = Nsf::from_bytes(&image).unwrap();
+        assert_eq!(nsfe.format, NsfFormat::Nsfe);
+        assert_eq!((nsfe.songs, nsfe.starting_song), (3, 1));
+        assert_eq!((nsfe.title.as_str(), nsfe.artist.as_str(), nsfe.copyright.as_str()), ("Title", "Artist", ""));
+        assert_eq!(nsfe.track_times, vec![Some(10000), None]);
+        assert_eq!(nsfe.banks, Some([0, 1, 2, 3, 4, 5, 6, 7]));
+        assert_eq!(nsfe.region(), Region::Pal);
+        assert_eq!(nsfe.data, nsf.data);
+
+        let mut unknown = b"NSFE".to_vec();
+        unknown.extend(chunk(b"ABCD", &[]));
+        assert!(matches!(Nsf::from_bytes(&unknown), Err(NsfError::UnknownChunk(id)) if &id == b"ABCD"));
+        let mut missing = b"NSFE".to_vec();
+        missing.extend(chunk(b"INFO", &[0x00, 0x80, 0x00, 0x80, 0x15, 0x80, 0x00, 0x00]));
+        missing.extend(chunk(b"NEND", &[]));
+        assert!(matches!(Nsf::from_bytes(&missing), Err(NsfError::MissingChunk(id)) if &id == b"DATA"));
+    }
+
+    #[test]
+    fn nsf_render() {
+        // one second at 44100hz, play runs 60 times and the 440hz pulse crosses zero about 396
+        // times once the dc filter has settled after the first 0.1s
+        let nsf = Nsf::from_bytes(&nsf_image()).unwrap();
+        let mut player = NsfPlayer::new(&nsf, 0, None).unwrap();
+        let mut samples = vec![0.0; 44100];
+        player.render(44100, &mut samples);
+        assert_eq!(player.peek(0x0000), Some(60));
+        let crossings = samples[4410..].windows(2).filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0).count();
+        assert!((394..=398).contains(&crossings));
+
+        let mut wav = Vec::new();
+        nsf::write_wav(&mut wav, 44100, &samples).unwrap();
+        assert_eq!(wav.len(), 44 + 44100 * 2);
+        assert_eq!(&wav[0..4], b"RIFF");
+    }
+
+    #[test]
+    fn nsf_expansion_audio() {
+        // init starts a 50% vrc6 pulse at period $1FF before the 2A03 init
+        let mut image = nsf_image();
+        image[0x0A..0x0C].copy_from_slice(&0x8018u16.to_le_bytes());
+        image.extend_from_slice(&[
+            0xA9, 0x7F, 0x8D, 0x00, 0x90, 0xA9, 0xFF, 0x8D, 0x01, 0x90,
+            0xA9, 0x81, 0x8D, 0x02, 0x90, 0x4C, 0x00, 0x80,
+        ]);
+        let render = |image: &[u8]| {
+            let mut player = NsfPlayer::new(&Nsf::from_bytes(image).unwrap(), 0, None).unwrap();
+            let mut samples = vec![0.0; 4410];
+            player.render(44100, &mut samples);
+            samples
+        };
+
+        // without the vrc6 bit its writes go nowhere and only the 2A03 is heard
+        let plain = render(&image);
+        image[0x7B] = 0x01;
+        let vrc6 = render(&image);
+        assert!(vrc6 != plain);
+        assert!(vrc6.iter().zip(plain.iter()).any(|(vrc6, plain)| (vrc6 - plain).abs() > 0.01));
+    }
+
+    #[test]
+    fn program_loaders() {
+        // LDA #$42, STA $10, JMP * at $0200 in two records, started at $0200
+        let hex = ":04020000A94285107A\n:030204004C0402A5\n:0400000500000200F5\n:00000001FF\n";
+        let program = Program::from_intel_hex(hex).unwrap();
+        assert_eq!(program.segments, vec![Segment { address: 0x0200, data: vec![0xA9, 0x42, 0x85, 0x10, 0x4C, 0x04, 0x02] }]);
+        assert_eq!(program.start, Some(0x0200));
+        assert!(matches!(Program::from_intel_hex(&hex.replace("7A", "7B")), Err(LoadError::Checksum { line: 1, expected: 0x7A, found: 0x7B })));
+        assert!(matches!(Program::from_intel_hex(":04020000A94285107A\n"), Err(LoadError::MissingEnd)));
+        // a linear base of $FFFF0000 puts the record at the top of the u32 range
+        let far = ":02000004FFFFFC\n:10FFF0000000000000000000000000000000000001\n:00000001FF\n";
+        assert!(matches!(Program::from_intel_hex(far), Err(LoadError::AddressRange { line: 2, address: 0xFFFFFFFF })));
+
+        let srec = Program::from_srecord("S00600006D6F73AA\nS10A0200A94285104C040221\nS9030200FA\n").unwrap();
+        assert_eq!(srec, program);
+        assert!(matches!(Program::from_srecord("S10A0200A94285104C040220\n"), Err(LoadError::Checksum { line: 1, .. })));
+
+        let prg = Program::from_prg(&[0x00, 0x02, 0xA9, 0x42, 0x85, 0x10, 0x4C, 0x04, 0x02]).unwrap();
+        assert_eq!((prg.segments, prg.start), (program.segments.clone(), None));
+        assert!(matches!(Program::from_raw(0xFFFF, &[0, 0]), Err(LoadError::AddressRange { line: 0, address: 0x10000 })));
+
+        // through the reset vector, the test bus has no poke and takes plain writes
+        let mut ram = Ram::with_program(0x0000, &[]);
+        program.write_to(&mut ram);
+        program.set_reset_vector(&mut ram).unwrap();
+        let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+        for _ in 0..20 {
+            pinout = cpu.tick(&mut ram, pinout);
+        }
+        assert_eq!(FastBus::read(&mut ram, 0x0010), 0x42);
+
+        // straight into pc, without a reset
+        let mut ram = bus::Ram::new(0x10000);
+        program.write_to(&mut ram);
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = program.jump(&mut cpu, &mut ram).unwrap();
+        for _ in 0..5 {
+            pinout = cpu.tick(&mut ram, pinout);
+        }
+        assert_eq!(bus::Bus::peek(&ram, 0x0010), Some(0x42));
+        assert_eq!(bus::Bus::peek(&ram, 0xFFFC), Some(0x00));
+
+        // segments ending at $FFFF, the vectors and the reset vector on top of them
+        let vectors = Program::from_raw(0xFFFA, &[0x00, 0x03, 0x00, 0x04, 0x00, 0x05]).unwrap();
+        vectors.write_to(&mut ram);
+        assert_eq!(bus::Bus::peek(&ram, 0xFFFF), Some(0x05));
+        program.set_reset_vector(&mut ram).unwrap();
+        assert_eq!((bus::Bus::peek(&ram, 0xFFFC), bus::Bus::peek(&ram, 0xFFFD)), (Some(0x00), Some(0x02)));
+        assert_eq!(bus::Bus::peek(&ram, 0xFFFE), Some(0x00));
+    }
+
+    #[test]
+    fn symbol_import() {
+        // two segments in the first two 16k banks of an ines image, both assembled for $8000
+        let dbg = "version\tmajor=2,minor=0\n\
+            file\tid=0,name=\"main.s\",size=100,mtime=0x5F000000,mod=0\n\
+            seg\tid=0,name=\"CODE\",start=0x008000,size=0x0010,addrsize=absolute,type=ro,oname=\"game.nes\",ooffs=16\n\
+            seg\tid=1,name=\"BANK1\",start=0x008000,size=0x0010,addrsize=absolute,type=ro,oname=\"game.nes\",ooffs=16400\n\
+            span\tid=0,seg=0,start=0,size=3\n\
+            span\tid=1,seg=0,start=3,size=2\n\
+            span\tid=2,seg=1,start=0,size=3\n\
+            line\tid=0,file=0,line=10,span=0\n\
+            line\tid=1,file=0,line=11,span=1\n\
+            line\tid=2,file=0,line=20,span=2\n\
+            sym\tid=0,name=\"reset\",addrsize=absolute,size=5,scope=0,def=0,val=0x8000,seg=0,type=lab\n\
+            sym\tid=1,name=\"other\",addrsize=absolute,scope=0,def=0,val=0x8000,seg=1,type=lab\n\
+            sym\tid=2,name=\"PPUCTRL\",addrsize=absolute,scope=0,def=0,val=0x2000,type=equ\n";
+        let mut symbols = SymbolTable::new();
+        symbols.import_ca65_dbg(dbg).unwrap();
+        assert_eq!(symbols.lookup(0x8003, Some(0)), Some(("reset", 3)));
+        assert_eq!(symbols.lookup(0x8005, Some(0)), None);
+        // bank 1 mapped at $C000
+        assert_eq!(symbols.lookup(0xC000, Some(1)), Some(("other", 0)));
+        assert_eq!(symbols.source(0x8003, Some(0)), Some(("main.s", 11)));
+        assert_eq!(symbols.describe(0xC001, Some(1)), "other+1 main.s:20");
+        assert_eq!(symbols.format_address(0x2000, None), "PPUCTRL");
+
+        // vice, fceux and mesen labels
+        symbols.import_vice("al C:0810 .loop\nbreak 1000\n").unwrap();
+        assert_eq!(symbols.label(0x0812, None), Some("loop+2".to_string()));
+        symbols.import_fceux_nl("$0200/100#oam#\n$C000#nmi#vblank\n", Some(3)).unwrap();
+        assert_eq!(symbols.label(0x0204, None), Some("oam+4".to_string()));
+        assert_eq!(symbols.lookup(0x8000, Some(3)), Some(("nmi", 0)));
+        symbols.import_mesen_mlb("NesPrgRom:4010:irq:\nNesInternalRam:0010:ptr\nNesWorkRam:0000-0001:save\nNesChrRom:0000:tiles\n").unwrap();
+        assert_eq!(symbols.label(0xC010, Some(1)), Some("irq".to_string()));
+        assert_eq!(symbols.label(0x6001, None), Some("save+1".to_string()));
+        assert_eq!(symbols.label(0x6002, None), None);
+        assert!(matches!(symbols.import_fceux_nl("C000#x#\n", None), Err(SymbolError::Syntax { line: 1 })));
+    }
+
+    #[test]
+    fn symbolic_output() {
+        // main: JSR sub, STA $2000, BNE main+3, sub: LDA $10, RTS
+        let mut ram = bus::Ram::new(0x10000);
+        ram.memory_mut()[0x8000..0x800B].copy_from_slice(&[0x20, 0x08, 0x80, 0x8D, 0x00, 0x20, 0xD0, 0xFB, 0xA5, 0x10, 0x60]);
+        // a line for every instruction but the bne
+        let dbg = "file\tid=0,name=\"main.s\",size=100,mtime=0x5F000000,mod=0\n\
+            seg\tid=0,name=\"CODE\",start=0x008000,size=0x000B,addrsize=absolute,type=ro\n\
+            span\tid=0,seg=0,start=0,size=3\n\
+            span\tid=1,seg=0,start=3,size=3\n\
+            span\tid=2,seg=0,start=8,size=2\n\
+            span\tid=3,seg=0,start=10,size=1\n\
+            line\tid=0,file=0,line=3,span=0\n\
+            line\tid=1,file=0,line=4,span=1\n\
+            line\tid=2,file=0,line=7,span=2\n\
+            line\tid=3,file=0,line=8,span=3\n\
+            sym\tid=0,name=\"main\",addrsize=absolute,size=8,scope=0,def=0,val=0x8000,seg=0,type=lab\n\
+            sym\tid=1,name=\"sub\",addrsize=absolute,scope=0,def=0,val=0x8008,seg=0,type=lab\n\
+            sym\tid=2,name=\"PPUCTRL\",addrsize=absolute,scope=0,def=0,val=0x2000,type=equ\n";
+        let mut symbols = SymbolTable::new();
+        symbols.import_ca65_dbg(dbg).unwrap();
+        symbols.add_label("ptr", 0x0010, None);
+
+        let lines: Vec<String> = disasm::disassemble_symbols(&ram, 0x8000, 5, &symbols, None).iter().map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "JSR sub ; main main.s:3",
+            "STA PPUCTRL ; main+3 main.s:4",
+            "BNE main+3 ; main+6",
+            "LDA ptr ; sub main.s:7",
+            "RTS ; sub+2 main.s:8",
+        ]);
+        assert_eq!(disasm::disassemble(&ram, 0x8000, 1)[0].to_string(), "JSR $8008");
+
+        // inside sub the trace names it and the call stack holds the JSR in main
+        ram.memory_mut()[0xFFFD] = 0x80;
+        let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+        while cpu.pc() != 0x8009 && cpu.cycle_count() < 50 {
+            pinout = cpu.tick(&mut ram, pinout);
+        }
+        assert!(cpu.debug_log_symbols(&symbols, None).ends_with(" sub main.s:7"));
+        assert_eq!(cpu.call_stack(), vec![0x8000]);
+        assert_eq!(cpu.debug_call_stack(&symbols, None), "$8000 main main.s:3");
+    }
+
+    #[test]
+    fn call_stack_ignores_pushed_data() {
+        let program = [
+            0xA9, 0x80,         // lda #$80
+            0x48,               // pha
+            0xA9, 0x08,         // lda #$08
+            0x48,               // pha, $8008 on the stack looks like a return from the jsr below
+            0x20, 0x0C, 0x80,   // jsr sub
+            0x4C, 0x09, 0x80,   // jmp $8009
+            0x20, 0x10, 0x80,   // sub: jsr inner
+            0x60,               // rts
+            0xEA,               // inner: nop
+            0x60,               // rts
+        ];
+        for &fast in [false, true].iter() {
+            let mut bus = Ram::with_program(0x8000, &program);
+            let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+            let mut pinout = cpu.debug_reset(0x8000, &mut bus);
+            let mut step = |cpu: &mut rp2a03::Rp2a03, count| {
+                for _ in 0..count {
+                    if fast {
+                        pinout = cpu.execute_instruction(&mut bus, pinout);
+                        continue;
+                    }
+                    pinout = cpu.tick(&mut bus, pinout);
+                    while !pinout.ctrl.contains(Ctrl::SYNC) {
+                        pinout = cpu.tick(&mut bus, pinout);
+                    }
+                }
+            };
+
+            // the reset sequence to the second jsr
+            step(&mut cpu, 7);
+            assert_eq!(cpu.pc(), 0x8011);
+            assert_eq!(cpu.call_stack(), vec![0x800C, 0x8006]);
+            step(&mut cpu, 2);
+            assert_eq!(cpu.call_stack(), vec![0x8006]);
+            step(&mut cpu, 1);
+            assert!(cpu.call_stack().is_empty());
+        }
+    }
+
+    #[test]
+    fn assembler() {
+        let assembly = crate::mos_asm!(
+            ".org $8000",
+            "result = $20",
+            "start:  ldx #3",
+            "loop:   lda table-1,x   ; forward, so absolute",
+            "        sta $10,x",
+            "        dex",
+            "        bne loop",
+            "        jmp (vector)",
+            "table:  .byte 'a', \"bc\"",
+            "vector: .word done",
+            "done:   lax $11",
+            "        stx result",
+            "halt:   jmp halt",
+        );
+        assert_eq!(assembly.program.start, Some(0x8000));
+        assert_eq!(assembly.program.segments, vec![Segment { address: 0x8000, data: vec![
+            0xA2, 0x03, 0xBD, 0x0C, 0x80, 0x95, 0x10, 0xCA, 0xD0, 0xF8, 0x6C, 0x10, 0x80,
+            0x61, 0x62, 0x63, 0x12, 0x80, 0xA7, 0x11, 0x86, 0x20, 0x4C, 0x16, 0x80,
+        ] }]);
+        assert_eq!(assembly.label("halt"), Some(0x8016));
+        assert_eq!(assembly.label("result"), None);
+        assert_eq!(assembly.symbols.describe(0x8014, None), "done+2 <source>:12");
+
+        let mut ram = bus::Ram::new(0x10000);
+        assembly.program.write_to(&mut ram);
+        let (mut cpu, _) = rp2a03::Rp2a03::from_power_on();
+        let mut pinout = cpu.debug_reset(0x8000, &mut ram);
+        while cpu.cycle_count() < 200 {
+            pinout = cpu.tick(&mut ram, pinout);
+        }
+        assert_eq!(&ram.memory()[0x11..0x14], b"abc");
+        assert_eq!(ram.memory()[0x20], b'a');
+    }
+
+    #[test]
+    fn assembler_errors() {
+        assert!(matches!(asm::assemble("lda #1\nfoo"), Err(AsmError::UnknownMnemonic { line: 2, .. })));
+        assert!(matches!(asm::assemble(".org $8000\nbne $9000"), Err(AsmError::BranchRange { line: 2, target: 0x9000 })));
+        assert!(matches!(asm::assemble("jmp nowhere"), Err(AsmError::Undefined { line: 1, .. })));
+        assert!(matches!(asm::assemble("lda #$100"), Err(AsmError::ValueRange { line: 1, value: 0x100 })));
+        assert!(matches!(asm::assemble("stx $10,x"), Err(AsmError::AddressingMode { line: 1 })));
+        // negating the most negative value wraps like the binary operators
+        assert_eq!(asm::assemble(".byte <-(-9223372036854775807 - 1)").unwrap().program.segments[0].data, vec![0]);
+
+        let dir = std::env::temp_dir().join(format!("mos_asm_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.s"), ".org $0300\n.include \"lib.s\"\n.word value").unwrap();
+        std::fs::write(dir.join("lib.s"), "value = 7\nlabel: .byte value\n").unwrap();
+        std::fs::write(dir.join("bad.s"), "nop\n.include \"lib.s\"\nbogus\n").unwrap();
+        let assembly = asm::assemble_file(dir.join("main.s")).unwrap();
+        assert_eq!(assembly.label("label"), Some(0x0300));
+        assert_eq!(assembly.program.segments[0].data, vec![7, 7, 0]);
+        match asm::assemble_file(dir.join("bad.s")) {
+            Err(AsmError::UnknownMnemonic { line: 3, .. }) => {}
+            other => panic!("{:?}", other),
+        }
+        std::fs::write(dir.join("main.s"), ".include \"bad.s\"").unwrap();
+        match asm::assemble_file(dir.join("main.s")) {
+            Err(AsmError::Included { file, err }) => {
+                assert!(file.ends_with("bad.s"));
+                assert!(matches!(*err, AsmError::UnknownMnemonic { line: 3, .. }));
+            }
+            other => panic!("{:?}", other),
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn code_data_log() {
+        let assembly = crate::mos_asm!(
+            ".org $8000",
+            "reset:  lda #<table",
+            "        sta $00",
+            "        lda #>table",
+            "        sta $01",
+            "        ldy #1",
+            "        lda ($00),y",
+            "        inc data",
+            "        jmp (vector)",
+            "vector: .word done",
+            "table:  .byte 1, 2",
+            "data:   .byte 3",
+            "unused: .byte 4",
+            "done:   jmp done",
+        );
+        let cartridge = Cartridge::from_bytes(&ines_image(0, &assembly.program.segments[0].data)).unwrap();
+        let log = CodeDataLog::for_cartridge(&cartridge);
+        let map = MemoryMap::builder()
+            .ram(0x0000, 0x1FFF, 0x0800)
+            .bus(0x4020, 0xFFFF, cartridge)
+            .build();
+        let mut bus = CdlBus::new(map, log);
+        let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+        for _ in 0..100 {
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+
+        let log = bus.log();
+        let flags = |label: &str| log.prg()[(assembly.label(label).unwrap() - 0x8000) as usize];
+        assert_eq!(flags("reset"), cdl::CODE);
+        assert_eq!(log.prg()[1], cdl::CODE);
+        assert_eq!(flags("vector"), cdl::DATA);
+        assert_eq!(flags("table"), 0);
+        assert_eq!(log.prg()[(assembly.label("table").unwrap() - 0x7FFF) as usize], cdl::DATA | cdl::INDIRECT_DATA);
+        assert_eq!(flags("data"), cdl::DATA);
+        assert_eq!(flags("unused"), 0);
+        assert_eq!(flags("done"), cdl::CODE | cdl::INDIRECT_CODE);
+        // the reset vector is read through the $E000 window
+        assert_eq!(log.prg()[0x3FFC], cdl::DATA | 0x0C);
+        assert_eq!(log.prg()[0x3FFE], 0);
+
+        let mut file = Vec::new();
+        log.write_to(&mut file).unwrap();
+        assert_eq!(file.len(), 0x4000 + 0x2000);
+    }
+
+    #[test]
+    fn profiler() {
+        let assembly = crate::mos_asm!(
+            ".org $8000",
+            "reset:  ldx #3",
+            "loop:   jsr outer",
+            "        dex",
+            "        bne loop",
+            "        brk",
+            "        nop",
+            "done:   jmp done",
+            "outer:  jsr inner",
+            "        rts",
+            "inner:  nop",
+            "        rts",
+            "irq:    rti",
+            ".org $FFFC",
+            ".word reset, irq",
+        );
+        let mut ram = bus::Ram::new(0x10000);
+        assembly.program.write_to(&mut ram);
+        let mut bus = ProfilerBus::new(ram);
+        let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+        for _ in 0..200 {
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+
+        let profiler = bus.profiler();
+        let label = |name: &str| assembly.label(name).unwrap();
+        let outer = profiler.subroutine(label("outer")).unwrap();
+        let inner = profiler.subroutine(label("inner")).unwrap();
+        // the last cycle of jsr, nop and rts up to pulling the return address
+        assert_eq!((inner.calls, inner.exclusive, inner.inclusive), (3, 3 * 7, 3 * 7));
+        assert_eq!(outer.calls, 3);
+        assert_eq!(outer.inclusive, outer.exclusive + inner.inclusive);
+        assert_eq!(profiler.subroutine(label("reset")).unwrap().inclusive, profiler.cycles() - 7);
+        assert_eq!(profiler.interrupts(Entry::Brk).count, 1);
+        assert_eq!(profiler.interrupts(Entry::Brk).cycles, 6);
+        assert_eq!(profiler.call_stack(), vec![label("reset")]);
+
+        let hottest = profiler.hot_spots()[0];
+        assert_eq!(hottest.address, label("done"));
+        // three cycles each, the last one may not have finished
+        assert_eq!(hottest.cycles.div_ceil(3), hottest.executions);
+
+        let mut folded = Vec::new();
+        profiler.write_folded(&mut folded, Some(&assembly.symbols)).unwrap();
+        let folded = String::from_utf8(folded).unwrap();
+        assert!(folded.lines().any(|line| line == "reset;outer;inner 21"));
+        assert!(folded.lines().any(|line| line == "reset;irq 6"));
+    }
+
+    #[test]
+    fn profiler_vectors() {
+        let assembly = crate::mos_asm!(
+            ".org $8000",
+            "reset:  jsr sub",
+            "        cli",
+            "        nop",
+            "opcode: brk",
+            "        nop",
+            "done:   jmp done",
+            "sub:    lda $FFFD",
+            "        lda $FFFB",
+            "        lda $FFFF",
+            "        rts",
+            "irq:    rti",
+            ".org $FFFA",
+            ".word irq, reset, irq",
+        );
+        let label = |name: &str| assembly.label(name).unwrap();
+        let mut ram = bus::Ram::new(0x10000);
+        assembly.program.write_to(&mut ram);
+        let mut bus = ProfilerBus::new(ram);
+        let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+        // irq held until its handler runs, it is taken in place of the brk
+        let mut irq = true;
+        while !pinout.ctrl.contains(Ctrl::SYNC) || pinout.address != label("done") {
+            irq &= !pinout.ctrl.contains(Ctrl::SYNC) || pinout.address != label("irq");
+            pinout.ctrl.set(Ctrl::IRQ, !irq);
+            pinout = cpu.tick(&mut bus, pinout);
+        }
+
+        let profiler = bus.profiler();
+        // reading the vectors is not a reset or an interrupt
+        assert_eq!(profiler.subroutine(label("sub")).unwrap().calls, 1);
+        assert_eq!(profiler.interrupts(Entry::Reset).count, 1);
+        assert_eq!(profiler.interrupts(Entry::Nmi), InterruptProfile::default());
+        // the brk opcode fetched before the irq is fetched again after its rti
+        assert_eq!(profiler.interrupts(Entry::Irq).count, 1);
+        assert_eq!(profiler.interrupts(Entry::Brk).count, 1);
+        let brk = profiler.hot_spots().into_iter().find(|spot| spot.address == label("opcode")).unwrap();
+        assert_eq!(brk.executions, 1);
+        assert_eq!(profiler.call_stack(), vec![label("reset")]);
+    }
+
+    #[test]
+    fn execution_stats() {
+        let assembly = crate::mos_asm!(
+            ".org $8000",
+            "reset:  ldx #16",
+            "loop:   lda $80F8,x",
+            "        sta $0200,x",
+            "        inc $10",
+            "        dex",
+            "        bne loop",
+            "        brk",
+            "        nop",
+            "done:   jmp done",
+            "irq:    rti",
+            ".org $FFFC",
+            ".word reset, irq",
+        );
+        let done = assembly.label("done").unwrap();
+
+        let mut ram = bus::Ram::new(0x10000);
+        assembly.program.write_to(&mut ram);
+        let (mut cpu, mut pinout) = rp2a03::Rp2a03::from_power_on();
+        while !pinout.ctrl.contains(Ctrl::SYNC) || pinout.address != done {
+            pinout = cpu.tick(&mut ram, pinout);
+        }
+
+        let stats = cpu.stats();
+        // the nop is the byte brk skips
+        assert_eq!(stats.instructions(), 1 + 16 * 5 + 2);
+        assert_eq!(stats.executions(0xBD), 16);
+        assert_eq!(stats.executions_by_mode(AddressingMode::AbsoluteX), 32);
+        // $80F8,x is on the next page down to x = 8
+        assert_eq!(stats.page_cross_cycles(), 9);
+        assert_eq!(stats.branch(0xD0), BranchStats { taken: 15, not_taken: 1 });
+        assert_eq!(stats.branches().taken_ratio(), 15.0 / 16.0);
+        assert_eq!(stats.interrupts(), InterruptStats { nmi: 0, irq: 0, brk: 1, reset: 1 });
+        // crossed lda, sta, dex, taken bne, brk and rti
+        assert_eq!(stats.dummy_reads(), 9 + 16 + 16 + 15 + 1 + 2);
+        assert_eq!(stats.dummy_writes(), 16);
+        assert!(stats.undocumented().is_empty());
+
+        // the instruction level path counts the same
+        let mut fast_ram = bus::Ram::new(0x10000);
+        assembly.program.write_to(&mut fast_ram);
+        let (mut fast_cpu, mut fast_pinout) = rp2a03::Rp2a03::from_power_on();
+        while !fast_pinout.ctrl.contains(Ctrl::SYNC) || fast_pinout.address != done {
+            fast_pinout = fast_cpu.execute_instruction(&mut fast_ram, fast_pinout);
+        }
+        assert_eq!(fast_cpu.stats(), cpu.stats());
+
+        cpu.reset_stats();
+        assert_eq!(cpu.stats().instructions(), 0);
+    }
 }
@@ -0,0 +1,668 @@
+use super::{Ctrl, Pinout};
+use super::bus::Bus;
+use super::clock::Region;
+use super::timing::{ApuTiming, LENGTH_TABLE};
+
+/*
+2A03 apu
+
+Two pulse channels, a triangle, a noise channel and the delta modulation channel, clocked
+once per cpu cycle from Bus::cycle and answering $4000-$4013, $4015 and $4017. The pulse
+timers run at half the cpu clock, everything else at the cpu clock. The frame sequencer
+steps come from the region's ApuTiming.
+
+The dmc fetches its sample bytes with dma, which needs the memory the apu sits beside. The
+owner of both services it, dmc_request gives the address the dmc wants and dmc_fill hands it
+the byte. The cpu cycles stolen by the fetch are not emulated.
+
+output is the nonlinear mix of the channels from 0.0 to about 1.0, mix adds the expansion
+audio of a cartridge at full scale as loud as both pulses at full volume.
+*/
+
+// expansion audio at full scale, about two pulses at full volume
+pub const EXPANSION_LEVEL: f32 = 0.25;
+
+const DUTY: [[u8; 8]; 4] = [
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 1, 1, 0, 0, 0, 0, 0],
+    [0, 1, 1, 1, 1, 0, 0, 0],
+    [1, 0, 0, 1, 1, 1, 1, 1],
+];
+
+const TRIANGLE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+];
+
+struct Envelope {
+    start: bool,
+    looping: bool,
+    constant: bool,
+    // constant volume or the divider period
+    volume: u8,
+    divider: u8,
+    decay: u8,
+}
+
+impl Envelope {
+    fn new() -> Envelope {
+        Envelope { start: false, looping: false, constant: false, volume: 0, divider: 0, decay: 0 }
+    }
+
+    fn write(&mut self, data: u8) {
+        self.looping = data & 0x20 != 0;
+        self.constant = data & 0x10 != 0;
+        self.volume = data & 0x0F;
+    }
+
+    // quarter frame
+    fn clock(&mut self) {
+        if self.start {
+            self.start = false;
+            self.decay = 15;
+            self.divider = self.volume;
+        }
+        else if self.divider == 0 {
+            self.divider = self.volume;
+            if self.decay > 0 {
+                self.decay -= 1;
+            }
+            else if self.looping {
+                self.decay = 15;
+            }
+        }
+        else {
+            self.divider -= 1;
+        }
+    }
+
+    fn output(&self) -> u8 {
+        if self.constant { self.volume } else { self.decay }
+    }
+}
+
+struct Length {
+    enabled: bool,
+    halt: bool,
+    counter: u8,
+}
+
+impl Length {
+    fn new() -> Length {
+        Length { enabled: false, halt: false, counter: 0 }
+    }
+
+    fn load(&mut self, data: u8) {
+        if self.enabled {
+            self.counter = LENGTH_TABLE[(data >> 3) as usize];
+        }
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.counter = 0;
+        }
+    }
+
+    // half frame
+    fn clock(&mut self) {
+        if self.counter > 0 && !self.halt {
+            self.counter -= 1;
+        }
+    }
+}
+
+struct Pulse {
+    // pulse 1 negates its sweep with ones complement
+    ones_complement: bool,
+    duty: u8,
+    step: u8,
+    period: u16,
+    timer: u16,
+    envelope: Envelope,
+    length: Length,
+    sweep_enabled: bool,
+    sweep_period: u8,
+    sweep_negate: bool,
+    sweep_shift: u8,
+    sweep_divider: u8,
+    sweep_reload: bool,
+}
+
+impl Pulse {
+    fn new(ones_complement: bool) -> Pulse {
+        Pulse {
+            ones_complement,
+            duty: 0,
+            step: 0,
+            period: 0,
+            timer: 0,
+            envelope: Envelope::new(),
+            length: Length::new(),
+            sweep_enabled: false,
+            sweep_period: 0,
+            sweep_negate: false,
+            sweep_shift: 0,
+            sweep_divider: 0,
+            sweep_reload: false,
+        }
+    }
+
+    fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.duty = data >> 6;
+                self.length.halt = data & 0x20 != 0;
+                self.envelope.write(data);
+            }
+            1 => {
+                self.sweep_enabled = data & 0x80 != 0;
+                self.sweep_period = (data >> 4) & 0x07;
+                self.sweep_negate = data & 0x08 != 0;
+                self.sweep_shift = data & 0x07;
+                self.sweep_reload = true;
+            }
+            2 => self.period = (self.period & 0x0700) | data as u16,
+            _ => {
+                self.period = (self.period & 0x00FF) | ((data & 0x07) as u16) << 8;
+                self.length.load(data);
+                self.step = 0;
+                self.envelope.start = true;
+            }
+        }
+    }
+
+    fn sweep_target(&self) -> u16 {
+        let change = self.period >> self.sweep_shift;
+        if self.sweep_negate {
+            let extra = if self.ones_complement { 1 } else { 0 };
+            self.period.saturating_sub(change + extra)
+        }
+        else {
+            self.period + change
+        }
+    }
+
+    // the sweep unit mutes the channel even while disabled
+    fn muted(&self) -> bool {
+        self.period < 8 || self.sweep_target() > 0x7FF
+    }
+
+    fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.period;
+            self.step = (self.step + 1) & 0x07;
+        }
+        else {
+            self.timer -= 1;
+        }
+    }
+
+    // half frame
+    fn clock_sweep(&mut self) {
+        if self.sweep_divider == 0 && self.sweep_enabled && self.sweep_shift > 0 && !self.muted() {
+            self.period = self.sweep_target();
+        }
+        if self.sweep_divider == 0 || self.sweep_reload {
+            self.sweep_divider = self.sweep_period;
+            self.sweep_reload = false;
+        }
+        else {
+            self.sweep_divider -= 1;
+        }
+    }
+
+    fn output(&self) -> u8 {
+        if self.length.counter == 0 || self.muted() || DUTY[self.duty as usize][self.step as usize] == 0 {
+            return 0;
+        }
+        self.envelope.output()
+    }
+}
+
+struct Triangle {
+    period: u16,
+    timer: u16,
+    step: u8,
+    length: Length,
+    linear: u8,
+    linear_reload: u8,
+    reload: bool,
+}
+
+impl Triangle {
+    fn new() -> Triangle {
+        Triangle { period: 0, timer: 0, step: 0, length: Length::new(), linear: 0, linear_reload: 0, reload: false }
+    }
+
+    fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.length.halt = data & 0x80 != 0;
+                self.linear_reload = data & 0x7F;
+            }
+            1 => {}
+            2 => self.period = (self.period & 0x0700) | data as u16,
+            _ => {
+                self.period = (self.period & 0x00FF) | ((data & 0x07) as u16) << 8;
+                self.length.load(data);
+                self.reload = true;
+            }
+        }
+    }
+
+    fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.period;
+            if self.length.counter > 0 && self.linear > 0 {
+                self.step = (self.step + 1) & 0x1F;
+            }
+        }
+        else {
+            self.timer -= 1;
+        }
+    }
+
+    // quarter frame, the control flag doubles as the length counter halt
+    fn clock_linear(&mut self) {
+        if self.reload {
+            self.linear = self.linear_reload;
+        }
+        else if self.linear > 0 {
+            self.linear -= 1;
+        }
+        if !self.length.halt {
+            self.reload = false;
+        }
+    }
+
+    // a stopped triangle holds its last step
+    fn output(&self) -> u8 {
+        TRIANGLE[self.step as usize]
+    }
+}
+
+struct Noise {
+    periods: &'static [u16; 16],
+    period: u16,
+    timer: u16,
+    short_mode: bool,
+    shift: u16,
+    envelope: Envelope,
+    length: Length,
+}
+
+impl Noise {
+    fn new(periods: &'static [u16; 16]) -> Noise {
+        Noise {
+            periods,
+            period: periods[0],
+            timer: 0,
+            short_mode: false,
+            shift: 1,
+            envelope: Envelope::new(),
+            length: Length::new(),
+        }
+    }
+
+    fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.length.halt = data & 0x20 != 0;
+                self.envelope.write(data);
+            }
+            1 => {}
+            2 => {
+                self.short_mode = data & 0x80 != 0;
+                self.period = self.periods[(data & 0x0F) as usize];
+            }
+            _ => {
+                self.length.load(data);
+                self.envelope.start = true;
+            }
+        }
+    }
+
+    fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.period - 1;
+        let tap = if self.short_mode { 6 } else { 1 };
+        let feedback = (self.shift ^ (self.shift >> tap)) & 0x01;
+        self.shift = (self.shift >> 1) | (feedback << 14);
+    }
+
+    fn output(&self) -> u8 {
+        if self.length.counter == 0 || self.shift & 0x01 != 0 { 0 } else { self.envelope.output() }
+    }
+}
+
+struct Dmc {
+    rates: &'static [u16; 16],
+    irq_enabled: bool,
+    looping: bool,
+    rate: u16,
+    timer: u16,
+    output: u8,
+    sample_address: u16,
+    sample_length: u16,
+    address: u16,
+    remaining: u16,
+    buffer: Option<u8>,
+    shift: u8,
+    bits: u8,
+    silence: bool,
+    irq: bool,
+}
+
+impl Dmc {
+    fn new(rates: &'static [u16; 16]) -> Dmc {
+        Dmc {
+            rates,
+            irq_enabled: false,
+            looping: false,
+            rate: rates[0],
+            timer: 0,
+            output: 0,
+            sample_address: 0xC000,
+            sample_length: 1,
+            address: 0xC000,
+            remaining: 0,
+            buffer: None,
+            shift: 0,
+            bits: 8,
+            silence: true,
+            irq: false,
+        }
+    }
+
+    fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.irq_enabled = data & 0x80 != 0;
+                if !self.irq_enabled {
+                    self.irq = false;
+                }
+                self.looping = data & 0x40 != 0;
+                self.rate = self.rates[(data & 0x0F) as usize];
+            }
+            1 => self.output = data & 0x7F,
+            2 => self.sample_address = 0xC000 | (data as u16) << 6,
+            _ => self.sample_length = (data as u16) << 4 | 1,
+        }
+    }
+
+    fn restart(&mut self) {
+        self.address = self.sample_address;
+        self.remaining = self.sample_length;
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.irq = false;
+        if !enabled {
+            self.remaining = 0;
+        }
+        else if self.remaining == 0 {
+            self.restart();
+        }
+    }
+
+    fn fill(&mut self, data: u8) {
+        self.buffer = Some(data);
+        self.address = if self.address == 0xFFFF { 0x8000 } else { self.address + 1 };
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            if self.looping {
+                self.restart();
+            }
+            else if self.irq_enabled {
+                self.irq = true;
+            }
+        }
+    }
+
+    fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.rate - 1;
+
+        if !self.silence {
+            if self.shift & 0x01 != 0 {
+                if self.output <= 125 {
+                    self.output += 2;
+                }
+            }
+            else if self.output >= 2 {
+                self.output -= 2;
+            }
+        }
+        self.shift >>= 1;
+        self.bits -= 1;
+        if self.bits == 0 {
+            self.bits = 8;
+            match self.buffer.take() {
+                Some(data) => {
+                    self.silence = false;
+                    self.shift = data;
+                }
+                None => self.silence = true,
+            }
+        }
+    }
+}
+
+pub struct Apu {
+    timing: &'static ApuTiming,
+    pulse: [Pulse; 2],
+    triangle: Triangle,
+    noise: Noise,
+    dmc: Dmc,
+    five_step: bool,
+    irq_inhibit: bool,
+    frame_irq: bool,
+    frame_cycle: u32,
+    // cycles until a write to $4017 restarts the sequence
+    frame_reset: Option<u8>,
+    cycles: u64,
+}
+
+impl Apu {
+    pub fn new(region: Region) -> Apu {
+        let timing = ApuTiming::for_region(region);
+        Apu {
+            timing,
+            pulse: [Pulse::new(true), Pulse::new(false)],
+            triangle: Triangle::new(),
+            noise: Noise::new(&timing.noise_periods),
+            dmc: Dmc::new(&timing.dmc_rates),
+            five_step: false,
+            irq_inhibit: false,
+            frame_irq: false,
+            frame_cycle: 0,
+            frame_reset: None,
+            cycles: 0,
+        }
+    }
+
+    pub fn write(&mut self, address: u16, data: u8) {
+        match address {
+            0x4000..=0x4003 => self.pulse[0].write(address & 0x03, data),
+            0x4004..=0x4007 => self.pulse[1].write(address & 0x03, data),
+            0x4008..=0x400B => self.triangle.write(address & 0x03, data),
+            0x400C..=0x400F => self.noise.write(address & 0x03, data),
+            0x4010..=0x4013 => self.dmc.write(address & 0x03, data),
+            0x4015 => {
+                self.pulse[0].length.set_enabled(data & 0x01 != 0);
+                self.pulse[1].length.set_enabled(data & 0x02 != 0);
+                self.triangle.length.set_enabled(data & 0x04 != 0);
+                self.noise.length.set_enabled(data & 0x08 != 0);
+                self.dmc.set_enabled(data & 0x10 != 0);
+            }
+            0x4017 => {
+                self.five_step = data & 0x80 != 0;
+                self.irq_inhibit = data & 0x40 != 0;
+                if self.irq_inhibit {
+                    self.frame_irq = false;
+                }
+                // the sequencer restarts 3 or 4 cycles after the write
+                self.frame_reset = Some(if self.cycles & 0x01 == 0 { 2 } else { 3 });
+            }
+            _ => {}
+        }
+    }
+
+    // $4015 without the side effect of reading it
+    pub fn peek_status(&self) -> u8 {
+        let mut status = 0;
+        for (bit, length) in [&self.pulse[0].length, &self.pulse[1].length, &self.triangle.length, &self.noise.length].iter().enumerate() {
+            if length.counter > 0 {
+                status |= 0x01 << bit;
+            }
+        }
+        if self.dmc.remaining > 0 {
+            status |= 0x10;
+        }
+        if self.frame_irq {
+            status |= 0x40;
+        }
+        if self.dmc.irq {
+            status |= 0x80;
+        }
+        status
+    }
+
+    // reading $4015 acknowledges the frame irq
+    pub fn read_status(&mut self) -> u8 {
+        let status = self.peek_status();
+        self.frame_irq = false;
+        status
+    }
+
+    fn quarter_frame(&mut self) {
+        self.pulse[0].envelope.clock();
+        self.pulse[1].envelope.clock();
+        self.noise.envelope.clock();
+        self.triangle.clock_linear();
+    }
+
+    fn half_frame(&mut self) {
+        for pulse in self.pulse.iter_mut() {
+            pulse.length.clock();
+            pulse.clock_sweep();
+        }
+        self.triangle.length.clock();
+        self.noise.length.clock();
+    }
+
+    fn clock_frame(&mut self) {
+        if let Some(delay) = self.frame_reset {
+            if delay == 0 {
+                self.frame_reset = None;
+                self.frame_cycle = 0;
+                if self.five_step {
+                    self.quarter_frame();
+                    self.half_frame();
+                }
+            }
+            else {
+                self.frame_reset = Some(delay - 1);
+            }
+        }
+
+        self.frame_cycle += 1;
+        let (steps, period) = if self.five_step {
+            (&self.timing.five_step, self.timing.five_step_period)
+        }
+        else {
+            (&self.timing.four_step, self.timing.four_step_period)
+        };
+        if let Some(step) = steps.iter().position(|cycle| *cycle == self.frame_cycle) {
+            self.quarter_frame();
+            if step & 0x01 == 1 {
+                self.half_frame();
+            }
+        }
+        if !self.five_step && !self.irq_inhibit && self.frame_cycle + 1 >= steps[3] {
+            self.frame_irq = true;
+        }
+        if self.frame_cycle >= period {
+            self.frame_cycle = 0;
+        }
+    }
+
+    // one cpu cycle
+    pub fn clock(&mut self) {
+        self.clock_frame();
+        if self.cycles & 0x01 == 1 {
+            self.pulse[0].clock_timer();
+            self.pulse[1].clock_timer();
+        }
+        self.triangle.clock_timer();
+        self.noise.clock_timer();
+        self.dmc.clock_timer();
+        self.cycles += 1;
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    // true while the frame counter or the dmc holds /IRQ low
+    pub fn irq(&self) -> bool {
+        self.frame_irq || self.dmc.irq
+    }
+
+    // address of the next sample byte while the dmc's buffer is empty
+    pub fn dmc_request(&self) -> Option<u16> {
+        if self.dmc.buffer.is_none() && self.dmc.remaining > 0 { Some(self.dmc.address) } else { None }
+    }
+
+    pub fn dmc_fill(&mut self, data: u8) {
+        self.dmc.fill(data);
+    }
+
+    pub fn output(&self) -> f32 {
+        let pulse = (self.pulse[0].output() + self.pulse[1].output()) as f32;
+        let pulse = if pulse == 0.0 { 0.0 } else { 95.88 / (8128.0 / pulse + 100.0) };
+
+        let tnd = self.triangle.output() as f32 / 8227.0 + self.noise.output() as f32 / 12241.0 + self.dmc.output as f32 / 22638.0;
+        let tnd = if tnd == 0.0 { 0.0 } else { 159.79 / (1.0 / tnd + 100.0) };
+        pulse + tnd
+    }
+
+    // output with expansion audio from 0.0 to 1.0 mixed in
+    pub fn mix(&self, expansion: f32) -> f32 {
+        self.output() + expansion * EXPANSION_LEVEL
+    }
+}
+
+impl Bus for Apu {
+    fn read(&mut self, mut pinout: Pinout) -> Pinout {
+        if pinout.address == 0x4015 {
+            pinout.data = self.read_status();
+        }
+        pinout
+    }
+
+    fn write(&mut self, pinout: Pinout) -> Pinout {
+        Apu::write(self, pinout.address, pinout.data);
+        pinout
+    }
+
+    fn cycle(&mut self, mut pinout: Pinout) -> Pinout {
+        self.clock();
+        if self.irq() {
+            pinout.ctrl.remove(Ctrl::IRQ);
+        }
+        pinout
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        if address == 0x4015 { Some(self.peek_status()) } else { None }
+    }
+}
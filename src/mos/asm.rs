@@ -0,0 +1,693 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use super::dispatch::{AddressingMode, OPCODES};
+use super::loader::{Program, Segment};
+use super::symbols::SymbolTable;
+
+/*
+Assembler
+
+A two pass assembler for small programs, mostly tests. The first pass sizes every line and
+collects the labels, the second emits the bytes with every label known.
+
+    .org $8000
+    count = 5
+    start:  ldx #count      ; comments run to the end of the line
+    loop:   sta $0200,x
+            dex
+            bne loop
+            jmp (vector)
+    vector: .word start
+    text:   .byte "hi", 0, >start
+    .include "more.s"
+
+Mnemonics are the ones of the opcode table, undocumented opcodes with or without their stars
+(*LAX* or LAX). Where an undocumented opcode shares a name with an official one (NOP, SBC) the
+official encoding is used unless the stars are written.
+
+Expressions are numbers ($hex, %binary, decimal, 'c'), labels, * for the address of the line,
+unary - ~ < (low byte) > (high byte), the binary * / % + - << >> & ^ | with the precedence
+they have in C, and parentheses. An operand in parentheses is only indirect for JMP, elsewhere
+(2+3)*4 is an expression.
+
+An operand whose value is known when the first pass reaches it and fits in a byte gets zero
+page addressing, forward references get absolute addressing. Constants must be defined before
+their value is needed for sizing, a .org cannot look forward. Assembly starts at $0000 until
+a .org says otherwise. Included files are found relative to the file including them.
+
+The bytes come out as a loader Program that starts at the first byte assembled, the labels go
+to a SymbolTable along with the file and line of every instruction and data line.
+*/
+
+// deeper than this a file most likely includes itself
+const MAX_INCLUDE_DEPTH: usize = 16;
+// the name source lines from a string get in the symbol table
+const SOURCE_NAME: &str = "<source>";
+
+#[derive(Debug)]
+pub enum AsmError {
+    Io(io::Error),
+    // lines count from 1 in the file they are in
+    Syntax { line: usize },
+    UnknownMnemonic { line: usize, mnemonic: String },
+    // the instruction has no encoding for the operand as written
+    AddressingMode { line: usize },
+    Undefined { line: usize, name: String },
+    Redefined { line: usize, name: String },
+    BranchRange { line: usize, target: u16 },
+    // an operand or data value that does not fit in its bytes
+    ValueRange { line: usize, value: i64 },
+    // code or data past $FFFF
+    AddressRange { line: usize },
+    IncludeDepth { line: usize },
+    // an error in an included file
+    Included { file: String, err: Box<AsmError> },
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AsmError::Io(ref err) => write!(f, "could not read the source: {}", err),
+            AsmError::Syntax { line } => write!(f, "line {}: syntax error", line),
+            AsmError::UnknownMnemonic { line, ref mnemonic } => write!(f, "line {}: unknown mnemonic {}", line, mnemonic),
+            AsmError::AddressingMode { line } => write!(f, "line {}: addressing mode not available for this instruction", line),
+            AsmError::Undefined { line, ref name } => write!(f, "line {}: {} is not defined", line, name),
+            AsmError::Redefined { line, ref name } => write!(f, "line {}: {} is already defined", line, name),
+            AsmError::BranchRange { line, target } => write!(f, "line {}: branch target ${:04X} is out of range", line, target),
+            AsmError::ValueRange { line, value } => write!(f, "line {}: value {} does not fit", line, value),
+            AsmError::AddressRange { line } => write!(f, "line {}: assembly runs past $FFFF", line),
+            AsmError::IncludeDepth { line } => write!(f, "line {}: includes are nested too deep", line),
+            AsmError::Included { ref file, ref err } => write!(f, "{}: {}", file, err),
+        }
+    }
+}
+
+impl std::error::Error for AsmError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            AsmError::Io(ref err) => Some(err),
+            AsmError::Included { ref err, .. } => err.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AsmError {
+    fn from(err: io::Error) -> AsmError {
+        AsmError::Io(err)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Assembly {
+    pub program: Program,
+    // code and data labels, constants are left out
+    pub labels: BTreeMap<String, u16>,
+    pub symbols: SymbolTable,
+}
+
+impl Assembly {
+    pub fn label(&self, name: &str) -> Option<u16> {
+        self.labels.get(name).copied()
+    }
+}
+
+// includes are found relative to the working directory
+pub fn assemble(source: &str) -> Result<Assembly, AsmError> {
+    let mut assembler = Assembler::new(SOURCE_NAME);
+    assembler.read(source, 0, Path::new(""), 0)?;
+    assembler.run()
+}
+
+pub fn assemble_file<P: AsRef<Path>>(path: P) -> Result<Assembly, AsmError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)?;
+    let mut assembler = Assembler::new(&path.to_string_lossy());
+    assembler.read(&source, 0, path.parent().unwrap_or_else(|| Path::new("")), 0)?;
+    assembler.run()
+}
+
+// assemble string literals, one per line, and panic on errors, for programs in tests
+#[macro_export]
+macro_rules! mos_asm {
+    ($($line:literal),* $(,)?) => {
+        $crate::mos::asm::assemble(concat!($($line, "\n"),*))
+            .unwrap_or_else(|err| panic!("mos_asm!: {}", err))
+    };
+}
+
+struct Line {
+    // index into files, 0 is the top level source
+    file: usize,
+    number: usize,
+    text: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Pass {
+    Size,
+    Emit,
+}
+
+struct Assembler {
+    files: Vec<String>,
+    lines: Vec<Line>,
+    // labels and constants
+    values: BTreeMap<String, i64>,
+    // the first pass choice for each line with a zero page or absolute operand
+    zero_page: Vec<Option<bool>>,
+    pass: Pass,
+    pc: u32,
+    assembly: Assembly,
+}
+
+impl Assembler {
+    fn new(name: &str) -> Assembler {
+        Assembler {
+            files: vec![name.to_string()],
+            lines: Vec::new(),
+            values: BTreeMap::new(),
+            zero_page: Vec::new(),
+            pass: Pass::Size,
+            pc: 0,
+            assembly: Assembly::default(),
+        }
+    }
+
+    // splices included files into the line list
+    fn read(&mut self, source: &str, file: usize, dir: &Path, depth: usize) -> Result<(), AsmError> {
+        for (index, text) in source.lines().enumerate() {
+            let number = index + 1;
+            let statement = strip_comment(text).trim();
+            let include = statement.get(..8).filter(|d| d.eq_ignore_ascii_case(".include")).map(|_| statement[8..].trim());
+            let name = match include {
+                Some(argument) => string_literal(argument).ok_or(AsmError::Syntax { line: number }),
+                None => {
+                    self.lines.push(Line { file, number, text: text.to_string() });
+                    continue;
+                }
+            };
+            let result = name.and_then(|name| {
+                if depth == MAX_INCLUDE_DEPTH {
+                    return Err(AsmError::IncludeDepth { line: number });
+                }
+                let path = dir.join(name);
+                let source = fs::read_to_string(&path).map_err(|err| included(&path, AsmError::Io(err)))?;
+                self.files.push(path.to_string_lossy().into_owned());
+                let included_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                self.read(&source, self.files.len() - 1, &included_dir, depth + 1)
+            });
+            result.map_err(|err| self.in_file(file, err))?;
+        }
+        Ok(())
+    }
+
+    fn in_file(&self, file: usize, err: AsmError) -> AsmError {
+        match err {
+            // already names the file it happened in
+            AsmError::Included { .. } => err,
+            err if file == 0 => err,
+            err => AsmError::Included { file: self.files[file].clone(), err: Box::new(err) },
+        }
+    }
+
+    fn run(mut self) -> Result<Assembly, AsmError> {
+        self.zero_page = vec![None; self.lines.len()];
+        for pass in [Pass::Size, Pass::Emit].iter() {
+            self.pass = *pass;
+            self.pc = 0;
+            for index in 0..self.lines.len() {
+                self.line(index).map_err(|err| self.in_file(self.lines[index].file, err))?;
+            }
+        }
+        Ok(self.assembly)
+    }
+
+    fn line(&mut self, index: usize) -> Result<(), AsmError> {
+        let line = self.lines[index].number;
+        let text = self.lines[index].text.clone();
+        let mut statement = strip_comment(&text).trim();
+
+        // labels
+        while let Some((name, rest)) = identifier(statement).filter(|(_, rest)| rest.starts_with(':')) {
+            self.define_label(name, line)?;
+            statement = rest[1..].trim();
+        }
+        if statement.is_empty() {
+            return Ok(());
+        }
+
+        // constants
+        if let Some((name, rest)) = identifier(statement).filter(|(_, rest)| rest.trim_start().starts_with('=')) {
+            let value = self.eval(rest.trim_start()[1..].trim(), line, false)?;
+            return self.define_constant(name, value, line);
+        }
+
+        let (word, operand) = match statement.find(char::is_whitespace) {
+            Some(end) => (&statement[..end], statement[end..].trim()),
+            None => (statement, ""),
+        };
+        if word.starts_with('.') {
+            self.directive(index, word, operand)
+        }
+        else {
+            self.instruction(index, word, operand)
+        }
+    }
+
+    fn define_label(&mut self, name: &str, line: usize) -> Result<(), AsmError> {
+        if self.pass == Pass::Emit {
+            return Ok(());
+        }
+        if self.values.contains_key(name) {
+            return Err(AsmError::Redefined { line, name: name.to_string() });
+        }
+        self.values.insert(name.to_string(), self.pc as i64);
+        self.assembly.labels.insert(name.to_string(), self.pc as u16);
+        self.assembly.symbols.add_label(name, self.pc as u16, None);
+        Ok(())
+    }
+
+    // a constant that looks forward is only defined by the second pass
+    fn define_constant(&mut self, name: &str, value: Option<i64>, line: usize) -> Result<(), AsmError> {
+        match (self.pass, value) {
+            (Pass::Size, Some(value)) => {
+                if self.values.insert(name.to_string(), value).is_some() {
+                    return Err(AsmError::Redefined { line, name: name.to_string() });
+                }
+            }
+            (Pass::Size, None) => {}
+            (Pass::Emit, value) => {
+                self.values.entry(name.to_string()).or_insert(value.unwrap_or(0));
+            }
+        }
+        Ok(())
+    }
+
+    fn directive(&mut self, index: usize, word: &str, operand: &str) -> Result<(), AsmError> {
+        let line = self.lines[index].number;
+        let mut bytes = Vec::new();
+        match word.to_ascii_lowercase().as_str() {
+            ".org" => {
+                let value = self.eval(operand, line, true)?.unwrap_or(0);
+                if !(0..=0xFFFF).contains(&value) {
+                    return Err(AsmError::ValueRange { line, value });
+                }
+                self.pc = value as u32;
+                return Ok(());
+            }
+            ".byte" => {
+                for argument in split_arguments(operand).ok_or(AsmError::Syntax { line })? {
+                    if argument.starts_with('"') {
+                        bytes.extend_from_slice(string_literal(argument).ok_or(AsmError::Syntax { line })?.as_bytes());
+                        continue;
+                    }
+                    let value = self.eval(argument, line, false)?;
+                    bytes.push(fit(value, -0x80, 0xFF, line)? as u8);
+                }
+            }
+            ".word" => {
+                for argument in split_arguments(operand).ok_or(AsmError::Syntax { line })? {
+                    let value = self.eval(argument, line, false)?;
+                    bytes.extend_from_slice(&(fit(value, -0x8000, 0xFFFF, line)? as u16).to_le_bytes());
+                }
+            }
+            _ => return Err(AsmError::Syntax { line }),
+        }
+        self.emit(index, &bytes)
+    }
+
+    fn instruction(&mut self, index: usize, word: &str, operand: &str) -> Result<(), AsmError> {
+        let line = self.lines[index].number;
+        let mnemonic = word.to_ascii_uppercase();
+        if !OPCODES.iter().any(|opcode| matches(&mnemonic, opcode.mnemonic)) {
+            return Err(AsmError::UnknownMnemonic { line, mnemonic: word.to_string() });
+        }
+        let has = |mode| encoding(&mnemonic, mode).is_some();
+
+        let (mode, expression) = match parse_operand(operand).ok_or(AsmError::Syntax { line })? {
+            Operand::None if has(AddressingMode::Implied) => (AddressingMode::Implied, None),
+            Operand::None | Operand::Accumulator if has(AddressingMode::Accumulator) => (AddressingMode::Accumulator, None),
+            Operand::None => return Err(AsmError::AddressingMode { line }),
+            Operand::Immediate(expression) => (AddressingMode::Immediate, Some(expression)),
+            Operand::Indirect(expression) if has(AddressingMode::Indirect) => (AddressingMode::Indirect, Some(expression)),
+            Operand::IndirectX(expression) => (AddressingMode::IndirectX, Some(expression)),
+            Operand::IndirectY(expression) => (AddressingMode::IndirectY, Some(expression)),
+            Operand::IndexedX(expression) => (self.zero_page_or_absolute(index, &mnemonic, expression, AddressingMode::ZeroPageX, AddressingMode::AbsoluteX)?, Some(expression)),
+            Operand::IndexedY(expression) => (self.zero_page_or_absolute(index, &mnemonic, expression, AddressingMode::ZeroPageY, AddressingMode::AbsoluteY)?, Some(expression)),
+            // a label called A, or (expression) outside of JMP
+            Operand::Accumulator | Operand::Indirect(_) | Operand::Direct => {
+                if has(AddressingMode::Relative) {
+                    (AddressingMode::Relative, Some(operand))
+                }
+                else {
+                    (self.zero_page_or_absolute(index, &mnemonic, operand, AddressingMode::ZeroPage, AddressingMode::Absolute)?, Some(operand))
+                }
+            }
+        };
+        let opcode = encoding(&mnemonic, mode).ok_or(AsmError::AddressingMode { line })?;
+
+        let value = match expression {
+            Some(expression) => self.eval(expression, line, false)?,
+            None => None,
+        };
+        let mut bytes = vec![opcode];
+        match mode {
+            AddressingMode::Implied | AddressingMode::Accumulator => {}
+            AddressingMode::Immediate => bytes.push(fit(value, -0x80, 0xFF, line)? as u8),
+            AddressingMode::ZeroPage | AddressingMode::ZeroPageX | AddressingMode::ZeroPageY
+                | AddressingMode::IndirectX | AddressingMode::IndirectY => bytes.push(fit(value, 0, 0xFF, line)? as u8),
+            AddressingMode::Absolute | AddressingMode::AbsoluteX | AddressingMode::AbsoluteY
+                | AddressingMode::Indirect => bytes.extend_from_slice(&(fit(value, 0, 0xFFFF, line)? as u16).to_le_bytes()),
+            AddressingMode::Relative => {
+                let target = fit(value, 0, 0xFFFF, line)?;
+                let offset = target - (self.pc as i64 + 2);
+                if value.is_some() && !(-0x80..=0x7F).contains(&offset) {
+                    return Err(AsmError::BranchRange { line, target: target as u16 });
+                }
+                bytes.push(offset as u8);
+            }
+        }
+        self.emit(index, &bytes)
+    }
+
+    // the first pass picks zero page for values it already knows to fit, the second follows it
+    fn zero_page_or_absolute(&mut self, index: usize, mnemonic: &str, expression: &str, zero_page: AddressingMode, absolute: AddressingMode) -> Result<AddressingMode, AsmError> {
+        let line = self.lines[index].number;
+        match (encoding(mnemonic, zero_page).is_some(), encoding(mnemonic, absolute).is_some()) {
+            (true, true) => {}
+            (true, false) => return Ok(zero_page),
+            (false, true) => return Ok(absolute),
+            (false, false) => return Err(AsmError::AddressingMode { line }),
+        }
+        if self.pass == Pass::Size {
+            let value = self.eval(expression, line, false)?;
+            self.zero_page[index] = Some(value.map(|value| (0..=0xFF).contains(&value)).unwrap_or(false));
+        }
+        Ok(if self.zero_page[index] == Some(true) { zero_page } else { absolute })
+    }
+
+    fn emit(&mut self, index: usize, bytes: &[u8]) -> Result<(), AsmError> {
+        let line = &self.lines[index];
+        if self.pc as usize + bytes.len() > 0x10000 {
+            return Err(AsmError::AddressRange { line: line.number });
+        }
+        if self.pass == Pass::Emit && !bytes.is_empty() {
+            let address = self.pc as u16;
+            let program = &mut self.assembly.program;
+            program.start = program.start.or(Some(address));
+            match program.segments.last_mut() {
+                Some(last) if last.address as usize + last.data.len() == address as usize => last.data.extend_from_slice(bytes),
+                _ => program.segments.push(Segment { address, data: bytes.to_vec() }),
+            }
+            self.assembly.symbols.add_source_line(&self.files[line.file], line.number as u32, address, bytes.len() as u16, None);
+        }
+        self.pc += bytes.len() as u32;
+        Ok(())
+    }
+
+    // None for an expression using labels the first pass has not reached, or any undefined
+    // name when required
+    fn eval(&self, text: &str, line: usize, required: bool) -> Result<Option<i64>, AsmError> {
+        let mut parser = Parser { text: text.as_bytes(), position: 0, assembler: self, line, required: required || self.pass == Pass::Emit };
+        let value = parser.expression()?;
+        parser.skip_spaces();
+        if parser.position != text.len() {
+            return Err(AsmError::Syntax { line });
+        }
+        Ok(value)
+    }
+}
+
+fn included(path: &Path, err: AsmError) -> AsmError {
+    AsmError::Included { file: path.to_string_lossy().into_owned(), err: Box::new(err) }
+}
+
+fn fit(value: Option<i64>, min: i64, max: i64, line: usize) -> Result<i64, AsmError> {
+    match value {
+        Some(value) if !(min..=max).contains(&value) => Err(AsmError::ValueRange { line, value }),
+        Some(value) => Ok(value),
+        // still unknown in the first pass
+        None => Ok(0),
+    }
+}
+
+// written with stars only the undocumented opcode matches, without either
+fn matches(mnemonic: &str, table: &str) -> bool {
+    mnemonic == table || table.strip_prefix('*').and_then(|t| t.strip_suffix('*')) == Some(mnemonic)
+}
+
+fn encoding(mnemonic: &str, mode: AddressingMode) -> Option<u8> {
+    let official = OPCODES.iter().position(|opcode| opcode.mnemonic == mnemonic && opcode.mode == mode);
+    official.or_else(|| OPCODES.iter().position(|opcode| matches(mnemonic, opcode.mnemonic) && opcode.mode == mode)).map(|opcode| opcode as u8)
+}
+
+enum Operand<'a> {
+    None,
+    Accumulator,
+    Immediate(&'a str),
+    Indirect(&'a str),
+    IndirectX(&'a str),
+    IndirectY(&'a str),
+    IndexedX(&'a str),
+    IndexedY(&'a str),
+    Direct,
+}
+
+fn parse_operand(operand: &str) -> Option<Operand<'_>> {
+    if operand.is_empty() {
+        return Some(Operand::None);
+    }
+    if operand.eq_ignore_ascii_case("a") {
+        return Some(Operand::Accumulator);
+    }
+    if let Some(expression) = operand.strip_prefix('#') {
+        return Some(Operand::Immediate(expression.trim()));
+    }
+
+    let arguments = split_arguments(operand)?;
+    match arguments.as_slice() {
+        [expression, index] if index.eq_ignore_ascii_case("x") => Some(Operand::IndexedX(expression)),
+        [expression, index] if index.eq_ignore_ascii_case("y") => {
+            Some(enclosed(expression).map(Operand::IndirectY).unwrap_or(Operand::IndexedY(expression)))
+        }
+        [expression] => {
+            let inner = match enclosed(expression) {
+                Some(inner) => inner,
+                None => return Some(Operand::Direct),
+            };
+            match split_arguments(inner)?.as_slice() {
+                [expression, index] if index.eq_ignore_ascii_case("x") => Some(Operand::IndirectX(expression)),
+                [_] => Some(Operand::Indirect(inner)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// the inside of text that is wholly in one pair of parentheses
+fn enclosed(text: &str) -> Option<&str> {
+    let inner = text.strip_prefix('(')?.strip_suffix(')')?;
+    let mut depth = 0;
+    for c in inner.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return None,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    Some(inner.trim())
+}
+
+// comma separated and trimmed, commas in quotes or parentheses do not count
+fn split_arguments(text: &str) -> Option<Vec<&str>> {
+    let mut arguments = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    let mut quote = None;
+    for (position, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                arguments.push(text[start..position].trim());
+                start = position + 1;
+            }
+            _ => {}
+        }
+    }
+    arguments.push(text[start..].trim());
+    if quote.is_some() || depth != 0 || arguments.iter().any(|argument| argument.is_empty()) {
+        return None;
+    }
+    Some(arguments)
+}
+
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    for (position, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, ';') => return &text[..position],
+            _ => {}
+        }
+    }
+    text
+}
+
+fn string_literal(text: &str) -> Option<&str> {
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    if inner.contains('"') {
+        return None;
+    }
+    Some(inner)
+}
+
+// a name at the start of text and the text after it
+fn identifier(text: &str) -> Option<(&str, &str)> {
+    let first = text.chars().next()?;
+    if !first.is_ascii_alphabetic() && first != '_' {
+        return None;
+    }
+    let end = text.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(text.len());
+    Some((&text[..end], &text[end..]))
+}
+
+// recursive descent, one function per precedence level
+struct Parser<'a> {
+    text: &'a [u8],
+    position: usize,
+    assembler: &'a Assembler,
+    line: usize,
+    // undefined names are errors rather than unknown values
+    required: bool,
+}
+
+type Value = Result<Option<i64>, AsmError>;
+
+impl<'a> Parser<'a> {
+    fn skip_spaces(&mut self) {
+        while self.text.get(self.position).map(u8::is_ascii_whitespace) == Some(true) {
+            self.position += 1;
+        }
+    }
+
+    // takes one of the operators if it comes next
+    fn operator(&mut self, operators: &[&'static str]) -> Option<&'static str> {
+        self.skip_spaces();
+        let rest = &self.text[self.position..];
+        let operator = operators.iter().find(|operator| rest.starts_with(operator.as_bytes()))?;
+        // not the first half of << or >>
+        if operator.len() == 1 && rest.len() > 1 && rest[1] == rest[0] && (rest[0] == b'<' || rest[0] == b'>') {
+            return None;
+        }
+        self.position += operator.len();
+        Some(operator)
+    }
+
+    fn expression(&mut self) -> Value {
+        self.binary(0)
+    }
+
+    fn binary(&mut self, level: usize) -> Value {
+        const LEVELS: &[&[&str]] = &[&["|"], &["^"], &["&"], &["<<", ">>"], &["+", "-"], &["*", "/", "%"]];
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        while let Some(operator) = self.operator(LEVELS[level]) {
+            let right = self.binary(level + 1)?;
+            left = match (left, right) {
+                (Some(left), Some(right)) => Some(self.apply(operator, left, right)?),
+                _ => None,
+            };
+        }
+        Ok(left)
+    }
+
+    fn apply(&self, operator: &str, left: i64, right: i64) -> Result<i64, AsmError> {
+        let value = match operator {
+            "|" => left | right,
+            "^" => left ^ right,
+            "&" => left & right,
+            "<<" => left.checked_shl(right as u32).unwrap_or(0),
+            ">>" => left.checked_shr(right as u32).unwrap_or(0),
+            "+" => left.wrapping_add(right),
+            "-" => left.wrapping_sub(right),
+            "*" => left.wrapping_mul(right),
+            "/" => left.checked_div(right).ok_or(AsmError::ValueRange { line: self.line, value: right })?,
+            _ => left.checked_rem(right).ok_or(AsmError::ValueRange { line: self.line, value: right })?,
+        };
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Value {
+        let value = match self.operator(&["-", "~", "<", ">"]) {
+            Some(operator) => (operator, self.unary()?),
+            None => return self.primary(),
+        };
+        Ok(match value {
+            ("-", value) => value.map(|value| value.wrapping_neg()),
+            ("~", value) => value.map(|value| !value),
+            ("<", value) => value.map(|value| value & 0xFF),
+            (_, value) => value.map(|value| (value >> 8) & 0xFF),
+        })
+    }
+
+    fn primary(&mut self) -> Value {
+        self.skip_spaces();
+        let syntax = AsmError::Syntax { line: self.line };
+        let rest = std::str::from_utf8(&self.text[self.position..]).map_err(|_| AsmError::Syntax { line: self.line })?;
+        let first = rest.chars().next().ok_or(AsmError::Syntax { line: self.line })?;
+
+        if first == '(' {
+            self.position += 1;
+            let value = self.expression()?;
+            return match self.operator(&[")"]) {
+                Some(_) => Ok(value),
+                None => Err(syntax),
+            };
+        }
+        if first == '*' {
+            self.position += 1;
+            return Ok(Some(self.assembler.pc as i64));
+        }
+        if first == '\'' {
+            let bytes = rest.as_bytes();
+            if bytes.len() < 3 || bytes[2] != b'\'' {
+                return Err(syntax);
+            }
+            self.position += 3;
+            return Ok(Some(bytes[1] as i64));
+        }
+
+        let (radix, digits) = match first {
+            '$' => (16, &rest[1..]),
+            '%' => (2, &rest[1..]),
+            c if c.is_ascii_digit() => (10, rest),
+            _ => {
+                let (name, _) = identifier(rest).ok_or(syntax)?;
+                self.position += name.len();
+                return match self.assembler.values.get(name) {
+                    Some(value) => Ok(Some(*value)),
+                    None if self.required => Err(AsmError::Undefined { line: self.line, name: name.to_string() }),
+                    None => Ok(None),
+                };
+            }
+        };
+        let len = digits.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(digits.len());
+        let value = i64::from_str_radix(&digits[..len], radix).map_err(|_| syntax)?;
+        self.position += rest.len() - digits.len() + len;
+        Ok(Some(value))
+    }
+}
@@ -0,0 +1,409 @@
+use super::{Ctrl, Pinout};
+use super::core::Context;
+use super::bus::FastBus;
+use super::dispatch::{AddressingMode, Operation, OPCODES};
+use super::fast;
+use super::rp2a03::Rp2a03;
+
+/*
+Cached block decoder
+
+Straight line runs of code are decoded once into blocks keyed by the address of their first
+opcode, then executed instruction by instruction with the operands already in hand. A block
+ends after any instruction that changes the flow of control.
+
+Blocks are only built from memory marked with map_memory, which must be plain ram or rom
+without side effects on access. Writes into a page holding cached code invalidate every block
+covering the written address, so self modifying code is picked up on the next instruction.
+Writes made directly through bus_mut are not seen, call invalidate afterwards.
+
+The cycle accurate core is used for the whole instruction whenever an interrupt line or RDY
+is asserted, an interrupt or reset sequence is running, or an instruction may touch an
+address outside of mapped memory.
+*/
+
+// longest straight line run decoded into a single block
+const MAX_BLOCK_LEN: usize = 32;
+
+// SHA, SHX, SHY and TAS can replace the high byte of their target address
+const UNSTABLE_STORES: [u8; 5] = [0x93, 0x9B, 0x9C, 0x9E, 0x9F];
+
+const fn to_address(hb: u8, lb: u8) -> u16 {
+    (hb as u16) << 8 | (lb as u16)
+}
+
+struct Decoded<F: FastBus> {
+    pc: u16,
+    opcode: u8,
+    operands: [u8; 2],
+    handler: fast::Handler<F>,
+    execute: fn(&mut Context),
+    // the data address depends on the registers and may fall outside of mapped memory
+    checked: bool,
+}
+
+impl<F: FastBus> Clone for Decoded<F> {
+    fn clone(&self) -> Decoded<F> {
+        *self
+    }
+}
+
+impl<F: FastBus> Copy for Decoded<F> {}
+
+struct Block<F: FastBus> {
+    start: u16,
+    // one past the last byte of the block
+    end: u32,
+    entries: Vec<Decoded<F>>,
+    valid: bool,
+}
+
+impl<F: FastBus> Block<F> {
+    fn overlaps(&self, start: u16, end: u16) -> bool {
+        (self.start as u32) <= (end as u32) && (start as u32) < self.end
+    }
+}
+
+// bus wrapper recording writes into pages that hold cached code
+struct Watched<F: FastBus> {
+    bus: F,
+    code_pages: [bool; 256],
+    writes: Vec<u16>,
+}
+
+impl<F: FastBus> FastBus for Watched<F> {
+    #[inline]
+    fn read(&mut self, address: u16) -> u8 {
+        self.bus.read(address)
+    }
+
+    #[inline]
+    fn write(&mut self, address: u16, data: u8) {
+        self.bus.write(address, data);
+        if self.code_pages[(address >> 8) as usize] {
+            self.writes.push(address);
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        self.bus.peek(address)
+    }
+}
+
+pub struct BlockCache<F: FastBus> {
+    memory: Watched<F>,
+    mapped: Vec<bool>,
+    mapped_pages: [bool; 256],
+    blocks: Vec<Block<Watched<F>>>,
+    free: Vec<usize>,
+    // block starting at each address
+    lookup: Vec<Option<usize>>,
+    block_count: usize,
+    // blocks covering each page, may hold stale entries until the page is next invalidated
+    pages: Vec<Vec<usize>>,
+}
+
+impl<F: FastBus + 'static> BlockCache<F> {
+    pub fn new(bus: F) -> BlockCache<F> {
+        BlockCache {
+            memory: Watched { bus, code_pages: [false; 256], writes: Vec::new() },
+            mapped: vec![false; 0x10000],
+            mapped_pages: [false; 256],
+            blocks: Vec::new(),
+            free: Vec::new(),
+            lookup: vec![None; 0x10000],
+            block_count: 0,
+            pages: vec![Vec::new(); 256],
+        }
+    }
+
+    // mark start..=end as plain memory that blocks may be decoded from and run against
+    pub fn map_memory(&mut self, start: u16, end: u16) {
+        for address in start..=end {
+            self.mapped[address as usize] = true;
+        }
+
+        for page in 0..256 {
+            self.mapped_pages[page] = self.mapped[page << 8..(page + 1) << 8].iter().all(|m| *m);
+        }
+    }
+
+    pub fn bus(&self) -> &F {
+        &self.memory.bus
+    }
+
+    pub fn bus_mut(&mut self) -> &mut F {
+        &mut self.memory.bus
+    }
+
+    pub fn peek(&self, address: u16) -> Option<u8> {
+        self.memory.bus.peek(address)
+    }
+
+    // blocks covering the address are dropped like on a write from the cpu
+    pub fn poke(&mut self, address: u16, data: u8) -> bool {
+        if !self.memory.bus.poke(address, data) {
+            return false;
+        }
+        self.invalidate(address, address);
+        true
+    }
+
+    pub fn into_bus(self) -> F {
+        self.memory.bus
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.block_count
+    }
+
+    // drop every block covering an address in start..=end
+    pub fn invalidate(&mut self, start: u16, end: u16) {
+        for page in (start >> 8) as usize..=(end >> 8) as usize {
+            let ids = std::mem::take(&mut self.pages[page]);
+            let mut kept = Vec::with_capacity(ids.len());
+
+            for id in ids {
+                if !self.blocks[id].valid || !self.blocks[id].overlaps((page << 8) as u16, (page << 8 | 0xFF) as u16) {
+                    continue;
+                }
+
+                if self.blocks[id].overlaps(start, end) {
+                    self.remove_block(id);
+                }
+                else if !kept.contains(&id) {
+                    kept.push(id);
+                }
+            }
+
+            self.memory.code_pages[page] = !kept.is_empty();
+            self.pages[page] = kept;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+        self.free.clear();
+        self.lookup.iter_mut().for_each(|id| *id = None);
+        self.block_count = 0;
+        self.memory.writes.clear();
+        self.memory.code_pages = [false; 256];
+        for page in self.pages.iter_mut() {
+            page.clear();
+        }
+    }
+
+    // run until at least cycles more cycles have elapsed, stopping at the end of a block. the
+    // input lines of pinout apply to the first instruction only
+    pub fn run(&mut self, cpu: &mut Rp2a03, mut pinout: Pinout, cycles: u64) -> Pinout {
+        let until = cpu.cycle_count() + cycles;
+        while cpu.cycle_count() < until {
+            pinout = self.step(cpu, pinout);
+        }
+
+        pinout
+    }
+
+    // run a single block, or a single instruction on the cycle accurate core
+    pub fn step(&mut self, cpu: &mut Rp2a03, pinout: Pinout) -> Pinout {
+        let lines = Ctrl::IRQ | Ctrl::NMI | Ctrl::RDY | Ctrl::RES;
+        if !pinout.ctrl.contains(lines) || cpu.cpu.nmi_detected || cpu.cpu.latch.irq || !fast::is_supported(&cpu.cpu) {
+            return self.fallback(cpu, pinout);
+        }
+
+        let pc = u16::from(cpu.cpu.pc).wrapping_sub(1);
+        let id = match self.lookup[pc as usize] {
+            Some(id) => id,
+            None => match self.decode(pc) {
+                Some(id) => id,
+                None => return self.fallback(cpu, pinout),
+            }
+        };
+
+        self.execute_block(cpu, id, pinout)
+    }
+
+    fn execute_block(&mut self, cpu: &mut Rp2a03, id: usize, pinout: Pinout) -> Pinout {
+        for index in 0..self.blocks[id].entries.len() {
+            let entry = self.blocks[id].entries[index];
+
+            // memory was written behind the cache's back
+            if cpu.cpu.ir.opcode != entry.opcode {
+                self.invalidate(entry.pc, entry.pc);
+                return self.fallback(cpu, pinout);
+            }
+
+            if entry.checked && self.touches_unmapped(&cpu.cpu, &entry) {
+                return self.fallback(cpu, pinout);
+            }
+
+            // the interrupt lines were checked to be released, nothing is polled inside a block
+            let cycles = (entry.handler)(&mut cpu.cpu, &mut self.memory, pinout, entry.operands, entry.execute);
+            cpu.count_instruction(entry.opcode, cycles);
+
+            if !self.memory.writes.is_empty() {
+                self.flush_writes();
+                if !self.blocks[id].valid { break; }
+            }
+        }
+
+        cpu.boundary_pinout(pinout)
+    }
+
+    // kept out of line, inlining the micro-op path slows down the block loop
+    #[inline(never)]
+    fn fallback(&mut self, cpu: &mut Rp2a03, pinout: Pinout) -> Pinout {
+        let pinout = cpu.tick_instruction(&mut self.memory, pinout);
+        self.flush_writes();
+        pinout
+    }
+
+    fn flush_writes(&mut self) {
+        while let Some(address) = self.memory.writes.pop() {
+            self.invalidate(address, address);
+        }
+    }
+
+    // indexed and indirect accesses can only be checked once the registers are known, including
+    // the read from the wrong page made before the high byte is fixed up
+    fn touches_unmapped(&mut self, cpu: &Context, entry: &Decoded<Watched<F>>) -> bool {
+        let [lo, hi] = entry.operands;
+        let (base, index) = match OPCODES[entry.opcode as usize].mode {
+            AddressingMode::AbsoluteX => (to_address(hi, lo), cpu.x),
+            AddressingMode::AbsoluteY => (to_address(hi, lo), cpu.y),
+            AddressingMode::IndirectX => {
+                let pointer = lo.wrapping_add(cpu.x);
+                let adl = self.memory.bus.read(to_address(0, pointer));
+                let adh = self.memory.bus.read(to_address(0, pointer.wrapping_add(1)));
+                (to_address(adh, adl), 0)
+            }
+            AddressingMode::IndirectY => {
+                let bal = self.memory.bus.read(to_address(0, lo));
+                let bah = self.memory.bus.read(to_address(0, lo.wrapping_add(1)));
+                (to_address(bah, bal), cpu.y)
+            }
+            // everything else was checked when the block was decoded
+            _ => return false,
+        };
+
+        let address = base.wrapping_add(index as u16);
+        let uncarried = (base & 0xFF00) | (address & 0x00FF);
+        !self.mapped[address as usize] || !self.mapped[uncarried as usize]
+    }
+
+    // accesses that are known from the instruction bytes alone
+    fn is_cacheable(&self, pc: u16, opcode: u8, operands: [u8; 2]) -> bool {
+        let op = &OPCODES[opcode as usize];
+        let [lo, hi] = operands;
+
+        let mode = match op.mode {
+            AddressingMode::ZeroPage | AddressingMode::ZeroPageX | AddressingMode::ZeroPageY |
+            AddressingMode::IndirectX | AddressingMode::IndirectY => self.mapped_pages[0],
+            AddressingMode::Absolute => match op.operation {
+                Operation::Read | Operation::Store | Operation::Modify => self.mapped[to_address(hi, lo) as usize],
+                _ => true,
+            }
+            // the pointer high byte is not incremented across a page
+            AddressingMode::Indirect => {
+                self.mapped[to_address(hi, lo) as usize] && self.mapped[to_address(hi, lo.wrapping_add(1)) as usize]
+            }
+            // a taken branch reads from the target before the high byte is fixed up
+            AddressingMode::Relative => {
+                let next = pc.wrapping_add(2);
+                let target = next.wrapping_add(lo as i8 as u16);
+                self.mapped[((next & 0xFF00) | (target & 0x00FF)) as usize]
+            }
+            _ => true,
+        };
+
+        let stack = match op.operation {
+            Operation::Php | Operation::Pha | Operation::Plp | Operation::Pla |
+            Operation::Jsr | Operation::Rts | Operation::Rti => self.mapped_pages[1],
+            _ => true,
+        };
+
+        mode && stack
+    }
+
+    fn decode(&mut self, start: u16) -> Option<usize> {
+        let mut entries = Vec::new();
+        let mut pc = start as u32;
+
+        while entries.len() < MAX_BLOCK_LEN {
+            let opcode = self.memory.bus.read(pc as u16);
+            let op = &OPCODES[opcode as usize];
+            if op.operation == Operation::Brk || op.operation == Operation::Jam || UNSTABLE_STORES.contains(&opcode) { break; }
+
+            let len = match op.mode {
+                AddressingMode::Implied | AddressingMode::Accumulator => 1,
+                AddressingMode::Absolute | AddressingMode::AbsoluteX | AddressingMode::AbsoluteY |
+                AddressingMode::Indirect => 3,
+                _ => 2,
+            };
+
+            // single byte instructions still read the byte after the opcode
+            if pc + len.max(2) > 0x10000 { break; }
+            if !(pc..pc + len.max(2)).all(|address| self.mapped[address as usize]) { break; }
+
+            let mut operands = [0; 2];
+            for i in 1..len {
+                operands[i as usize - 1] = self.memory.bus.read((pc + i) as u16);
+            }
+
+            if !self.is_cacheable(pc as u16, opcode, operands) { break; }
+
+            let checked = match op.mode {
+                AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => {
+                    !self.mapped_pages[operands[1] as usize] || !self.mapped_pages[operands[1].wrapping_add(1) as usize]
+                }
+                AddressingMode::IndirectX | AddressingMode::IndirectY => self.mapped_pages.contains(&false),
+                _ => false,
+            };
+
+            entries.push(Decoded {
+                pc: pc as u16,
+                opcode,
+                operands,
+                handler: fast::handler(opcode),
+                execute: op.execute,
+                checked,
+            });
+            pc += len;
+
+            match op.operation {
+                Operation::Branch | Operation::Jmp | Operation::Jsr | Operation::Rts | Operation::Rti => break,
+                _ => {}
+            }
+        }
+
+        if entries.is_empty() {
+            return None;
+        }
+
+        let block = Block { start, end: pc, entries, valid: true };
+        let id = match self.free.pop() {
+            Some(id) => { self.blocks[id] = block; id }
+            None => { self.blocks.push(block); self.blocks.len() - 1 }
+        };
+
+        for page in (start >> 8) as usize..=((pc - 1) >> 8) as usize {
+            self.pages[page].push(id);
+            self.memory.code_pages[page] = true;
+        }
+        self.lookup[start as usize] = Some(id);
+        self.block_count += 1;
+
+        Some(id)
+    }
+
+    fn remove_block(&mut self, id: usize) {
+        let block = &mut self.blocks[id];
+        block.valid = false;
+        block.entries.clear();
+        if self.lookup[block.start as usize] == Some(id) {
+            self.lookup[block.start as usize] = None;
+            self.block_count -= 1;
+        }
+        self.free.push(id);
+    }
+}
@@ -1,5 +1,142 @@
 use super::Pinout;
+use super::power::PowerOnProfile;
+
 pub trait Bus {
     fn read(&mut self, pinout: Pinout) -> Pinout;
     fn write(&mut self, pinout: Pinout) -> Pinout;
-}
\ No newline at end of file
+
+    /*
+    Access for debuggers and other tools. Neither may have a side effect the running system
+    could see, no register is cleared and no device is clocked. A bus that cannot give that
+    guarantee for an address returns None or false and tools fall back to showing nothing.
+    */
+    fn peek(&self, _address: u16) -> Option<u8> {
+        None
+    }
+
+    fn poke(&mut self, _address: u16, _data: u8) -> bool {
+        false
+    }
+
+    // where in prg rom a cpu address reads from with the banks as they are now, for code/data
+    // loggers. None for anything that is not cartridge rom
+    fn prg_rom_offset(&self, _address: u16) -> Option<usize> {
+        None
+    }
+
+    // called at the end of every cycle of the cycle accurate path with the pins as the cycle
+    // left them, for hardware clocked by M2 like mapper counters
+    fn cycle(&mut self, pinout: Pinout) -> Pinout {
+        pinout
+    }
+
+    // called by the reset and interrupt sequences after their stack cycles, right before the
+    // vector is read, for tools that follow the program flow from the bus
+    fn vector(&mut self, _sequence: Sequence) {}
+}
+
+// the sequences that jump through a vector
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Sequence {
+    Reset,
+    Brk,
+    Irq,
+    Nmi,
+    // a brk or irq taken over by an nmi, it reads the nmi vector
+    BrkHijack,
+    IrqHijack,
+}
+
+/*
+Simplified bus used by the instruction level execution path. Only the address and data
+are passed, dummy reads and writes are not performed. Every FastBus is also a Bus so the
+same memory can be driven by the cycle accurate path.
+*/
+pub trait FastBus {
+    fn read(&mut self, address: u16) -> u8;
+    fn write(&mut self, address: u16, data: u8);
+
+    // see Bus::peek and Bus::poke
+    fn peek(&self, _address: u16) -> Option<u8> {
+        None
+    }
+
+    fn poke(&mut self, _address: u16, _data: u8) -> bool {
+        false
+    }
+}
+
+impl<F: FastBus> Bus for F {
+    #[inline]
+    fn read(&mut self, mut pinout: Pinout) -> Pinout {
+        pinout.data = FastBus::read(self, pinout.address);
+        pinout
+    }
+
+    #[inline]
+    fn write(&mut self, pinout: Pinout) -> Pinout {
+        FastBus::write(self, pinout.address, pinout.data);
+        pinout
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        FastBus::peek(self, address)
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        FastBus::poke(self, address, data)
+    }
+}
+
+// ram mirrored over the whole address range, the size must be a power of two
+pub struct Ram {
+    mem: Vec<u8>,
+    mask: usize,
+}
+
+impl Ram {
+    pub fn new(size: usize) -> Ram {
+        Ram::from_power_on(size, PowerOnProfile::Zero)
+    }
+
+    pub fn from_power_on(size: usize, profile: PowerOnProfile) -> Ram {
+        assert!(size.is_power_of_two() && size <= 0x10000, "ram size must be a power of two up to 64k");
+        let mut ram = Ram { mem: vec![0; size], mask: size - 1 };
+        ram.power_on(profile);
+        ram
+    }
+
+    // refill the contents as found at power on
+    pub fn power_on(&mut self, profile: PowerOnProfile) {
+        profile.fill(&mut self.mem);
+    }
+
+    pub fn memory(&self) -> &[u8] {
+        &self.mem
+    }
+
+    pub fn memory_mut(&mut self) -> &mut [u8] {
+        &mut self.mem
+    }
+}
+
+impl FastBus for Ram {
+    #[inline]
+    fn read(&mut self, address: u16) -> u8 {
+        self.mem[address as usize & self.mask]
+    }
+
+    #[inline]
+    fn write(&mut self, address: u16, data: u8) {
+        self.mem[address as usize & self.mask] = data;
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        Some(self.mem[address as usize & self.mask])
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        self.mem[address as usize & self.mask] = data;
+        true
+    }
+}
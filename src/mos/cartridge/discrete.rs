@@ -0,0 +1,121 @@
+use super::header::Mirroring;
+use super::mapper::{Banks, Mapper};
+
+/*
+Discrete logic boards
+
+A latch on the data bus at $8000-$FFFF selects whole banks, there is nothing to read back.
+*/
+
+// mapper 0, 16k or 32k of prg and 8k of chr, nothing switches
+pub struct NRom;
+
+impl Mapper for NRom {
+    fn power_on(&mut self, banks: &mut Banks) {
+        banks.set_prg_32k(0);
+    }
+
+    fn write(&mut self, _banks: &mut Banks, _address: u16, _data: u8) {}
+}
+
+// mapper 2, 16k at $8000 switched, the last 16k fixed at $C000
+pub struct UxRom {
+    pub conflicts: bool,
+}
+
+impl Mapper for UxRom {
+    fn power_on(&mut self, banks: &mut Banks) {
+        banks.set_prg_16k(0, 0);
+        banks.set_prg_16k(1, (banks.prg_banks() / 2).saturating_sub(1));
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        if address >= 0x8000 {
+            banks.set_prg_16k(0, data as usize);
+        }
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.conflicts
+    }
+}
+
+// mapper 3, 8k of chr switched
+pub struct CnRom {
+    pub conflicts: bool,
+}
+
+impl Mapper for CnRom {
+    fn power_on(&mut self, banks: &mut Banks) {
+        banks.set_prg_32k(0);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        if address >= 0x8000 {
+            banks.set_chr_8k(data as usize);
+        }
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.conflicts
+    }
+}
+
+// mapper 7, 32k of prg switched and a single screen picked by bit 4
+pub struct AxRom {
+    pub conflicts: bool,
+}
+
+impl Mapper for AxRom {
+    fn power_on(&mut self, banks: &mut Banks) {
+        banks.set_prg_32k(0);
+        banks.mirroring = Mirroring::SingleScreenLower;
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        if address >= 0x8000 {
+            banks.set_prg_32k((data & 0x07) as usize);
+            banks.mirroring = if data & 0x10 != 0 { Mirroring::SingleScreenUpper } else { Mirroring::SingleScreenLower };
+        }
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.conflicts
+    }
+}
+
+// mapper 11, 32k of prg in bits 0-1 and 8k of chr in bits 4-7, always has bus conflicts
+pub struct ColorDreams;
+
+impl Mapper for ColorDreams {
+    fn power_on(&mut self, banks: &mut Banks) {
+        banks.set_prg_32k(0);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        if address >= 0x8000 {
+            banks.set_prg_32k((data & 0x03) as usize);
+            banks.set_chr_8k((data >> 4) as usize);
+        }
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        true
+    }
+}
+
+// mapper 66, 32k of prg in bits 4-5 and 8k of chr in bits 0-1
+pub struct GxRom;
+
+impl Mapper for GxRom {
+    fn power_on(&mut self, banks: &mut Banks) {
+        banks.set_prg_32k(0);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        if address >= 0x8000 {
+            banks.set_prg_32k(((data >> 4) & 0x03) as usize);
+            banks.set_chr_8k((data & 0x03) as usize);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use super::header::Mirroring;
+use super::mapper::{Banks, Mapper};
+use super::vrc::Vrc6;
+use super::fme7::Fme7;
+use super::n163::N163;
+
+/*
+Expansion sound chips without a cartridge
+
+NSF players drive the sound of VRC6, Sunsoft 5B and Namco 163 boards with no board around
+them. ExpansionAudio runs the board's mapper over banks of its own and only passes on the
+accesses to its sound registers, a tune writing elsewhere in $8000-$FFFF does not reach the
+bank or irq registers. audio is the same 0.0 to 1.0 output as Cartridge::audio.
+*/
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExpansionChip {
+    Vrc6,
+    Sunsoft5b,
+    N163,
+}
+
+pub struct ExpansionAudio {
+    chip: ExpansionChip,
+    mapper: Box<dyn Mapper>,
+    banks: Banks,
+}
+
+impl ExpansionAudio {
+    pub fn new(chip: ExpansionChip) -> ExpansionAudio {
+        let mut mapper: Box<dyn Mapper> = match chip {
+            ExpansionChip::Vrc6 => Box::new(Vrc6::new(false)),
+            ExpansionChip::Sunsoft5b => Box::new(Fme7::new()),
+            ExpansionChip::N163 => Box::new(N163::new()),
+        };
+        let mut banks = Banks::new(0x8000, 0x2000, 0, Mirroring::Horizontal);
+        mapper.power_on(&mut banks);
+        ExpansionAudio { chip, mapper, banks }
+    }
+
+    pub fn chip(&self) -> ExpansionChip {
+        self.chip
+    }
+
+    // the address is one of the chip's sound registers or ports
+    pub fn decodes(&self, address: u16) -> bool {
+        match self.chip {
+            ExpansionChip::Vrc6 => matches!(address, 0x9000..=0x9003 | 0xA000..=0xA002 | 0xB000..=0xB002),
+            ExpansionChip::Sunsoft5b => address >= 0xC000,
+            ExpansionChip::N163 => matches!(address, 0x4800..=0x4FFF | 0xF800..=0xFFFF),
+        }
+    }
+
+    pub fn write(&mut self, address: u16, data: u8) {
+        if self.decodes(address) {
+            self.mapper.write(&mut self.banks, address, data);
+        }
+    }
+
+    // the n163 sound ram reads back through its data port
+    pub fn read(&mut self, address: u16) -> Option<u8> {
+        if !self.decodes(address) {
+            return None;
+        }
+        self.mapper.read(&mut self.banks, address)
+    }
+
+    // an M2 cycle has ended
+    pub fn cycle(&mut self) {
+        self.mapper.cycle(&mut self.banks);
+    }
+
+    pub fn audio(&self) -> f32 {
+        self.mapper.audio()
+    }
+}
@@ -0,0 +1,197 @@
+use super::header::Mirroring;
+use super::mapper::{Banks, Mapper};
+
+/*
+Sunsoft FME-7 and 5B, mapper 69
+
+A command written to $8000-$9FFF picks the register the parameter written to $A000-$BFFF
+goes to. $6000-$7FFF holds either a prg rom bank or prg ram, the last 8k at $E000 is fixed.
+
+The irq counter is 16 bits and counts down every M2 cycle while enabled, irq is raised when
+it wraps from $0000 to $FFFF with irq enabled. Writing the irq control register acknowledges.
+
+The 5B adds a YM2149 style sound chip behind $C000 (register select) and $E000 (data). The
+three square channels and the noise generator are emulated, channels using the envelope
+play at a fixed volume as the envelope generator is not.
+*/
+
+// 5B volume steps, about 3db each
+const VOLUME: [f32; 16] = [
+    0.0, 0.0079, 0.0112, 0.0158, 0.0224, 0.0316, 0.0447, 0.0631,
+    0.0891, 0.1259, 0.1778, 0.2512, 0.3548, 0.5012, 0.7079, 1.0,
+];
+
+struct Sunsoft5b {
+    registers: [u8; 16],
+    select: u8,
+    // the chip runs at half of M2 and its tone dividers at a further 1/8
+    divider: u8,
+    tone_counters: [u16; 3],
+    tone_outputs: [bool; 3],
+    noise_counter: u8,
+    noise_shift: u32,
+}
+
+impl Sunsoft5b {
+    fn new() -> Sunsoft5b {
+        Sunsoft5b {
+            registers: [0; 16],
+            select: 0,
+            divider: 0,
+            tone_counters: [0; 3],
+            tone_outputs: [false; 3],
+            noise_counter: 0,
+            noise_shift: 1,
+        }
+    }
+
+    fn tone_period(&self, channel: usize) -> u16 {
+        let period = self.registers[channel * 2] as u16 | ((self.registers[channel * 2 + 1] & 0x0F) as u16) << 8;
+        period.max(1)
+    }
+
+    fn clock(&mut self) {
+        self.divider = (self.divider + 1) & 0x0F;
+        if self.divider != 0 {
+            return;
+        }
+
+        for channel in 0..3 {
+            self.tone_counters[channel] += 1;
+            if self.tone_counters[channel] >= self.tone_period(channel) {
+                self.tone_counters[channel] = 0;
+                self.tone_outputs[channel] = !self.tone_outputs[channel];
+            }
+        }
+
+        // the noise divider runs at half the tone rate
+        self.noise_counter += 1;
+        if self.noise_counter >= (self.registers[6] & 0x1F).max(1) * 2 {
+            self.noise_counter = 0;
+            let feedback = (self.noise_shift ^ (self.noise_shift >> 3)) & 0x01;
+            self.noise_shift = (self.noise_shift >> 1) | (feedback << 16);
+        }
+    }
+
+    fn output(&self) -> f32 {
+        let mixer = self.registers[7];
+        let noise = self.noise_shift & 0x01 != 0;
+        let mut sum = 0.0;
+        for channel in 0..3 {
+            // mixer bits are active low
+            let tone = self.tone_outputs[channel] || mixer & (0x01 << channel) != 0;
+            let noise = noise || mixer & (0x08 << channel) != 0;
+            if tone && noise {
+                let volume = self.registers[8 + channel];
+                sum += if volume & 0x10 != 0 { VOLUME[15] } else { VOLUME[(volume & 0x0F) as usize] };
+            }
+        }
+        sum / 3.0
+    }
+}
+
+pub struct Fme7 {
+    command: u8,
+    chr: [u8; 8],
+    prg: [u8; 3],
+    prg_6000: u8,
+    irq_enabled: bool,
+    counter_enabled: bool,
+    counter: u16,
+    pending: bool,
+    audio: Sunsoft5b,
+}
+
+impl Fme7 {
+    pub fn new() -> Fme7 {
+        Fme7 {
+            command: 0,
+            chr: [0; 8],
+            prg: [0; 3],
+            prg_6000: 0,
+            irq_enabled: false,
+            counter_enabled: false,
+            counter: 0,
+            pending: false,
+            audio: Sunsoft5b::new(),
+        }
+    }
+
+    fn update(&self, banks: &mut Banks) {
+        for (slot, bank) in self.chr.iter().enumerate() {
+            banks.set_chr_1k(slot, *bank as usize);
+        }
+        for (slot, bank) in self.prg.iter().enumerate() {
+            banks.set_prg_8k(slot, (bank & 0x3F) as usize);
+        }
+        banks.set_prg_8k(3, banks.prg_banks() - 1);
+
+        // bit 6 selects ram, bit 7 enables it
+        if self.prg_6000 & 0x40 != 0 {
+            banks.prg_rom_6000 = None;
+            banks.prg_ram_enabled = self.prg_6000 & 0x80 != 0;
+        }
+        else {
+            banks.prg_rom_6000 = Some(banks.bank_prg_8k((self.prg_6000 & 0x3F) as usize));
+            banks.prg_ram_enabled = false;
+        }
+    }
+}
+
+impl Default for Fme7 {
+    fn default() -> Fme7 {
+        Fme7::new()
+    }
+}
+
+impl Mapper for Fme7 {
+    fn power_on(&mut self, banks: &mut Banks) {
+        self.update(banks);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        match address & 0xE000 {
+            0x8000 => self.command = data & 0x0F,
+            0xA000 => match self.command {
+                0..=7 => self.chr[self.command as usize] = data,
+                8 => self.prg_6000 = data,
+                9..=0x0B => self.prg[(self.command - 9) as usize] = data,
+                0x0C => banks.mirroring = match data & 0x03 {
+                    0 => Mirroring::Vertical,
+                    1 => Mirroring::Horizontal,
+                    2 => Mirroring::SingleScreenLower,
+                    _ => Mirroring::SingleScreenUpper,
+                },
+                0x0D => {
+                    self.irq_enabled = data & 0x01 != 0;
+                    self.counter_enabled = data & 0x80 != 0;
+                    self.pending = false;
+                }
+                0x0E => self.counter = (self.counter & 0xFF00) | data as u16,
+                _ => self.counter = (self.counter & 0x00FF) | (data as u16) << 8,
+            },
+            0xC000 => self.audio.select = data & 0x0F,
+            0xE000 => self.audio.registers[self.audio.select as usize] = data,
+            _ => return,
+        }
+        self.update(banks);
+    }
+
+    fn cycle(&mut self, _banks: &mut Banks) {
+        if self.counter_enabled {
+            self.counter = self.counter.wrapping_sub(1);
+            if self.counter == 0xFFFF && self.irq_enabled {
+                self.pending = true;
+            }
+        }
+        self.audio.clock();
+    }
+
+    fn irq(&self) -> bool {
+        self.pending
+    }
+
+    fn audio(&self) -> f32 {
+        self.audio.output()
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt;
+use std::io;
+use super::super::clock::Region;
+
+/*
+iNES and NES 2.0 headers
+
+Both start with "NES" 0x1A and share the first eight bytes, NES 2.0 is marked by 0b10 in
+bits 2-3 of byte 7 and uses bytes 8-15 for the larger mapper number, the submapper, the
+upper bits of the rom sizes, the ram sizes and the timing region.
+
+iNES files have no reliable ram size or region, 8k of prg ram is assumed and the region is
+left to the caller. Some old dumping tools wrote a signature over bytes 7-15, when bytes
+12-15 are not zero on an iNES header the upper nibble of the mapper number is ignored.
+*/
+
+pub const HEADER_LEN: usize = 16;
+pub const TRAINER_LEN: usize = 512;
+
+#[derive(Debug)]
+pub enum CartridgeError {
+    Io(io::Error),
+    // fewer than 16 bytes
+    TooShort,
+    BadMagic,
+    // the header asks for more prg, chr or trainer data than the file holds
+    Truncated { expected: usize, found: usize },
+    NoPrgRom,
+    // a rom size in exponent notation that does not fit in memory
+    SizeOverflow,
+    UnsupportedMapper { mapper: u16, submapper: u8 },
+    // a save file that does not match the battery backed ram
+    SaveSize { expected: usize, found: usize },
+}
+
+impl fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CartridgeError::Io(ref err) => write!(f, "could not read the rom image: {}", err),
+            CartridgeError::TooShort => write!(f, "rom image is shorter than its header"),
+            CartridgeError::BadMagic => write!(f, "rom image does not start with NES<EOF>"),
+            CartridgeError::Truncated { expected, found } => write!(f, "rom image is truncated, expected {} bytes but found {}", expected, found),
+            CartridgeError::NoPrgRom => write!(f, "rom image has no prg rom"),
+            CartridgeError::SizeOverflow => write!(f, "rom size in the header is too large"),
+            CartridgeError::UnsupportedMapper { mapper, submapper } => write!(f, "mapper {}.{} is not supported", mapper, submapper),
+            CartridgeError::SaveSize { expected, found } => write!(f, "save file is {} bytes, the cartridge has {} bytes of battery backed ram", found, expected),
+        }
+    }
+}
+
+impl std::error::Error for CartridgeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            CartridgeError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CartridgeError {
+    fn from(err: io::Error) -> CartridgeError {
+        CartridgeError::Io(err)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    INes,
+    Nes2,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Mirroring {
+    Horizontal,
+    Vertical,
+    FourScreen,
+    // set by mappers, every nametable address maps to the same 1k
+    SingleScreenLower,
+    SingleScreenUpper,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Timing {
+    Ntsc,
+    Pal,
+    // runs on either, treated as ntsc
+    MultiRegion,
+    Dendy,
+}
+
+impl Timing {
+    pub fn region(&self) -> Region {
+        match *self {
+            Timing::Ntsc | Timing::MultiRegion => Region::Ntsc,
+            Timing::Pal => Region::Pal,
+            Timing::Dendy => Region::Dendy,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Header {
+    pub format: Format,
+    pub mapper: u16,
+    pub submapper: u8,
+    // sizes in bytes
+    pub prg_rom_size: usize,
+    pub chr_rom_size: usize,
+    pub prg_ram_size: usize,
+    pub prg_nvram_size: usize,
+    pub chr_ram_size: usize,
+    pub chr_nvram_size: usize,
+    pub mirroring: Mirroring,
+    pub battery: bool,
+    pub trainer: bool,
+    // None when an iNES header does not say
+    pub timing: Option<Timing>,
+}
+
+impl Header {
+    pub fn parse(data: &[u8]) -> Result<Header, CartridgeError> {
+        if data.len() < HEADER_LEN {
+            return Err(CartridgeError::TooShort);
+        }
+        if &data[0..4] != b"NES\x1A" {
+            return Err(CartridgeError::BadMagic);
+        }
+
+        let flags6 = data[6];
+        let flags7 = data[7];
+        let mirroring = if flags6 & 0x08 != 0 { Mirroring::FourScreen }
+            else if flags6 & 0x01 != 0 { Mirroring::Vertical }
+            else { Mirroring::Horizontal };
+        let battery = flags6 & 0x02 != 0;
+        let trainer = flags6 & 0x04 != 0;
+
+        let header = if flags7 & 0x0C == 0x08 {
+            Header {
+                format: Format::Nes2,
+                mapper: (flags6 >> 4) as u16 | (flags7 & 0xF0) as u16 | ((data[8] & 0x0F) as u16) << 8,
+                submapper: data[8] >> 4,
+                prg_rom_size: rom_size(data[4], data[9] & 0x0F, 0x4000)?,
+                chr_rom_size: rom_size(data[5], data[9] >> 4, 0x2000)?,
+                prg_ram_size: ram_size(data[10] & 0x0F),
+                prg_nvram_size: ram_size(data[10] >> 4),
+                chr_ram_size: ram_size(data[11] & 0x0F),
+                chr_nvram_size: ram_size(data[11] >> 4),
+                mirroring,
+                battery,
+                trainer,
+                timing: Some(match data[12] & 0x03 {
+                    0 => Timing::Ntsc,
+                    1 => Timing::Pal,
+                    2 => Timing::MultiRegion,
+                    _ => Timing::Dendy,
+                }),
+            }
+        }
+        else {
+            // a signature over the end of the header, the upper mapper nibble is garbage too
+            let upper = if data[12..16].iter().all(|b| *b == 0) { flags7 & 0xF0 } else { 0 };
+            let chr_rom_size = data[5] as usize * 0x2000;
+            let prg_ram_size = if data[8] == 0 { 0x2000 } else { data[8] as usize * 0x2000 };
+            Header {
+                format: Format::INes,
+                mapper: ((flags6 >> 4) | upper) as u16,
+                submapper: 0,
+                prg_rom_size: data[4] as usize * 0x4000,
+                chr_rom_size,
+                prg_ram_size: if battery { 0 } else { prg_ram_size },
+                prg_nvram_size: if battery { prg_ram_size } else { 0 },
+                // boards without chr rom have 8k of chr ram
+                chr_ram_size: if chr_rom_size == 0 { 0x2000 } else { 0 },
+                chr_nvram_size: 0,
+                mirroring,
+                battery,
+                trainer,
+                timing: None,
+            }
+        };
+
+        if header.prg_rom_size == 0 {
+            return Err(CartridgeError::NoPrgRom);
+        }
+        Ok(header)
+    }
+
+    // bytes the header says follow it
+    pub fn data_len(&self) -> usize {
+        let trainer = if self.trainer { TRAINER_LEN } else { 0 };
+        trainer + self.prg_rom_size + self.chr_rom_size
+    }
+}
+
+// nes 2.0 rom size, an upper nibble of 0xF selects 2^E * (MM * 2 + 1) from the low byte
+fn rom_size(lsb: u8, msb: u8, unit: usize) -> Result<usize, CartridgeError> {
+    if msb == 0x0F {
+        let exponent = (lsb >> 2) as u32;
+        let multiplier = (lsb & 0x03) as usize * 2 + 1;
+        1usize.checked_shl(exponent)
+            .and_then(|size| size.checked_mul(multiplier))
+            .filter(|size| *size <= 1 << 30)
+            .ok_or(CartridgeError::SizeOverflow)
+    }
+    else {
+        Ok(((msb as usize) << 8 | lsb as usize) * unit)
+    }
+}
+
+// nes 2.0 ram size, 64 << shift or nothing
+fn ram_size(shift: u8) -> usize {
+    if shift == 0 { 0 } else { 64 << shift }
+}
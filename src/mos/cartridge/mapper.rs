@@ -0,0 +1,185 @@
+use super::header::{CartridgeError, Header, Mirroring};
+use super::discrete::{AxRom, CnRom, ColorDreams, GxRom, NRom, UxRom};
+use super::mmc1::Mmc1;
+use super::mmc2::Mmc2;
+use super::vrc::{Vrc4, Vrc6, Vrc7};
+use super::fme7::Fme7;
+use super::n163::N163;
+
+/*
+Mappers
+
+A mapper only holds its registers, the cartridge owns the memory and reads it through the
+bank offsets a mapper sets in Banks. Prg rom is switched in 8k slots from $8000, chr in 1k
+slots from $0000 of the ppu bus and prg ram in one 8k slot at $6000. Bank numbers past the
+end of a rom wrap around like the unconnected upper address lines do on the boards.
+
+Mappers see every cpu write to $4020-$FFFF after prg ram has been written, on boards with
+bus conflicts the data is already anded with the rom byte at the address. Every cpu cycle
+and every ppu pattern fetch is passed on for mappers that count or latch on them, a mapper
+asserting irq pulls /IRQ low at the end of the cycle.
+*/
+
+pub struct Banks {
+    // offsets into prg rom, chr and prg ram
+    pub prg: [usize; 4],
+    pub chr: [usize; 8],
+    pub prg_ram: usize,
+    pub prg_ram_enabled: bool,
+    // prg rom mapped over $6000-$7FFF in place of the ram
+    pub prg_rom_6000: Option<usize>,
+    pub mirroring: Mirroring,
+    prg_len: usize,
+    chr_len: usize,
+    prg_ram_len: usize,
+}
+
+impl Banks {
+    pub fn new(prg_len: usize, chr_len: usize, prg_ram_len: usize, mirroring: Mirroring) -> Banks {
+        let mut banks = Banks {
+            prg: [0; 4],
+            chr: [0; 8],
+            prg_ram: 0,
+            prg_ram_enabled: true,
+            prg_rom_6000: None,
+            mirroring,
+            prg_len,
+            chr_len,
+            prg_ram_len,
+        };
+        banks.set_prg_32k(0);
+        banks.set_chr_8k(0);
+        banks
+    }
+
+    // 8k prg banks in the rom
+    pub fn prg_banks(&self) -> usize {
+        (self.prg_len / 0x2000).max(1)
+    }
+
+    pub fn prg_ram_len(&self) -> usize {
+        self.prg_ram_len
+    }
+
+    pub fn set_prg_8k(&mut self, slot: usize, bank: usize) {
+        self.prg[slot] = self.bank_prg_8k(bank);
+    }
+
+    // slot 0 at $8000, slot 1 at $C000
+    pub fn set_prg_16k(&mut self, slot: usize, bank: usize) {
+        self.set_prg_8k(slot * 2, bank * 2);
+        self.set_prg_8k(slot * 2 + 1, bank * 2 + 1);
+    }
+
+    pub fn set_prg_32k(&mut self, bank: usize) {
+        self.set_prg_16k(0, bank * 2);
+        self.set_prg_16k(1, bank * 2 + 1);
+    }
+
+    pub fn set_chr_1k(&mut self, slot: usize, bank: usize) {
+        self.chr[slot] = bank * 0x400 % self.chr_len;
+    }
+
+    pub fn set_chr_2k(&mut self, slot: usize, bank: usize) {
+        self.set_chr_1k(slot * 2, bank * 2);
+        self.set_chr_1k(slot * 2 + 1, bank * 2 + 1);
+    }
+
+    // slot 0 at $0000, slot 1 at $1000
+    pub fn set_chr_4k(&mut self, slot: usize, bank: usize) {
+        for i in 0..4 {
+            self.set_chr_1k(slot * 4 + i, bank * 4 + i);
+        }
+    }
+
+    pub fn set_chr_8k(&mut self, bank: usize) {
+        self.set_chr_4k(0, bank * 2);
+        self.set_chr_4k(1, bank * 2 + 1);
+    }
+
+    pub fn set_prg_ram_8k(&mut self, bank: usize) {
+        if self.prg_ram_len > 0 {
+            self.prg_ram = bank * 0x2000 % self.prg_ram_len;
+        }
+    }
+
+    pub fn bank_prg_8k(&self, bank: usize) -> usize {
+        bank * 0x2000 % self.prg_len
+    }
+
+    pub fn prg_offset(&self, address: u16) -> usize {
+        self.prg[((address >> 13) & 0x3) as usize] + (address & 0x1FFF) as usize
+    }
+
+    pub fn chr_offset(&self, address: u16) -> usize {
+        self.chr[((address >> 10) & 0x7) as usize] + (address & 0x3FF) as usize
+    }
+
+    // None when there is no prg ram or it is disabled
+    pub fn prg_ram_offset(&self, address: u16) -> Option<usize> {
+        if self.prg_ram_len == 0 || !self.prg_ram_enabled {
+            return None;
+        }
+        Some((self.prg_ram + (address & 0x1FFF) as usize) % self.prg_ram_len)
+    }
+}
+
+pub trait Mapper {
+    // set up the banks at power on
+    fn power_on(&mut self, banks: &mut Banks);
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8);
+
+    // registers read back through the cpu bus, None leaves the read to the cartridge
+    fn read(&mut self, _banks: &mut Banks, _address: u16) -> Option<u8> {
+        None
+    }
+
+    // an M2 cycle has ended
+    fn cycle(&mut self, _banks: &mut Banks) {}
+
+    // true while the mapper pulls /IRQ low
+    fn irq(&self) -> bool {
+        false
+    }
+
+    // expansion audio, 0.0 to 1.0 at full volume of every channel
+    fn audio(&self) -> f32 {
+        0.0
+    }
+
+    // the ppu has read pattern memory at address
+    fn ppu_read(&mut self, _banks: &mut Banks, _address: u16) {}
+
+    // writes to prg rom are anded with the byte read from it
+    fn bus_conflicts(&self) -> bool {
+        false
+    }
+}
+
+// nes 2.0 submapper 2 of the discrete boards, anded bus conflicts
+fn conflicts(header: &Header) -> bool {
+    header.submapper == 2
+}
+
+pub fn create(header: &Header) -> Result<Box<dyn Mapper>, CartridgeError> {
+    let mapper: Box<dyn Mapper> = match header.mapper {
+        0 => Box::new(NRom),
+        1 => Box::new(Mmc1::new()),
+        2 => Box::new(UxRom { conflicts: conflicts(header) }),
+        3 => Box::new(CnRom { conflicts: conflicts(header) }),
+        7 => Box::new(AxRom { conflicts: conflicts(header) }),
+        9 => Box::new(Mmc2::new()),
+        10 => Box::new(Mmc2::mmc4()),
+        11 => Box::new(ColorDreams),
+        19 => Box::new(N163::new()),
+        21 | 23 | 25 => Box::new(Vrc4::new(header.mapper, header.submapper)),
+        24 => Box::new(Vrc6::new(false)),
+        26 => Box::new(Vrc6::new(true)),
+        66 => Box::new(GxRom),
+        69 => Box::new(Fme7::new()),
+        85 => Box::new(Vrc7::new(header.submapper)),
+        mapper => return Err(CartridgeError::UnsupportedMapper { mapper, submapper: header.submapper }),
+    };
+    Ok(mapper)
+}
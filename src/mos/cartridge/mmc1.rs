@@ -0,0 +1,132 @@
+use super::header::Mirroring;
+use super::mapper::{Banks, Mapper};
+
+/*
+MMC1, mapper 1
+
+Registers are loaded a bit at a time through a shift register, five writes to $8000-$FFFF
+with bit 0 holding the data and the fifth picking the register by bits 13-14 of its address.
+A write with bit 7 set empties the shift register and sets prg mode 3.
+
+The chip only sees a write on the first of consecutive M2 cycles, so the second write of a
+read modify write instruction is dropped. Cycles are counted from Bus::cycle, without it
+every write is taken.
+
+Boards with 512k of prg use bit 4 of the chr registers to pick the 256k half, boards with
+more than 8k of prg ram pick the ram bank with bits 2-3.
+*/
+
+pub struct Mmc1 {
+    shift: u8,
+    // bits written into the shift register so far
+    count: u8,
+    control: u8,
+    chr0: u8,
+    chr1: u8,
+    prg: u8,
+    cycles: u64,
+    last_write: Option<u64>,
+}
+
+impl Mmc1 {
+    pub fn new() -> Mmc1 {
+        Mmc1 {
+            shift: 0,
+            count: 0,
+            control: 0x0C,
+            chr0: 0,
+            chr1: 0,
+            prg: 0,
+            cycles: 0,
+            last_write: None,
+        }
+    }
+
+    fn update(&self, banks: &mut Banks) {
+        banks.mirroring = match self.control & 0x03 {
+            0 => Mirroring::SingleScreenLower,
+            1 => Mirroring::SingleScreenUpper,
+            2 => Mirroring::Vertical,
+            _ => Mirroring::Horizontal,
+        };
+
+        // 512k boards switch the 256k half with the chr register
+        let outer = if banks.prg_banks() > 32 { (self.chr0 & 0x10) as usize } else { 0 };
+        let bank = (self.prg & 0x0F) as usize;
+        match (self.control >> 2) & 0x03 {
+            0 | 1 => banks.set_prg_32k((outer | bank) >> 1),
+            2 => {
+                banks.set_prg_16k(0, outer);
+                banks.set_prg_16k(1, outer | bank);
+            }
+            _ => {
+                banks.set_prg_16k(0, outer | bank);
+                banks.set_prg_16k(1, outer | 0x0F);
+            }
+        }
+
+        if self.control & 0x10 == 0 {
+            banks.set_chr_8k((self.chr0 >> 1) as usize);
+        }
+        else {
+            banks.set_chr_4k(0, self.chr0 as usize);
+            banks.set_chr_4k(1, self.chr1 as usize);
+        }
+
+        let ram_bank = if banks.prg_ram_len() > 0x4000 { (self.chr0 >> 2) & 0x03 } else { (self.chr0 >> 3) & 0x01 };
+        banks.set_prg_ram_8k(ram_bank as usize);
+        banks.prg_ram_enabled = self.prg & 0x10 == 0;
+    }
+}
+
+impl Default for Mmc1 {
+    fn default() -> Mmc1 {
+        Mmc1::new()
+    }
+}
+
+impl Mapper for Mmc1 {
+    fn power_on(&mut self, banks: &mut Banks) {
+        self.update(banks);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        if address < 0x8000 {
+            return;
+        }
+
+        let consecutive = self.last_write == Some(self.cycles.wrapping_sub(1));
+        self.last_write = Some(self.cycles);
+        if consecutive {
+            return;
+        }
+
+        if data & 0x80 != 0 {
+            self.shift = 0;
+            self.count = 0;
+            self.control |= 0x0C;
+            self.update(banks);
+            return;
+        }
+
+        self.shift |= (data & 0x01) << self.count;
+        self.count += 1;
+        if self.count < 5 {
+            return;
+        }
+
+        match (address >> 13) & 0x03 {
+            0 => self.control = self.shift,
+            1 => self.chr0 = self.shift,
+            2 => self.chr1 = self.shift,
+            _ => self.prg = self.shift,
+        }
+        self.shift = 0;
+        self.count = 0;
+        self.update(banks);
+    }
+
+    fn cycle(&mut self, _banks: &mut Banks) {
+        self.cycles += 1;
+    }
+}
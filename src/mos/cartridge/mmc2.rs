@@ -0,0 +1,86 @@
+use super::header::Mirroring;
+use super::mapper::{Banks, Mapper};
+
+/*
+MMC2 and MMC4, mappers 9 and 10
+
+Each 4k half of the pattern tables has two chr registers and a latch picking between them.
+The latch flips when the ppu fetches tile $FD or $FE, on the MMC2 the left half only reacts
+to the fetch of $0FD8 or $0FE8 while the right half and both halves on the MMC4 react to the
+whole $xFD8-$xFDF and $xFE8-$xFEF ranges. The switch takes effect from the next fetch.
+
+The MMC2 switches 8k of prg at $8000 with the last three 8k fixed, the MMC4 switches 16k at
+$8000 with the last 16k fixed and has prg ram.
+*/
+
+pub struct Mmc2 {
+    mmc4: bool,
+    // chr banks for latch values $FD and $FE of each half
+    chr: [[u8; 2]; 2],
+    // 0 for $FD, 1 for $FE
+    latch: [usize; 2],
+}
+
+impl Mmc2 {
+    pub fn new() -> Mmc2 {
+        Mmc2 { mmc4: false, chr: [[0; 2]; 2], latch: [1; 2] }
+    }
+
+    pub fn mmc4() -> Mmc2 {
+        Mmc2 { mmc4: true, chr: [[0; 2]; 2], latch: [1; 2] }
+    }
+
+    fn update_chr(&self, banks: &mut Banks) {
+        for half in 0..2 {
+            banks.set_chr_4k(half, self.chr[half][self.latch[half]] as usize);
+        }
+    }
+}
+
+impl Mapper for Mmc2 {
+    fn power_on(&mut self, banks: &mut Banks) {
+        let last = banks.prg_banks();
+        if self.mmc4 {
+            banks.set_prg_16k(0, 0);
+            banks.set_prg_16k(1, (last / 2).saturating_sub(1));
+        }
+        else {
+            banks.set_prg_8k(0, 0);
+            for slot in 1..4 {
+                banks.set_prg_8k(slot, (last + slot).saturating_sub(4));
+            }
+        }
+        self.update_chr(banks);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        match address & 0xF000 {
+            0xA000 if self.mmc4 => banks.set_prg_16k(0, (data & 0x0F) as usize),
+            0xA000 => banks.set_prg_8k(0, (data & 0x0F) as usize),
+            0xB000 => self.chr[0][0] = data & 0x1F,
+            0xC000 => self.chr[0][1] = data & 0x1F,
+            0xD000 => self.chr[1][0] = data & 0x1F,
+            0xE000 => self.chr[1][1] = data & 0x1F,
+            0xF000 => banks.mirroring = if data & 0x01 != 0 { Mirroring::Horizontal } else { Mirroring::Vertical },
+            _ => return,
+        }
+        self.update_chr(banks);
+    }
+
+    fn ppu_read(&mut self, banks: &mut Banks, address: u16) {
+        let half = ((address >> 12) & 0x01) as usize;
+        let tile = address & 0x0FF8;
+        // the left half of the mmc2 only flips on the first byte of the tile
+        let exact = half == 0 && !self.mmc4;
+        let latch = match tile {
+            0x0FD8 if !exact || address & 0x0FFF == 0x0FD8 => 0,
+            0x0FE8 if !exact || address & 0x0FFF == 0x0FE8 => 1,
+            _ => return,
+        };
+
+        if self.latch[half] != latch {
+            self.latch[half] = latch;
+            self.update_chr(banks);
+        }
+    }
+}
@@ -0,0 +1,247 @@
+mod header;
+mod mapper;
+mod discrete;
+mod mmc1;
+mod mmc2;
+mod vrc;
+mod fme7;
+mod n163;
+mod expansion;
+
+pub use self::header::{CartridgeError, Format, Header, Mirroring, Timing, HEADER_LEN, TRAINER_LEN};
+pub use self::mapper::{Banks, Mapper};
+pub use self::expansion::{ExpansionAudio, ExpansionChip};
+
+use std::fs;
+use std::path::Path;
+use super::{Ctrl, Pinout};
+use super::apu::Apu;
+use super::bus::Bus;
+
+/*
+Nes cartridge, cpu side
+
+Answers $4020-$FFFF, prg ram at $6000-$7FFF and prg rom at $8000-$FFFF switched by the
+mapper. Addresses outside of the cartridge, $4020-$5FFF and disabled prg ram leave the bus
+as it was, so put the cartridge behind a MemoryMap to get open bus there.
+
+A trainer is loaded into prg ram at $7000 like the copiers it was dumped from did. The chr
+data is kept for the ppu side, chr ram when the board has no chr rom. Prg ram is battery
+backed when the header says so, save_ram and load_save_ram move it to and from save files.
+
+Mappers with an irq counter pull /IRQ low from Bus::cycle, irq reports the same line for
+callers wiring it up themselves. audio is the mapper's expansion sound, mix adds it to the apu
+output the way the console mixes the cartridge audio pin with its own channels.
+*/
+
+pub struct Cartridge {
+    header: Header,
+    prg_rom: Vec<u8>,
+    chr: Vec<u8>,
+    chr_is_ram: bool,
+    prg_ram: Vec<u8>,
+    banks: Banks,
+    mapper: Box<dyn Mapper>,
+}
+
+impl Cartridge {
+    pub fn from_bytes(data: &[u8]) -> Result<Cartridge, CartridgeError> {
+        let header = Header::parse(data)?;
+        let expected = HEADER_LEN + header.data_len();
+        if data.len() < expected {
+            return Err(CartridgeError::Truncated { expected, found: data.len() });
+        }
+
+        let mut mapper = mapper::create(&header)?;
+
+        let mut offset = HEADER_LEN;
+        let trainer = if header.trainer {
+            offset += TRAINER_LEN;
+            Some(&data[HEADER_LEN..offset])
+        }
+        else {
+            None
+        };
+
+        let prg_rom = data[offset..offset + header.prg_rom_size].to_vec();
+        offset += header.prg_rom_size;
+        let chr_is_ram = header.chr_rom_size == 0;
+        let chr = if !chr_is_ram {
+            data[offset..offset + header.chr_rom_size].to_vec()
+        }
+        else {
+            // a header without chr rom or chr ram sizes still has 8k of chr ram on the board
+            vec![0; (header.chr_ram_size + header.chr_nvram_size).max(0x2000)]
+        };
+
+        // always room for the trainer
+        let mut prg_ram_size = header.prg_ram_size + header.prg_nvram_size;
+        if trainer.is_some() {
+            prg_ram_size = prg_ram_size.max(0x2000);
+        }
+        let mut prg_ram = vec![0; prg_ram_size];
+        if let Some(trainer) = trainer {
+            prg_ram[0x1000..0x1000 + TRAINER_LEN].copy_from_slice(trainer);
+        }
+
+        let mut banks = Banks::new(prg_rom.len(), chr.len(), prg_ram.len(), header.mirroring);
+        mapper.power_on(&mut banks);
+
+        Ok(Cartridge { header, prg_rom, chr, chr_is_ram, prg_ram, banks, mapper })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Cartridge, CartridgeError> {
+        let data = fs::read(path)?;
+        Cartridge::from_bytes(&data)
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn prg_rom(&self) -> &[u8] {
+        &self.prg_rom
+    }
+
+    // chr rom, or chr ram when the board has none
+    pub fn chr(&self) -> &[u8] {
+        &self.chr
+    }
+
+    pub fn chr_mut(&mut self) -> &mut [u8] {
+        &mut self.chr
+    }
+
+    pub fn prg_ram(&self) -> &[u8] {
+        &self.prg_ram
+    }
+
+    pub fn prg_ram_mut(&mut self) -> &mut [u8] {
+        &mut self.prg_ram
+    }
+
+    // the nametable layout, mappers may change it at any time
+    pub fn mirroring(&self) -> Mirroring {
+        self.banks.mirroring
+    }
+
+    // battery backed prg ram to write to a save file, None without a battery
+    pub fn save_ram(&self) -> Option<&[u8]> {
+        if self.header.battery { Some(&self.prg_ram) } else { None }
+    }
+
+    pub fn load_save_ram(&mut self, data: &[u8]) -> Result<(), CartridgeError> {
+        let expected = if self.header.battery { self.prg_ram.len() } else { 0 };
+        if data.len() != expected {
+            return Err(CartridgeError::SaveSize { expected, found: data.len() });
+        }
+        self.prg_ram.copy_from_slice(data);
+        Ok(())
+    }
+
+    // true while the mapper asserts /IRQ
+    pub fn irq(&self) -> bool {
+        self.mapper.irq()
+    }
+
+    // expansion audio of the mapper, 0.0 on boards without
+    pub fn audio(&self) -> f32 {
+        self.mapper.audio()
+    }
+
+    // the system audio output, the apu channels with the expansion audio mixed in
+    pub fn mix(&self, apu: &Apu) -> f32 {
+        apu.mix(self.audio())
+    }
+
+    // pattern memory as seen by the ppu at $0000-$1FFF, the fetch is passed on to the mapper
+    pub fn ppu_read(&mut self, address: u16) -> u8 {
+        let data = self.chr[self.banks.chr_offset(address)];
+        self.mapper.ppu_read(&mut self.banks, address);
+        data
+    }
+
+    pub fn ppu_write(&mut self, address: u16, data: u8) {
+        if self.chr_is_ram {
+            let offset = self.banks.chr_offset(address);
+            self.chr[offset] = data;
+        }
+    }
+
+    fn prg_ram_offset(&self, address: u16) -> Option<usize> {
+        if !(0x6000..0x8000).contains(&address) {
+            return None;
+        }
+        self.banks.prg_ram_offset(address)
+    }
+}
+
+impl Bus for Cartridge {
+    fn read(&mut self, mut pinout: Pinout) -> Pinout {
+        if pinout.address >= 0x4020 {
+            if let Some(data) = self.mapper.read(&mut self.banks, pinout.address) {
+                pinout.data = data;
+                return pinout;
+            }
+        }
+        if let Some(data) = self.peek(pinout.address) {
+            pinout.data = data;
+        }
+        pinout
+    }
+
+    fn write(&mut self, pinout: Pinout) -> Pinout {
+        let address = pinout.address;
+        if address < 0x4020 {
+            return pinout;
+        }
+
+        let mut data = pinout.data;
+        if let Some(offset) = self.prg_ram_offset(address) {
+            self.prg_ram[offset] = data;
+        }
+        if address >= 0x8000 && self.mapper.bus_conflicts() {
+            data &= self.prg_rom[self.banks.prg_offset(address)];
+        }
+
+        self.mapper.write(&mut self.banks, address, data);
+        pinout
+    }
+
+    fn cycle(&mut self, mut pinout: Pinout) -> Pinout {
+        self.mapper.cycle(&mut self.banks);
+        if self.mapper.irq() {
+            pinout.ctrl.remove(Ctrl::IRQ);
+        }
+        pinout
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        if let Some(offset) = self.prg_rom_offset(address) {
+            return Some(self.prg_rom[offset]);
+        }
+        self.prg_ram_offset(address).map(|offset| self.prg_ram[offset])
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        if address >= 0x8000 {
+            let offset = self.banks.prg_offset(address);
+            self.prg_rom[offset] = data;
+            return true;
+        }
+        match self.prg_ram_offset(address) {
+            Some(offset) => { self.prg_ram[offset] = data; true },
+            None => false,
+        }
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        if address >= 0x8000 {
+            return Some(self.banks.prg_offset(address));
+        }
+        match ((0x6000..0x8000).contains(&address), self.banks.prg_rom_6000) {
+            (true, Some(bank)) => Some(bank + (address & 0x1FFF) as usize),
+            _ => None,
+        }
+    }
+}
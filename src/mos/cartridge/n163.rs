@@ -0,0 +1,168 @@
+use super::mapper::{Banks, Mapper};
+
+/*
+Namco 163, mapper 19
+
+Prg is switched in three 8k banks at $8000, $A000 and $C000 with the last 8k fixed, chr in
+eight 1k banks. The nametable registers at $C000-$DFFF are not emulated, the board's
+mirroring from the header is kept.
+
+The irq counter is 15 bits, readable and writable at $5000 (low) and $5800 (high, bit 7
+enables). It counts up every M2 cycle while enabled and raises irq on reaching $7FFF where it
+stops. Writing either half acknowledges.
+
+Audio is up to eight wavetable channels kept in 128 bytes of internal ram, reached through
+the address port at $F800 (bit 7 increments after each access) and the data port at $4800.
+The chip updates one channel every 15 cycles round robin and outputs one channel at a time,
+the output here is the average of the enabled channels which is what the multiplexing sounds
+like once filtered.
+*/
+
+pub struct N163 {
+    ram: [u8; 128],
+    ram_address: u8,
+    auto_increment: bool,
+    counter: u16,
+    counter_enabled: bool,
+    pending: bool,
+    sound_disabled: bool,
+    // cycles until the next channel update
+    divider: u8,
+    channel: usize,
+    outputs: [u8; 8],
+}
+
+impl N163 {
+    pub fn new() -> N163 {
+        N163 {
+            ram: [0; 128],
+            ram_address: 0,
+            auto_increment: false,
+            counter: 0,
+            counter_enabled: false,
+            pending: false,
+            sound_disabled: false,
+            divider: 15,
+            channel: 7,
+            outputs: [0; 8],
+        }
+    }
+
+    // channels 8 - n to 7 are enabled
+    fn channels(&self) -> usize {
+        (((self.ram[0x7F] >> 4) & 0x07) + 1) as usize
+    }
+
+    fn data_port(&mut self) -> usize {
+        let address = self.ram_address as usize;
+        if self.auto_increment {
+            self.ram_address = (self.ram_address + 1) & 0x7F;
+        }
+        address
+    }
+
+    fn update_channel(&mut self, channel: usize) {
+        let base = 0x40 + channel * 8;
+        let registers = &mut self.ram[base..base + 8];
+        let frequency = registers[0] as u32 | (registers[2] as u32) << 8 | ((registers[4] & 0x03) as u32) << 16;
+        let phase = registers[1] as u32 | (registers[3] as u32) << 8 | (registers[5] as u32) << 16;
+        let length = (256 - (registers[4] & 0xFC) as u32) << 16;
+        let phase = (phase + frequency) % length;
+        registers[1] = phase as u8;
+        registers[3] = (phase >> 8) as u8;
+        registers[5] = (phase >> 16) as u8;
+
+        let sample = ((phase >> 16) as u8).wrapping_add(registers[6]);
+        let volume = registers[7] & 0x0F;
+        let nibble = (self.ram[(sample >> 1) as usize & 0x7F] >> ((sample & 0x01) * 4)) & 0x0F;
+        self.outputs[channel] = nibble * volume;
+    }
+}
+
+impl Default for N163 {
+    fn default() -> N163 {
+        N163::new()
+    }
+}
+
+impl Mapper for N163 {
+    fn power_on(&mut self, banks: &mut Banks) {
+        banks.set_prg_8k(3, banks.prg_banks() - 1);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        match address & 0xF800 {
+            0x4800 => {
+                let address = self.data_port();
+                self.ram[address] = data;
+            }
+            0x5000 => {
+                self.counter = (self.counter & 0x7F00) | data as u16;
+                self.pending = false;
+            }
+            0x5800 => {
+                self.counter = (self.counter & 0x00FF) | ((data & 0x7F) as u16) << 8;
+                self.counter_enabled = data & 0x80 != 0;
+                self.pending = false;
+            }
+            0x8000 | 0x8800 | 0x9000 | 0x9800 | 0xA000 | 0xA800 | 0xB000 | 0xB800 => {
+                banks.set_chr_1k(((address - 0x8000) >> 11) as usize, data as usize);
+            }
+            0xE000 => {
+                banks.set_prg_8k(0, (data & 0x3F) as usize);
+                self.sound_disabled = data & 0x40 != 0;
+            }
+            0xE800 => banks.set_prg_8k(1, (data & 0x3F) as usize),
+            0xF000 => banks.set_prg_8k(2, (data & 0x3F) as usize),
+            0xF800 => {
+                self.ram_address = data & 0x7F;
+                self.auto_increment = data & 0x80 != 0;
+            }
+            _ => {}
+        }
+    }
+
+    fn read(&mut self, _banks: &mut Banks, address: u16) -> Option<u8> {
+        match address & 0xF800 {
+            0x4800 => {
+                let address = self.data_port();
+                Some(self.ram[address])
+            }
+            0x5000 => Some(self.counter as u8),
+            0x5800 => Some((self.counter >> 8) as u8 | if self.counter_enabled { 0x80 } else { 0 }),
+            _ => None,
+        }
+    }
+
+    fn cycle(&mut self, _banks: &mut Banks) {
+        if self.counter_enabled && self.counter < 0x7FFF {
+            self.counter += 1;
+            if self.counter == 0x7FFF {
+                self.pending = true;
+            }
+        }
+
+        if self.sound_disabled {
+            return;
+        }
+        self.divider -= 1;
+        if self.divider == 0 {
+            self.divider = 15;
+            self.update_channel(self.channel);
+            self.channel = if self.channel <= 8 - self.channels() { 7 } else { self.channel - 1 };
+        }
+    }
+
+    fn irq(&self) -> bool {
+        self.pending
+    }
+
+    fn audio(&self) -> f32 {
+        if self.sound_disabled {
+            return 0.0;
+        }
+        let channels = self.channels();
+        let sum: u32 = self.outputs[8 - channels..].iter().map(|output| *output as u32).sum();
+        sum as f32 / channels as f32 / 225.0
+    }
+}
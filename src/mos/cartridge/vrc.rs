@@ -0,0 +1,449 @@
+use super::header::Mirroring;
+use super::mapper::{Banks, Mapper};
+
+/*
+Konami VRC4, VRC6 and VRC7
+
+The three share an irq counter clocked by M2. In cycle mode it counts every cpu cycle, in
+scanline mode a prescaler takes 3 off 341 every cpu cycle and clocks the counter when it
+runs out, one clock per 113.67 cpu cycles or one ntsc scanline. The counter counts up from
+the latch and raises irq when it overflows from $FF, reloading the latch.
+
+Register addresses depend on which cpu address lines a board wired to the chip's register
+select pins. NES 2.0 submappers say which, without one both wirings of a mapper number are
+decoded at once as there is no overlap between them.
+
+VRC6 audio adds two pulse channels and a saw channel. The FM synthesis of the VRC7 is not
+emulated, its audio registers are accepted and ignored.
+*/
+
+fn vrc_mirroring(data: u8) -> Mirroring {
+    match data & 0x03 {
+        0 => Mirroring::Vertical,
+        1 => Mirroring::Horizontal,
+        2 => Mirroring::SingleScreenLower,
+        _ => Mirroring::SingleScreenUpper,
+    }
+}
+
+pub struct VrcIrq {
+    latch: u8,
+    counter: u8,
+    prescaler: i16,
+    enabled: bool,
+    enable_after_ack: bool,
+    cycle_mode: bool,
+    pending: bool,
+}
+
+impl VrcIrq {
+    pub fn new() -> VrcIrq {
+        VrcIrq {
+            latch: 0,
+            counter: 0,
+            prescaler: 341,
+            enabled: false,
+            enable_after_ack: false,
+            cycle_mode: false,
+            pending: false,
+        }
+    }
+
+    pub fn write_latch(&mut self, data: u8) {
+        self.latch = data;
+    }
+
+    // the vrc4 loads the latch a nibble at a time
+    pub fn write_latch_nibble(&mut self, high: bool, data: u8) {
+        self.latch = if high { (self.latch & 0x0F) | (data << 4) } else { (self.latch & 0xF0) | (data & 0x0F) };
+    }
+
+    pub fn write_control(&mut self, data: u8) {
+        self.enable_after_ack = data & 0x01 != 0;
+        self.enabled = data & 0x02 != 0;
+        self.cycle_mode = data & 0x04 != 0;
+        self.pending = false;
+        if self.enabled {
+            self.counter = self.latch;
+            self.prescaler = 341;
+        }
+    }
+
+    pub fn acknowledge(&mut self) {
+        self.pending = false;
+        self.enabled = self.enable_after_ack;
+    }
+
+    pub fn clock(&mut self) {
+        if !self.enabled {
+            return;
+        }
+
+        if !self.cycle_mode {
+            self.prescaler -= 3;
+            if self.prescaler > 0 {
+                return;
+            }
+            self.prescaler += 341;
+        }
+
+        if self.counter == 0xFF {
+            self.counter = self.latch;
+            self.pending = true;
+        }
+        else {
+            self.counter += 1;
+        }
+    }
+
+    pub fn pending(&self) -> bool {
+        self.pending
+    }
+}
+
+impl Default for VrcIrq {
+    fn default() -> VrcIrq {
+        VrcIrq::new()
+    }
+}
+
+//====================================================
+// VRC4, mappers 21, 23 and 25
+//====================================================
+pub struct Vrc4 {
+    // cpu address lines wired to register selects 0 and 1
+    wiring: &'static [(u16, u16)],
+    prg: [u8; 2],
+    prg_swap: bool,
+    chr: [u16; 8],
+    irq: VrcIrq,
+}
+
+impl Vrc4 {
+    pub fn new(mapper: u16, submapper: u8) -> Vrc4 {
+        let wiring: &'static [(u16, u16)] = match (mapper, submapper) {
+            (21, 1) => &[(1, 2)],
+            (21, 2) => &[(6, 7)],
+            (21, _) => &[(1, 2), (6, 7)],
+            (23, 1) => &[(0, 1)],
+            (23, 2) => &[(2, 3)],
+            (23, _) => &[(0, 1), (2, 3)],
+            (25, 1) => &[(1, 0)],
+            (25, 2) => &[(3, 2)],
+            _ => &[(1, 0), (3, 2)],
+        };
+        Vrc4 { wiring, prg: [0; 2], prg_swap: false, chr: [0; 8], irq: VrcIrq::new() }
+    }
+
+    // register select pins as seen by the chip
+    fn select(&self, address: u16) -> u16 {
+        self.wiring.iter().fold(0, |select, &(a0, a1)| {
+            select | ((address >> a0) & 0x01) | (((address >> a1) & 0x01) << 1)
+        })
+    }
+
+    fn update(&self, banks: &mut Banks) {
+        let second_last = banks.prg_banks().saturating_sub(2);
+        if self.prg_swap {
+            banks.set_prg_8k(0, second_last);
+            banks.set_prg_8k(2, self.prg[0] as usize);
+        }
+        else {
+            banks.set_prg_8k(0, self.prg[0] as usize);
+            banks.set_prg_8k(2, second_last);
+        }
+        banks.set_prg_8k(1, self.prg[1] as usize);
+        banks.set_prg_8k(3, second_last + 1);
+
+        for (slot, bank) in self.chr.iter().enumerate() {
+            banks.set_chr_1k(slot, *bank as usize);
+        }
+    }
+}
+
+impl Mapper for Vrc4 {
+    fn power_on(&mut self, banks: &mut Banks) {
+        self.update(banks);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        let select = self.select(address);
+        match address & 0xF000 {
+            0x8000 => self.prg[0] = data & 0x1F,
+            0x9000 if select & 0x02 == 0 => banks.mirroring = vrc_mirroring(data),
+            0x9000 => self.prg_swap = data & 0x02 != 0,
+            0xA000 => self.prg[1] = data & 0x1F,
+            0xB000..=0xE000 => {
+                let slot = (((address & 0xF000) - 0xB000) >> 11) as usize | (select >> 1) as usize;
+                self.chr[slot] = if select & 0x01 == 0 {
+                    (self.chr[slot] & 0x1F0) | (data & 0x0F) as u16
+                }
+                else {
+                    (self.chr[slot] & 0x00F) | ((data & 0x1F) as u16) << 4
+                };
+            }
+            0xF000 => match select {
+                0 => self.irq.write_latch_nibble(false, data),
+                1 => self.irq.write_latch_nibble(true, data),
+                2 => self.irq.write_control(data),
+                _ => self.irq.acknowledge(),
+            },
+            _ => return,
+        }
+        self.update(banks);
+    }
+
+    fn cycle(&mut self, _banks: &mut Banks) {
+        self.irq.clock();
+    }
+
+    fn irq(&self) -> bool {
+        self.irq.pending()
+    }
+}
+
+//====================================================
+// VRC6, mappers 24 and 26
+//====================================================
+struct Vrc6Pulse {
+    volume: u8,
+    duty: u8,
+    constant: bool,
+    period: u16,
+    enabled: bool,
+    counter: u16,
+    step: u8,
+}
+
+impl Vrc6Pulse {
+    fn new() -> Vrc6Pulse {
+        Vrc6Pulse { volume: 0, duty: 0, constant: false, period: 0, enabled: false, counter: 0, step: 15 }
+    }
+
+    fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.volume = data & 0x0F;
+                self.duty = (data >> 4) & 0x07;
+                self.constant = data & 0x80 != 0;
+            }
+            1 => self.period = (self.period & 0x0F00) | data as u16,
+            _ => {
+                self.period = (self.period & 0x00FF) | ((data & 0x0F) as u16) << 8;
+                self.enabled = data & 0x80 != 0;
+                if !self.enabled {
+                    self.step = 15;
+                }
+            }
+        }
+    }
+
+    fn clock(&mut self, shift: u8) {
+        if !self.enabled {
+            return;
+        }
+        if self.counter == 0 {
+            self.counter = self.period >> shift;
+            self.step = self.step.wrapping_sub(1) & 0x0F;
+        }
+        else {
+            self.counter -= 1;
+        }
+    }
+
+    fn output(&self) -> u8 {
+        if self.enabled && (self.constant || self.step <= self.duty) { self.volume } else { 0 }
+    }
+}
+
+struct Vrc6Saw {
+    rate: u8,
+    period: u16,
+    enabled: bool,
+    counter: u16,
+    step: u8,
+    accumulator: u8,
+}
+
+impl Vrc6Saw {
+    fn new() -> Vrc6Saw {
+        Vrc6Saw { rate: 0, period: 0, enabled: false, counter: 0, step: 0, accumulator: 0 }
+    }
+
+    fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => self.rate = data & 0x3F,
+            1 => self.period = (self.period & 0x0F00) | data as u16,
+            _ => {
+                self.period = (self.period & 0x00FF) | ((data & 0x0F) as u16) << 8;
+                self.enabled = data & 0x80 != 0;
+                if !self.enabled {
+                    self.step = 0;
+                    self.accumulator = 0;
+                }
+            }
+        }
+    }
+
+    // the accumulator takes the rate on every other clock and is cleared on the 14th
+    fn clock(&mut self, shift: u8) {
+        if !self.enabled {
+            return;
+        }
+        if self.counter > 0 {
+            self.counter -= 1;
+            return;
+        }
+
+        self.counter = self.period >> shift;
+        self.step += 1;
+        if self.step == 14 {
+            self.step = 0;
+            self.accumulator = 0;
+        }
+        else if self.step & 0x01 == 0 {
+            self.accumulator = self.accumulator.wrapping_add(self.rate);
+        }
+    }
+
+    fn output(&self) -> u8 {
+        self.accumulator >> 3
+    }
+}
+
+pub struct Vrc6 {
+    // mapper 26 swaps the register select lines
+    swapped: bool,
+    chr: [u8; 8],
+    irq: VrcIrq,
+    pulse: [Vrc6Pulse; 2],
+    saw: Vrc6Saw,
+    halt: bool,
+    shift: u8,
+}
+
+impl Vrc6 {
+    pub fn new(swapped: bool) -> Vrc6 {
+        Vrc6 {
+            swapped,
+            chr: [0; 8],
+            irq: VrcIrq::new(),
+            pulse: [Vrc6Pulse::new(), Vrc6Pulse::new()],
+            saw: Vrc6Saw::new(),
+            halt: false,
+            shift: 0,
+        }
+    }
+}
+
+impl Mapper for Vrc6 {
+    fn power_on(&mut self, banks: &mut Banks) {
+        banks.set_prg_16k(0, 0);
+        banks.set_prg_8k(2, 0);
+        banks.set_prg_8k(3, banks.prg_banks() - 1);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        let select = if self.swapped { (address & 0x01) << 1 | (address & 0x02) >> 1 } else { address & 0x03 };
+        match (address & 0xF000, select) {
+            (0x8000, _) => banks.set_prg_16k(0, (data & 0x0F) as usize),
+            (0x9000, 3) => {
+                self.halt = data & 0x01 != 0;
+                self.shift = if data & 0x04 != 0 { 8 } else if data & 0x02 != 0 { 4 } else { 0 };
+            }
+            (0x9000, register) => self.pulse[0].write(register, data),
+            (0xA000, 3) => {}
+            (0xA000, register) => self.pulse[1].write(register, data),
+            (0xB000, 3) => {
+                banks.mirroring = vrc_mirroring(data >> 2);
+                banks.prg_ram_enabled = data & 0x80 != 0;
+            }
+            (0xB000, register) => self.saw.write(register, data),
+            (0xC000, _) => banks.set_prg_8k(2, (data & 0x1F) as usize),
+            (0xD000, register) | (0xE000, register) => {
+                let slot = (((address & 0xF000) - 0xD000) >> 10) as usize | register as usize;
+                self.chr[slot] = data;
+                banks.set_chr_1k(slot, data as usize);
+            }
+            (0xF000, 0) => self.irq.write_latch(data),
+            (0xF000, 1) => self.irq.write_control(data),
+            (0xF000, 2) => self.irq.acknowledge(),
+            _ => {}
+        }
+    }
+
+    fn cycle(&mut self, _banks: &mut Banks) {
+        self.irq.clock();
+        if !self.halt {
+            self.pulse[0].clock(self.shift);
+            self.pulse[1].clock(self.shift);
+            self.saw.clock(self.shift);
+        }
+    }
+
+    fn irq(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn audio(&self) -> f32 {
+        let sum = self.pulse[0].output() as f32 + self.pulse[1].output() as f32 + self.saw.output() as f32;
+        sum / 61.0
+    }
+}
+
+//====================================================
+// VRC7, mapper 85
+//====================================================
+pub struct Vrc7 {
+    // cpu address line wired to the upper register select
+    select_lines: u16,
+    irq: VrcIrq,
+}
+
+impl Vrc7 {
+    pub fn new(submapper: u8) -> Vrc7 {
+        let select_lines = match submapper {
+            1 => 0x08,
+            2 => 0x10,
+            _ => 0x18,
+        };
+        Vrc7 { select_lines, irq: VrcIrq::new() }
+    }
+}
+
+impl Mapper for Vrc7 {
+    fn power_on(&mut self, banks: &mut Banks) {
+        banks.set_prg_8k(3, banks.prg_banks() - 1);
+    }
+
+    fn write(&mut self, banks: &mut Banks, address: u16, data: u8) {
+        let upper = address & self.select_lines != 0;
+        match (address & 0xF000, upper) {
+            (0x8000, false) => banks.set_prg_8k(0, (data & 0x3F) as usize),
+            (0x8000, true) => banks.set_prg_8k(1, (data & 0x3F) as usize),
+            (0x9000, false) => banks.set_prg_8k(2, (data & 0x3F) as usize),
+            // fm synthesis registers
+            (0x9000, true) => {}
+            (0xA000..=0xD000, _) => {
+                let slot = (((address & 0xF000) - 0xA000) >> 11) as usize | upper as usize;
+                banks.set_chr_1k(slot, data as usize);
+            }
+            (0xE000, false) => {
+                banks.mirroring = vrc_mirroring(data);
+                banks.prg_ram_enabled = data & 0x80 != 0;
+            }
+            (0xE000, true) => self.irq.write_latch(data),
+            (0xF000, false) => self.irq.write_control(data),
+            (0xF000, true) => self.irq.acknowledge(),
+            _ => {}
+        }
+    }
+
+    fn cycle(&mut self, _banks: &mut Banks) {
+        self.irq.clock();
+    }
+
+    fn irq(&self) -> bool {
+        self.irq.pending()
+    }
+}
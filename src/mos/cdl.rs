@@ -0,0 +1,246 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use super::{Ctrl, Pinout};
+use super::bus::{Bus, Sequence};
+use super::cartridge::Cartridge;
+use super::disasm::instruction_len;
+use super::dispatch::{AddressingMode, Operation, OPCODES};
+
+/*
+Code/data logger
+
+Records how the cpu used every byte of prg rom in the FCEUX .cdl format, one flag byte per
+byte of prg rom followed by one per byte of chr rom.
+
+    prg  xPdcAADC
+         C   executed as an opcode or fetched as an operand
+         D   read as data
+         AA  the 8k window the byte was last used through, 0 for $8000 up to 3 for $E000
+         c   jumped to through JMP ($xxxx)
+         d   read through an (indirect,X) or (indirect),Y pointer
+         P   played as a dmc sample
+    chr  xxxxxxRR, rendered and read through $2007, left clear as there is no ppu here
+
+CdlBus sits between the cpu and the system bus and sees every access of the cycle accurate
+path. An opcode fetch has SYNC high, the bytes after it up to the length of the instruction
+are its operand. The other reads of an instruction that reads memory are dummy reads and
+pointer fetches ending with the read of the effective address, which is the one logged as
+data. The pointer bytes of JMP ($xxxx) and the interrupt vectors are data too, everything
+else an instruction reads, stack and dummy reads, is not logged. Nothing is logged after an
+instruction first writes, which keeps the dummy read of an interrupt sequence out.
+
+Cpu addresses are turned into prg rom offsets by Bus::prg_rom_offset, a Cartridge answers
+with its banks as they are at the access. Dmc sample fetches do not go through the cpu, the
+owner servicing Apu::dmc_request logs them with log_pcm.
+*/
+
+pub const CODE: u8 = 0x01;
+pub const DATA: u8 = 0x02;
+pub const INDIRECT_CODE: u8 = 0x10;
+pub const INDIRECT_DATA: u8 = 0x20;
+pub const PCM: u8 = 0x40;
+// the AA bits
+const WINDOW: u8 = 0x0C;
+
+const VECTORS: u16 = 0xFFFA;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CodeDataLog {
+    prg: Vec<u8>,
+    chr: Vec<u8>,
+}
+
+impl CodeDataLog {
+    pub fn new(prg_len: usize, chr_len: usize) -> CodeDataLog {
+        CodeDataLog { prg: vec![0; prg_len], chr: vec![0; chr_len] }
+    }
+
+    // chr ram is not logged
+    pub fn for_cartridge(cartridge: &Cartridge) -> CodeDataLog {
+        CodeDataLog::new(cartridge.prg_rom().len(), cartridge.header().chr_rom_size)
+    }
+
+    pub fn prg(&self) -> &[u8] {
+        &self.prg
+    }
+
+    pub fn chr(&self) -> &[u8] {
+        &self.chr
+    }
+
+    // adds flags to a prg byte used through the cpu address, the window bits are replaced
+    pub fn mark_prg(&mut self, offset: usize, address: u16, flags: u8) {
+        if let Some(byte) = self.prg.get_mut(offset) {
+            let window = if address >= 0x8000 { ((address >> 11) as u8) & WINDOW } else { *byte & WINDOW };
+            *byte = (*byte & !WINDOW) | flags | window;
+        }
+    }
+
+    // prg bytes with any of the flags
+    pub fn count(&self, flags: u8) -> usize {
+        self.prg.iter().filter(|byte| **byte & flags != 0).count()
+    }
+
+    pub fn clear(&mut self) {
+        self.prg.iter_mut().chain(self.chr.iter_mut()).for_each(|byte| *byte = 0);
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.prg)?;
+        writer.write_all(&self.chr)?;
+        writer.flush()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_to(BufWriter::new(File::create(path)?))
+    }
+}
+
+pub struct CdlBus<B> {
+    bus: B,
+    log: CodeDataLog,
+    // the instruction being executed
+    start: u16,
+    len: u16,
+    operation: Operation,
+    mode: AddressingMode,
+    // the last read that may be the effective address and the one before it, the effective
+    // address is logged when the instruction ends
+    pending: Option<(usize, u16)>,
+    previous: Option<(usize, u16)>,
+    // reads since the operand
+    reads: u8,
+    // the instruction is a JMP ($xxxx), the next opcode is indirect code
+    jump_indirect: bool,
+}
+
+impl<B: Bus> CdlBus<B> {
+    pub fn new(bus: B, log: CodeDataLog) -> CdlBus<B> {
+        CdlBus {
+            bus,
+            log,
+            start: 0,
+            len: 0,
+            operation: Operation::Implied,
+            mode: AddressingMode::Implied,
+            pending: None,
+            previous: None,
+            reads: 0,
+            jump_indirect: false,
+        }
+    }
+
+    pub fn bus(&self) -> &B {
+        &self.bus
+    }
+
+    pub fn bus_mut(&mut self) -> &mut B {
+        &mut self.bus
+    }
+
+    pub fn log(&self) -> &CodeDataLog {
+        &self.log
+    }
+
+    pub fn log_mut(&mut self) -> &mut CodeDataLog {
+        &mut self.log
+    }
+
+    pub fn into_inner(self) -> (B, CodeDataLog) {
+        (self.bus, self.log)
+    }
+
+    // a dmc sample fetch, made by whoever services Apu::dmc_request
+    pub fn log_pcm(&mut self, address: u16) {
+        self.mark(address, PCM);
+    }
+
+    fn mark(&mut self, address: u16, flags: u8) {
+        if let Some(offset) = self.bus.prg_rom_offset(address) {
+            self.log.mark_prg(offset, address, flags);
+        }
+    }
+
+    // the effective address read, if the instruction had one
+    fn end_instruction(&mut self) {
+        if let Some((offset, address)) = self.pending.take() {
+            let indirect = match self.mode {
+                AddressingMode::IndirectX | AddressingMode::IndirectY => INDIRECT_DATA,
+                _ => 0,
+            };
+            self.log.mark_prg(offset, address, DATA | indirect);
+        }
+    }
+
+    fn logged_read(&mut self, pinout: Pinout) {
+        let address = pinout.address;
+        if pinout.ctrl.contains(Ctrl::SYNC) {
+            self.end_instruction();
+            let opcode = &OPCODES[pinout.data as usize];
+            let indirect = if self.jump_indirect { INDIRECT_CODE } else { 0 };
+            self.mark(address, CODE | indirect);
+            self.start = address;
+            self.len = instruction_len(opcode.mode);
+            self.operation = opcode.operation;
+            self.mode = opcode.mode;
+            self.previous = None;
+            self.reads = 0;
+            self.jump_indirect = opcode.operation == Operation::Jmp && opcode.mode == AddressingMode::Indirect;
+            return;
+        }
+
+        if address.wrapping_sub(self.start) < self.len {
+            self.mark(address, CODE);
+            return;
+        }
+        self.reads = self.reads.saturating_add(1);
+        if address >= VECTORS || (self.jump_indirect && self.reads <= 2) {
+            self.mark(address, DATA);
+        }
+        else if self.operation == Operation::Read || self.operation == Operation::Modify {
+            self.previous = self.pending;
+            self.pending = self.bus.prg_rom_offset(address).map(|offset| (offset, address));
+        }
+    }
+}
+
+impl<B: Bus> Bus for CdlBus<B> {
+    fn read(&mut self, pinout: Pinout) -> Pinout {
+        let pinout = self.bus.read(pinout);
+        self.logged_read(pinout);
+        pinout
+    }
+
+    fn write(&mut self, pinout: Pinout) -> Pinout {
+        // instructions that read memory and write do it after the read, an instruction that
+        // only reads is writing because an interrupt is pushing and its last read was the
+        // dummy fetch of the interrupt
+        if self.operation == Operation::Read {
+            self.pending = self.previous.take();
+        }
+        self.end_instruction();
+        self.operation = Operation::Implied;
+        self.bus.write(pinout)
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        self.bus.peek(address)
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        self.bus.poke(address, data)
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        self.bus.prg_rom_offset(address)
+    }
+
+    fn cycle(&mut self, pinout: Pinout) -> Pinout {
+        self.bus.cycle(pinout)
+    }
+
+    fn vector(&mut self, sequence: Sequence) {
+        self.bus.vector(sequence)
+    }
+}
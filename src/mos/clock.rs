@@ -0,0 +1,157 @@
+/*
+Master clock
+
+Every chip in a console is driven from one crystal and steps every n master cycles. The cpu
+divides by 12 on a 2A03, 16 on a 2A07 and 15 on the Dendy clones, the ppu by 4 or 5. The
+scheduler keeps a master cycle count and hands out the components due on each master cycle,
+so the chips stay in phase with each other and a run is deterministic.
+
+A component with divider d and phase p is clocked on master cycles p, p + d, p + 2d ... When
+several are due on the same master cycle they are clocked in the order they were added.
+*/
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Region {
+    #[default]
+    Ntsc,
+    Pal,
+    Dendy,
+}
+
+impl Region {
+    // crystal frequency in hz
+    pub fn master_clock(&self) -> u32 {
+        match *self {
+            Region::Ntsc => 21_477_272,
+            Region::Pal => 26_601_712,
+            Region::Dendy => 26_601_712,
+        }
+    }
+
+    pub fn cpu_divider(&self) -> u32 {
+        match *self {
+            Region::Ntsc => 12,
+            Region::Pal => 16,
+            Region::Dendy => 15,
+        }
+    }
+
+    pub fn ppu_divider(&self) -> u32 {
+        match *self {
+            Region::Ntsc => 4,
+            Region::Pal => 5,
+            Region::Dendy => 5,
+        }
+    }
+
+    // cpu cycles per second, rounded down
+    pub fn cpu_clock(&self) -> u32 {
+        self.master_clock() / self.cpu_divider()
+    }
+}
+
+// index of a component in the order it was added
+pub type ComponentId = usize;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Component {
+    divider: u32,
+    // master cycle of the next clock
+    next: u64,
+    cycles: u64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Scheduler {
+    master: u64,
+    components: Vec<Component>,
+}
+
+impl Scheduler {
+    pub fn new() -> Scheduler {
+        Scheduler {
+            master: 0,
+            components: Vec::new(),
+        }
+    }
+
+    // cpu added first at phase 0 then the ppu, as wired on the region's console
+    pub fn for_region(region: Region) -> (Scheduler, ComponentId, ComponentId) {
+        let mut scheduler = Scheduler::new();
+        let cpu = scheduler.add(region.cpu_divider(), 0);
+        let ppu = scheduler.add(region.ppu_divider(), 0);
+        (scheduler, cpu, ppu)
+    }
+
+    // the phase is in master cycles from the current one and is taken modulo the divider
+    pub fn add(&mut self, divider: u32, phase: u32) -> ComponentId {
+        assert!(divider > 0, "a divider of 0 never clocks");
+        self.components.push(Component {
+            divider,
+            next: self.master + u64::from(phase % divider),
+            cycles: 0,
+        });
+        self.components.len() - 1
+    }
+
+    // move the next clock of a component, keeps the divider
+    pub fn set_phase(&mut self, id: ComponentId, phase: u32) {
+        let component = &mut self.components[id];
+        component.next = self.master + u64::from(phase % component.divider);
+    }
+
+    pub fn divider(&self, id: ComponentId) -> u32 {
+        self.components[id].divider
+    }
+
+    // the current master cycle, that of the last clock handed out or where a run stopped
+    pub fn master_cycles(&self) -> u64 {
+        self.master
+    }
+
+    // times a component has been clocked
+    pub fn cycles(&self, id: ComponentId) -> u64 {
+        self.components[id].cycles
+    }
+
+    // the next component due, advancing the master clock to it
+    pub fn next_due(&mut self) -> Option<ComponentId> {
+        // first added wins a tie
+        let mut due: Option<ComponentId> = None;
+        for (id, component) in self.components.iter().enumerate() {
+            match due {
+                Some(best) if self.components[best].next <= component.next => {}
+                _ => due = Some(id),
+            }
+        }
+
+        let id = due?;
+        let component = &mut self.components[id];
+        self.master = component.next;
+        component.next += u64::from(component.divider);
+        component.cycles += 1;
+        Some(id)
+    }
+
+    // clock every component due before a master cycle in order, the master clock is left on it
+    pub fn run_until<F: FnMut(ComponentId)>(&mut self, master: u64, mut clock: F) {
+        while self.components.iter().any(|c| c.next < master) {
+            if let Some(id) = self.next_due() {
+                clock(id);
+            }
+        }
+        if self.master < master {
+            self.master = master;
+        }
+    }
+
+    pub fn run_for<F: FnMut(ComponentId)>(&mut self, master_cycles: u64, clock: F) {
+        self.run_until(self.master + master_cycles, clock);
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Scheduler {
+        Scheduler::new()
+    }
+}
@@ -1,3 +1,5 @@
+use super::unstable::UnstableOpcodeConfig;
+
 
 #[derive(PartialEq, Debug, Clone, Copy)]
 pub struct FlagsRegister {
@@ -82,7 +84,7 @@ impl ProgramCounter {
     #[inline]
     pub fn increment(&mut self) {
         let mut pc: u16 = u16::from(*self);
-        pc += 1;
+        pc = pc.wrapping_add(1);
         *self = ProgramCounter::from(pc);
     }
 }
@@ -143,15 +145,47 @@ impl std::convert::From<u16> for InstructionRegister {
     }
 }
 
+/*
+The sequence run by the BRK slot. BRK, IRQ and NMI share the same seven cycles and only differ
+in the B flag pushed and the vector fetched. An NMI detected by the end of the fourth cycle
+hijacks a BRK or IRQ already in progress, the B flag of the original is still pushed but the
+vector is fetched from 0xFFFA and the NMI is treated as handled.
+*/
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum InterruptState {
+    // no interrupt, the sequence is a BRK instruction or a reset
     None,
+    // BRK that jumped through the nmi vector, B is set on the pushed flags
     BrkHijack,
+    // IRQ that jumped through the nmi vector, B is clear on the pushed flags
     IrqHijack,
     Irq,
     Nmi,
 }
 
+// interrupt lines as latched at the end of a cycle, irq is only latched while the i flag is clear
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct InterruptLatch {
+    pub irq: bool,
+    pub nmi: bool,
+}
+
+impl InterruptLatch {
+    pub fn new() -> InterruptLatch {
+        InterruptLatch {
+            irq: false,
+            nmi: false,
+        }
+    }
+}
+
+// a cold reset follows power on, a warm reset is caused by the RES pin and keeps the registers
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ResetKind {
+    Cold,
+    Warm,
+}
+
 /*
 Holds intermediate data during cycle operations, the 6502 would have used internal registers
 or the ALU to hold this data
@@ -174,6 +208,8 @@ pub struct OpState {
     pub branch_taken: bool,
     // data input latch
     pub dl: u8,
+    // rdy stalled the cycle before an indexed store
+    pub rdy_stall: bool,
 }
 
 impl OpState {
@@ -190,6 +226,7 @@ impl OpState {
             offset_neg: false,
             branch_taken: false,
             dl: 0,
+            rdy_stall: false,
         }
     }
 
@@ -205,6 +242,7 @@ impl OpState {
         self.offset_neg = false;
         self.branch_taken = false;
         self.dl = 0;
+        self.rdy_stall = false;
     }
 }
 
@@ -223,7 +261,16 @@ pub struct Context
     pub ops: OpState,
     pub ints: InterruptState,
     pub nmi_detected: bool,
+    // nmi pin as sampled on the last phi2, true while low
+    pub nmi_line: bool,
+    // latched at the end of the last cycle and the cycle before it
+    pub latch: InterruptLatch,
+    pub prev_latch: InterruptLatch,
     pub first_cycle: bool,
+    // a KIL opcode locked up the cpu, cleared by reset
+    pub jammed: bool,
+    pub reset: ResetKind,
+    pub unstable: UnstableOpcodeConfig,
 }
 
 impl Context
@@ -243,21 +290,42 @@ impl Context
             ops: OpState::new(),
             ints: InterruptState::None,
             nmi_detected: false,
+            nmi_line: false,
+            latch: InterruptLatch::new(),
+            prev_latch: InterruptLatch::new(),
             first_cycle: false,
+            jammed: false,
+            reset: ResetKind::Cold,
+            unstable: UnstableOpcodeConfig::default(),
         }
     }
 
-    pub fn reset(&mut self) {
-        self.a = 0;
-        self.x = 0;
-        self.y = 0;
-        self.sp = 0;
-        self.cycle = 0;
-        self.ir = InstructionRegister::new();
-        self.p = FlagsRegister::from(0);
-        self.ops = OpState::new();
-        self.ints = InterruptState::None;
-        self.nmi_detected = false;
+    /*
+    End of cycle, irq and nmi are true when the pins are held low.
+
+    The pins are sampled during phi2, the second half of the cycle. Nmi goes through an edge
+    detector, the flag is only set when the pin was high on the previous phi2 and is low on
+    this one, so holding it low triggers once and it must be released before it can trigger
+    again. The flag stays set until the nmi is handled. Irq is level detected and is only
+    seen while the pin is held.
+
+    The internal signals follow during phi1 of the next cycle, they are what the polls of
+    the next cycle see and are kept in latch.
+    */
+    #[inline]
+    pub fn latch_interrupts(&mut self, irq: bool, nmi: bool) {
+        // phi2
+        if nmi == true && self.nmi_line == false {
+            self.nmi_detected = true;
+        }
+        self.nmi_line = nmi;
+
+        // phi1
+        self.prev_latch = self.latch;
+        self.latch = InterruptLatch {
+            irq: irq == true && self.p.interrupt_disable == false,
+            nmi: self.nmi_detected,
+        };
     }
 }
 
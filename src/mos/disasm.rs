@@ -0,0 +1,137 @@
+use super::bus::Bus;
+use super::dispatch::{AddressingMode, OPCODES};
+use super::symbols::SymbolTable;
+
+/*
+Disassembler
+
+Reads memory with Bus::peek so it can be pointed at a running system without disturbing it.
+An instruction whose bytes cannot all be peeked is not disassembled. Unofficial opcodes keep
+the *XXX* mnemonics of the opcode table.
+
+The _symbols variants show addresses in operands and branch targets as label+offset from a
+symbol table, immediates stay numbers, and describe each instruction's own address the way
+the trace does, "label+offset file:line" with whatever the table knows. The bank is the prg
+rom bank being disassembled, see SymbolTable.
+*/
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Disassembled {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    pub mnemonic: &'static str,
+    // operand as written in assembly, empty for implied instructions
+    pub operand: String,
+    // SymbolTable::describe of the address, empty without symbols
+    pub description: String,
+}
+
+impl Disassembled {
+    pub fn len(&self) -> u16 {
+        self.bytes.len() as u16
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    // address of the instruction that follows
+    pub fn next(&self) -> u16 {
+        self.address.wrapping_add(self.len())
+    }
+}
+
+impl std::fmt::Display for Disassembled {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.operand.is_empty() {
+            write!(f, "{}", self.mnemonic)?;
+        }
+        else {
+            write!(f, "{} {}", self.mnemonic, self.operand)?;
+        }
+        if !self.description.is_empty() {
+            write!(f, " ; {}", self.description)?;
+        }
+        Ok(())
+    }
+}
+
+// bytes taken by an instruction in this addressing mode, opcode included
+pub fn instruction_len(mode: AddressingMode) -> u16 {
+    match mode {
+        AddressingMode::Implied | AddressingMode::Accumulator => 1,
+        AddressingMode::Immediate | AddressingMode::ZeroPage | AddressingMode::ZeroPageX | AddressingMode::ZeroPageY
+            | AddressingMode::IndirectX | AddressingMode::IndirectY | AddressingMode::Relative => 2,
+        AddressingMode::Absolute | AddressingMode::AbsoluteX | AddressingMode::AbsoluteY | AddressingMode::Indirect => 3,
+    }
+}
+
+pub fn disassemble_one<B: Bus>(bus: &B, address: u16) -> Option<Disassembled> {
+    decode(bus, address, &|address, digits| format!("${:01$X}", address, digits))
+}
+
+pub fn disassemble_one_symbols<B: Bus>(bus: &B, address: u16, symbols: &SymbolTable, bank: Option<u32>) -> Option<Disassembled> {
+    let mut line = decode(bus, address, &|address, digits| {
+        symbols.label(address, bank).unwrap_or_else(|| format!("${:01$X}", address, digits))
+    })?;
+    line.description = symbols.describe(address, bank);
+    Some(line)
+}
+
+// name is given an operand address and the hex digits it is written with
+fn decode<B: Bus>(bus: &B, address: u16, name: &dyn Fn(u16, usize) -> String) -> Option<Disassembled> {
+    let opcode = bus.peek(address)?;
+    let descriptor = &OPCODES[opcode as usize];
+    let len = instruction_len(descriptor.mode);
+
+    let mut bytes = vec![opcode];
+    for offset in 1..len {
+        bytes.push(bus.peek(address.wrapping_add(offset))?);
+    }
+
+    let byte = bytes.get(1).copied().unwrap_or(0);
+    let word = (bytes.get(2).copied().unwrap_or(0) as u16) << 8 | byte as u16;
+    let zero_page = name(byte as u16, 2);
+    let absolute = name(word, 4);
+    let operand = match descriptor.mode {
+        AddressingMode::Implied => String::new(),
+        AddressingMode::Accumulator => String::from("A"),
+        AddressingMode::Immediate => format!("#${:02X}", byte),
+        AddressingMode::ZeroPage => zero_page,
+        AddressingMode::ZeroPageX => format!("{},X", zero_page),
+        AddressingMode::ZeroPageY => format!("{},Y", zero_page),
+        AddressingMode::Absolute => absolute,
+        AddressingMode::AbsoluteX => format!("{},X", absolute),
+        AddressingMode::AbsoluteY => format!("{},Y", absolute),
+        AddressingMode::Indirect => format!("({})", absolute),
+        AddressingMode::IndirectX => format!("({},X)", zero_page),
+        AddressingMode::IndirectY => format!("({}),Y", zero_page),
+        // shown as the branch target
+        AddressingMode::Relative => name(address.wrapping_add(2).wrapping_add(byte as i8 as u16), 4),
+    };
+
+    Some(Disassembled { address, bytes, mnemonic: descriptor.mnemonic, operand, description: String::new() })
+}
+
+// up to count instructions from address, stops early at memory that cannot be peeked
+pub fn disassemble<B: Bus>(bus: &B, address: u16, count: usize) -> Vec<Disassembled> {
+    disassemble_with(address, count, |address| disassemble_one(bus, address))
+}
+
+pub fn disassemble_symbols<B: Bus>(bus: &B, address: u16, count: usize, symbols: &SymbolTable, bank: Option<u32>) -> Vec<Disassembled> {
+    disassemble_with(address, count, |address| disassemble_one_symbols(bus, address, symbols, bank))
+}
+
+fn disassemble_with<F: Fn(u16) -> Option<Disassembled>>(mut address: u16, count: usize, one: F) -> Vec<Disassembled> {
+    let mut lines = Vec::with_capacity(count);
+    for _ in 0..count {
+        match one(address) {
+            Some(line) => {
+                address = line.next();
+                lines.push(line);
+            }
+            None => break,
+        }
+    }
+    lines
+}
@@ -0,0 +1,466 @@
+use std::marker::PhantomData;
+
+use super::Pinout;
+use super::core::Context;
+use super::bus::Bus;
+use super::operations::*;
+use super::instructions::*;
+
+/*
+Table driven opcode dispatch
+
+Every opcode is described once by its mnemonic, addressing mode and the micro-op
+sequence that implements it. The sequence for an opcode is indexed by the timing
+step held in the instruction register, so a tick is a single table lookup instead
+of a match over every (opcode, timing step) pair.
+
+The tables only depend on the bus type, any cpu variant built on the same core
+shares them.
+*/
+
+pub type MicroOp<B> = fn(&mut Context, &mut B, Pinout) -> Pinout;
+
+// the reset sequence lives in the brk opcode slot starting at this timing step
+pub const RESET_TM: u8 = 0x10;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AddressingMode {
+    Implied,
+    Accumulator,
+    Immediate,
+    ZeroPage,
+    ZeroPageX,
+    ZeroPageY,
+    Absolute,
+    AbsoluteX,
+    AbsoluteY,
+    Indirect,
+    IndirectX,
+    IndirectY,
+    Relative,
+}
+
+// how an opcode uses its operand, together with the addressing mode this
+// determines the bus accesses and cycle count of the instruction
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Operation {
+    Implied,
+    Read,
+    Store,
+    Modify,
+    Branch,
+    Brk,
+    Jsr,
+    Rts,
+    Rti,
+    Jmp,
+    Php,
+    Pha,
+    Plp,
+    Pla,
+    Jam,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Opcode {
+    pub mnemonic: &'static str,
+    pub mode: AddressingMode,
+    pub operation: Operation,
+    pub execute: fn(&mut Context),
+}
+
+// micro-op tables, one set per bus type
+pub struct OpTable<B> {
+    _bus: PhantomData<B>,
+}
+
+impl<B: Bus + 'static> OpTable<B> {
+    pub const RESET: &'static [MicroOp<B>] = &[rst_c0::<B>, rst_c1::<B>, rst_c2::<B>, rst_c3::<B>, rst_c4::<B>,
+        rst_c5::<B>, rst_c6::<B>, rst_c7::<B>, rst_c8::<B>];
+
+    #[inline]
+    pub fn micro_op(opcode: u8, tm: u8) -> Option<MicroOp<B>> {
+        if opcode == 0x00 && tm >= RESET_TM {
+            Self::RESET.get((tm - RESET_TM) as usize).copied()
+        }
+        else {
+            Self::SEQUENCES[opcode as usize].get(tm as usize).copied()
+        }
+    }
+}
+
+//====================================================
+// micro-op sequences per addressing mode
+//====================================================
+macro_rules! sequence {
+    (absolute_modify, $B:ident, $T:ident) => { &[absolute_modify_c0::<$B>, absolute_modify_c1::<$B>, absolute_modify_c2::<$B>, absolute_modify_c3::<$B, $T>, absolute_modify_c4::<$B>, absolute_modify_c5::<$B>] };
+    (absolute_read, $B:ident, $T:ident) => { &[absolute_read_c0::<$B>, absolute_read_c1::<$B>, absolute_read_c2::<$B>, absolute_read_c3::<$B, $T>] };
+    (absolute_store, $B:ident, $T:ident) => { &[absolute_store_c0::<$B>, absolute_store_c1::<$B>, absolute_store_c2::<$B, $T>, absolute_store_c3::<$B>] };
+    (absolute_x_modify, $B:ident, $T:ident) => { &[absolute_x_modify_c0::<$B>, absolute_x_modify_c1::<$B>, absolute_x_modify_c2::<$B>, absolute_x_modify_c3::<$B>, absolute_x_modify_c4::<$B, $T>, absolute_x_modify_c5::<$B>, absolute_x_modify_c6::<$B>] };
+    (absolute_x_read, $B:ident, $T:ident) => { &[absolute_x_read_c0::<$B>, absolute_x_read_c1::<$B>, absolute_x_read_c2::<$B>, absolute_x_read_c3::<$B>, absolute_x_read_c4::<$B, $T>] };
+    (absolute_x_store, $B:ident, $T:ident) => { &[absolute_x_store_c0::<$B>, absolute_x_store_c1::<$B>, absolute_x_store_c2::<$B>, absolute_x_store_c3::<$B, $T>, absolute_x_store_c4::<$B>] };
+    (absolute_y_read, $B:ident, $T:ident) => { &[absolute_y_read_c0::<$B>, absolute_y_read_c1::<$B>, absolute_y_read_c2::<$B>, absolute_y_read_c3::<$B>, absolute_y_read_c4::<$B, $T>] };
+    (absolute_y_store, $B:ident, $T:ident) => { &[absolute_y_store_c0::<$B>, absolute_y_store_c1::<$B>, absolute_y_store_c2::<$B>, absolute_y_store_c3::<$B, $T>, absolute_y_store_c4::<$B>] };
+    (branch, $B:ident, $T:ident) => { &[branch_c0::<$B>, branch_c1::<$B, $T>, branch_c2::<$B>, branch_c3::<$B>] };
+    (brk, $B:ident) => { &[brk_c0::<$B>, brk_c1::<$B>, brk_c2::<$B>, brk_c3::<$B>, brk_c4::<$B>, brk_c5::<$B>, brk_c6::<$B>] };
+    (immediate_read, $B:ident, $T:ident) => { &[immediate_read_c0::<$B>, immediate_read_c1::<$B, $T>] };
+    (indirect_x_read, $B:ident, $T:ident) => { &[indirect_x_read_c0::<$B>, indirect_x_read_c1::<$B>, indirect_x_read_c2::<$B>, indirect_x_read_c3::<$B>, indirect_x_read_c4::<$B>, indirect_x_read_c5::<$B, $T>] };
+    (indirect_x_store, $B:ident, $T:ident) => { &[indirect_x_store_c0::<$B>, indirect_x_store_c1::<$B>, indirect_x_store_c2::<$B>, indirect_x_store_c3::<$B>, indirect_x_store_c4::<$B, $T>, indirect_x_store_c5::<$B>] };
+    (indirect_y_read, $B:ident, $T:ident) => { &[indirect_y_read_c0::<$B>, indirect_y_read_c1::<$B>, indirect_y_read_c2::<$B>, indirect_y_read_c3::<$B>, indirect_y_read_c4::<$B>, indirect_y_read_c5::<$B, $T>] };
+    (indirect_y_store, $B:ident, $T:ident) => { &[indirect_y_store_c0::<$B>, indirect_y_store_c1::<$B>, indirect_y_store_c2::<$B>, indirect_y_store_c3::<$B>, indirect_y_store_c4::<$B, $T>, indirect_y_store_c5::<$B>] };
+    (jam, $B:ident) => { &[jam_c0::<$B>, jam_c1::<$B>, jam_c2::<$B>, jam_c3::<$B>, jam_c4::<$B>] };
+    (jmp_absolute, $B:ident) => { &[jmp_absolute_c0::<$B>, jmp_absolute_c1::<$B>, jmp_absolute_c2::<$B>] };
+    (jmp_indirect, $B:ident) => { &[jmp_indirect_c0::<$B>, jmp_indirect_c1::<$B>, jmp_indirect_c2::<$B>, jmp_indirect_c3::<$B>, jmp_indirect_c4::<$B>] };
+    (jsr, $B:ident) => { &[jsr_c0::<$B>, jsr_c1::<$B>, jsr_c2::<$B>, jsr_c3::<$B>, jsr_c4::<$B>, jsr_c5::<$B>] };
+    (pha, $B:ident) => { &[pha_c0::<$B>, pha_c1::<$B>, pha_c2::<$B>] };
+    (php, $B:ident) => { &[php_c0::<$B>, php_c1::<$B>, php_c2::<$B>] };
+    (pla, $B:ident) => { &[pla_c0::<$B>, pla_c1::<$B>, pla_c2::<$B>, pla_c3::<$B>] };
+    (plp, $B:ident) => { &[plp_c0::<$B>, plp_c1::<$B>, plp_c2::<$B>, plp_c3::<$B>] };
+    (rti, $B:ident) => { &[rti_c0::<$B>, rti_c1::<$B>, rti_c2::<$B>, rti_c3::<$B>, rti_c4::<$B>, rti_c5::<$B>] };
+    (rts, $B:ident) => { &[rts_c0::<$B>, rts_c1::<$B>, rts_c2::<$B>, rts_c3::<$B>, rts_c4::<$B>, rts_c5::<$B>] };
+    (single_byte, $B:ident, $T:ident) => { &[single_byte_c0::<$B>, single_byte_c1::<$B, $T>] };
+    (undoc_absolute_y, $B:ident, $T:ident) => { &[undoc_absolute_y_c0::<$B>, undoc_absolute_y_c1::<$B>, undoc_absolute_y_c2::<$B>, undoc_absolute_y_c3::<$B>, undoc_absolute_y_c4::<$B, $T>, undoc_absolute_y_c5::<$B>, undoc_absolute_y_c6::<$B>] };
+    (undoc_indirect_x, $B:ident, $T:ident) => { &[undoc_indirect_x_c0::<$B>, undoc_indirect_x_c1::<$B>, undoc_indirect_x_c2::<$B>, undoc_indirect_x_c3::<$B>, undoc_indirect_x_c4::<$B>, undoc_indirect_x_c5::<$B, $T>, undoc_indirect_x_c6::<$B>, undoc_indirect_x_c7::<$B>] };
+    (undoc_indirect_y, $B:ident, $T:ident) => { &[undoc_indirect_y_c0::<$B>, undoc_indirect_y_c1::<$B>, undoc_indirect_y_c2::<$B>, undoc_indirect_y_c3::<$B>, undoc_indirect_y_c4::<$B>, undoc_indirect_y_c5::<$B, $T>, undoc_indirect_y_c6::<$B>, undoc_indirect_y_c7::<$B>] };
+    (zeropage_modify, $B:ident, $T:ident) => { &[zeropage_modify_c0::<$B>, zeropage_modify_c1::<$B>, zeropage_modify_c2::<$B, $T>, zeropage_modify_c3::<$B>, zeropage_modify_c4::<$B>] };
+    (zeropage_read, $B:ident, $T:ident) => { &[zeropage_read_c0::<$B>, zeropage_read_c1::<$B>, zeropage_read_c2::<$B, $T>] };
+    (zeropage_store, $B:ident, $T:ident) => { &[zeropage_store_c0::<$B>, zeropage_store_c1::<$B, $T>, zeropage_store_c2::<$B>] };
+    (zeropage_x_modify, $B:ident, $T:ident) => { &[zeropage_x_modify_c0::<$B>, zeropage_x_modify_c1::<$B>, zeropage_x_modify_c2::<$B>, zeropage_x_modify_c3::<$B, $T>, zeropage_x_modify_c4::<$B>, zeropage_x_modify_c5::<$B>] };
+    (zeropage_x_read, $B:ident, $T:ident) => { &[zeropage_x_read_c0::<$B>, zeropage_x_read_c1::<$B>, zeropage_x_read_c2::<$B>, zeropage_x_read_c3::<$B, $T>] };
+    (zeropage_x_store, $B:ident, $T:ident) => { &[zeropage_x_store_c0::<$B>, zeropage_x_store_c1::<$B>, zeropage_x_store_c2::<$B, $T>, zeropage_x_store_c3::<$B>] };
+    (zeropage_y_read, $B:ident, $T:ident) => { &[zeropage_y_read_c0::<$B>, zeropage_y_read_c1::<$B>, zeropage_y_read_c2::<$B>, zeropage_y_read_c3::<$B, $T>] };
+    (zeropage_y_store, $B:ident, $T:ident) => { &[zeropage_y_store_c0::<$B>, zeropage_y_store_c1::<$B>, zeropage_y_store_c2::<$B, $T>, zeropage_y_store_c3::<$B>] };
+}
+
+macro_rules! operation {
+    (single_byte) => { Operation::Implied };
+    (immediate_read) => { Operation::Read };
+    (zeropage_read) => { Operation::Read };
+    (zeropage_x_read) => { Operation::Read };
+    (zeropage_y_read) => { Operation::Read };
+    (absolute_read) => { Operation::Read };
+    (absolute_x_read) => { Operation::Read };
+    (absolute_y_read) => { Operation::Read };
+    (indirect_x_read) => { Operation::Read };
+    (indirect_y_read) => { Operation::Read };
+    (zeropage_store) => { Operation::Store };
+    (zeropage_x_store) => { Operation::Store };
+    (zeropage_y_store) => { Operation::Store };
+    (absolute_store) => { Operation::Store };
+    (absolute_x_store) => { Operation::Store };
+    (absolute_y_store) => { Operation::Store };
+    (indirect_x_store) => { Operation::Store };
+    (indirect_y_store) => { Operation::Store };
+    (zeropage_modify) => { Operation::Modify };
+    (zeropage_x_modify) => { Operation::Modify };
+    (absolute_modify) => { Operation::Modify };
+    (absolute_x_modify) => { Operation::Modify };
+    (undoc_indirect_x) => { Operation::Modify };
+    (undoc_indirect_y) => { Operation::Modify };
+    (undoc_absolute_y) => { Operation::Modify };
+    (branch) => { Operation::Branch };
+    (brk) => { Operation::Brk };
+    (jsr) => { Operation::Jsr };
+    (rts) => { Operation::Rts };
+    (rti) => { Operation::Rti };
+    (jmp_absolute) => { Operation::Jmp };
+    (jmp_indirect) => { Operation::Jmp };
+    (php) => { Operation::Php };
+    (pha) => { Operation::Pha };
+    (plp) => { Operation::Plp };
+    (pla) => { Operation::Pla };
+    (jam) => { Operation::Jam };
+}
+
+macro_rules! execute {
+    () => { <Nop as Instruction>::execute };
+    ($t:ident) => { <$t as Instruction>::execute };
+}
+
+//====================================================
+// opcode descriptors
+//====================================================
+macro_rules! opcode_table {
+    ($($op:literal => $mnemonic:literal, $mode:ident, $seq:ident $(<$t:ident>)?;)*) => {
+        // opcodes must be listed in order, the position in the table is the opcode
+        const _: () = {
+            let ops = [$($op as usize),*];
+            let mut i = 0;
+            while i < ops.len() {
+                assert!(ops[i] == i, "opcode table out of order");
+                i += 1;
+            }
+            assert!(ops.len() == 256, "opcode table incomplete");
+        };
+
+        pub static OPCODES: [Opcode; 256] = [$(Opcode {
+            mnemonic: $mnemonic,
+            mode: AddressingMode::$mode,
+            operation: operation!($seq),
+            execute: execute!($($t)?),
+        },)*];
+
+        impl<B: Bus + 'static> OpTable<B> {
+            pub const SEQUENCES: &'static [&'static [MicroOp<B>]; 256] = &[$(sequence!($seq, B $(, $t)?),)*];
+        }
+    }
+}
+
+opcode_table! {
+    0x00 => "BRK",    Implied,     brk;
+    0x01 => "ORA",    IndirectX,   indirect_x_read<Ora>;
+    0x02 => "*KIL*",  Implied,     jam;
+    0x03 => "*SLO*",  IndirectX,   undoc_indirect_x<Slo>;
+    0x04 => "*DOP*",  ZeroPage,    zeropage_read<Nop>;
+    0x05 => "ORA",    ZeroPage,    zeropage_read<Ora>;
+    0x06 => "ASL",    ZeroPage,    zeropage_modify<Asl>;
+    0x07 => "*SLO*",  ZeroPage,    zeropage_modify<Slo>;
+    0x08 => "PHP",    Implied,     php;
+    0x09 => "ORA",    Immediate,   immediate_read<Ora>;
+    0x0A => "ASL",    Accumulator, single_byte<AslAccum>;
+    0x0B => "*AAC*",  Immediate,   immediate_read<Aac>;
+    0x0C => "*TOP*",  Absolute,    absolute_read<Nop>;
+    0x0D => "ORA",    Absolute,    absolute_read<Ora>;
+    0x0E => "ASL",    Absolute,    absolute_modify<Asl>;
+    0x0F => "*SLO*",  Absolute,    absolute_modify<Slo>;
+    0x10 => "BPL",    Relative,    branch<Bpl>;
+    0x11 => "ORA",    IndirectY,   indirect_y_read<Ora>;
+    0x12 => "*KIL*",  Implied,     jam;
+    0x13 => "*SLO*",  IndirectY,   undoc_indirect_y<Slo>;
+    0x14 => "*DOP*",  ZeroPageX,   zeropage_x_read<Nop>;
+    0x15 => "ORA",    ZeroPageX,   zeropage_x_read<Ora>;
+    0x16 => "ASL",    ZeroPageX,   zeropage_x_modify<Asl>;
+    0x17 => "*SLO*",  ZeroPageX,   zeropage_x_modify<Slo>;
+    0x18 => "CLC",    Implied,     single_byte<Clc>;
+    0x19 => "ORA",    AbsoluteY,   absolute_y_read<Ora>;
+    0x1A => "*NOP*",  Implied,     single_byte<Nop>;
+    0x1B => "*SLO*",  AbsoluteY,   undoc_absolute_y<Slo>;
+    0x1C => "*TOP*",  AbsoluteX,   absolute_x_read<Nop>;
+    0x1D => "ORA",    AbsoluteX,   absolute_x_read<Ora>;
+    0x1E => "ASL",    AbsoluteX,   absolute_x_modify<Asl>;
+    0x1F => "*SLO*",  AbsoluteX,   absolute_x_modify<Slo>;
+    0x20 => "JSR",    Absolute,    jsr;
+    0x21 => "AND",    IndirectX,   indirect_x_read<And>;
+    0x22 => "*KIL*",  Implied,     jam;
+    0x23 => "*RLA*",  IndirectX,   undoc_indirect_x<Rla>;
+    0x24 => "BIT",    ZeroPage,    zeropage_read<Bit>;
+    0x25 => "AND",    ZeroPage,    zeropage_read<And>;
+    0x26 => "ROL",    ZeroPage,    zeropage_modify<Rol>;
+    0x27 => "*RLA*",  ZeroPage,    zeropage_modify<Rla>;
+    0x28 => "PLP",    Implied,     plp;
+    0x29 => "AND",    Immediate,   immediate_read<And>;
+    0x2A => "ROL",    Accumulator, single_byte<RolAccum>;
+    0x2B => "*AAC*",  Immediate,   immediate_read<Aac>;
+    0x2C => "BIT",    Absolute,    absolute_read<Bit>;
+    0x2D => "AND",    Absolute,    absolute_read<And>;
+    0x2E => "ROL",    Absolute,    absolute_modify<Rol>;
+    0x2F => "*RLA*",  Absolute,    absolute_modify<Rla>;
+    0x30 => "BMI",    Relative,    branch<Bmi>;
+    0x31 => "AND",    IndirectY,   indirect_y_read<And>;
+    0x32 => "*KIL*",  Implied,     jam;
+    0x33 => "*RLA*",  IndirectY,   undoc_indirect_y<Rla>;
+    0x34 => "*DOP*",  ZeroPageX,   zeropage_x_read<Nop>;
+    0x35 => "AND",    ZeroPageX,   zeropage_x_read<And>;
+    0x36 => "ROL",    ZeroPageX,   zeropage_x_modify<Rol>;
+    0x37 => "*RLA*",  ZeroPageX,   zeropage_x_modify<Rla>;
+    0x38 => "SEC",    Implied,     single_byte<Sec>;
+    0x39 => "AND",    AbsoluteY,   absolute_y_read<And>;
+    0x3A => "*NOP*",  Implied,     single_byte<Nop>;
+    0x3B => "*RLA*",  AbsoluteY,   undoc_absolute_y<Rla>;
+    0x3C => "*TOP*",  AbsoluteX,   absolute_x_read<Nop>;
+    0x3D => "AND",    AbsoluteX,   absolute_x_read<And>;
+    0x3E => "ROL",    AbsoluteX,   absolute_x_modify<Rol>;
+    0x3F => "*RLA*",  AbsoluteX,   absolute_x_modify<Rla>;
+    0x40 => "RTI",    Implied,     rti;
+    0x41 => "EOR",    IndirectX,   indirect_x_read<Eor>;
+    0x42 => "*KIL*",  Implied,     jam;
+    0x43 => "*SRE*",  IndirectX,   undoc_indirect_x<Sre>;
+    0x44 => "*DOP*",  ZeroPage,    zeropage_read<Nop>;
+    0x45 => "EOR",    ZeroPage,    zeropage_read<Eor>;
+    0x46 => "LSR",    ZeroPage,    zeropage_modify<Lsr>;
+    0x47 => "*SRE*",  ZeroPage,    zeropage_modify<Sre>;
+    0x48 => "PHA",    Implied,     pha;
+    0x49 => "EOR",    Immediate,   immediate_read<Eor>;
+    0x4A => "LSR",    Accumulator, single_byte<LsrAccum>;
+    0x4B => "*ASR*",  Immediate,   immediate_read<Asr>;
+    0x4C => "JMP",    Absolute,    jmp_absolute;
+    0x4D => "EOR",    Absolute,    absolute_read<Eor>;
+    0x4E => "LSR",    Absolute,    absolute_modify<Lsr>;
+    0x4F => "*SRE*",  Absolute,    absolute_modify<Sre>;
+    0x50 => "BVC",    Relative,    branch<Bvc>;
+    0x51 => "EOR",    IndirectY,   indirect_y_read<Eor>;
+    0x52 => "*KIL*",  Implied,     jam;
+    0x53 => "*SRE*",  IndirectY,   undoc_indirect_y<Sre>;
+    0x54 => "*DOP*",  ZeroPageX,   zeropage_x_read<Nop>;
+    0x55 => "EOR",    ZeroPageX,   zeropage_x_read<Eor>;
+    0x56 => "LSR",    ZeroPageX,   zeropage_x_modify<Lsr>;
+    0x57 => "*SRE*",  ZeroPageX,   zeropage_x_modify<Sre>;
+    0x58 => "CLI",    Implied,     single_byte<Cli>;
+    0x59 => "EOR",    AbsoluteY,   absolute_y_read<Eor>;
+    0x5A => "*NOP*",  Implied,     single_byte<Nop>;
+    0x5B => "*SRE*",  AbsoluteY,   undoc_absolute_y<Sre>;
+    0x5C => "*TOP*",  AbsoluteX,   absolute_x_read<Nop>;
+    0x5D => "EOR",    AbsoluteX,   absolute_x_read<Eor>;
+    0x5E => "LSR",    AbsoluteX,   absolute_x_modify<Lsr>;
+    0x5F => "*SRE*",  AbsoluteX,   absolute_x_modify<Sre>;
+    0x60 => "RTS",    Implied,     rts;
+    0x61 => "ADC",    IndirectX,   indirect_x_read<AdcNoDec>;
+    0x62 => "*KIL*",  Implied,     jam;
+    0x63 => "*RRA*",  IndirectX,   undoc_indirect_x<Rra>;
+    0x64 => "*DOP*",  ZeroPage,    zeropage_read<Nop>;
+    0x65 => "ADC",    ZeroPage,    zeropage_read<AdcNoDec>;
+    0x66 => "ROR",    ZeroPage,    zeropage_modify<Ror>;
+    0x67 => "*RRA*",  ZeroPage,    zeropage_modify<Rra>;
+    0x68 => "PLA",    Implied,     pla;
+    0x69 => "ADC",    Immediate,   immediate_read<AdcNoDec>;
+    0x6A => "ROR",    Accumulator, single_byte<RorAccum>;
+    0x6B => "*ARR*",  Immediate,   immediate_read<Arr>;
+    0x6C => "JMP",    Indirect,    jmp_indirect;
+    0x6D => "ADC",    Absolute,    absolute_read<AdcNoDec>;
+    0x6E => "ROR",    Absolute,    absolute_modify<Ror>;
+    0x6F => "*RRA*",  Absolute,    absolute_modify<Rra>;
+    0x70 => "BVS",    Relative,    branch<Bvs>;
+    0x71 => "ADC",    IndirectY,   indirect_y_read<AdcNoDec>;
+    0x72 => "*KIL*",  Implied,     jam;
+    0x73 => "*RRA*",  IndirectY,   undoc_indirect_y<Rra>;
+    0x74 => "*DOP*",  ZeroPageX,   zeropage_x_read<Nop>;
+    0x75 => "ADC",    ZeroPageX,   zeropage_x_read<AdcNoDec>;
+    0x76 => "ROR",    ZeroPageX,   zeropage_x_modify<Ror>;
+    0x77 => "*RRA*",  ZeroPageX,   zeropage_x_modify<Rra>;
+    0x78 => "SEI",    Implied,     single_byte<Sei>;
+    0x79 => "ADC",    AbsoluteY,   absolute_y_read<AdcNoDec>;
+    0x7A => "*NOP*",  Implied,     single_byte<Nop>;
+    0x7B => "*RRA*",  AbsoluteY,   undoc_absolute_y<Rra>;
+    0x7C => "*TOP*",  AbsoluteX,   absolute_x_read<Nop>;
+    0x7D => "ADC",    AbsoluteX,   absolute_x_read<AdcNoDec>;
+    0x7E => "ROR",    AbsoluteX,   absolute_x_modify<Ror>;
+    0x7F => "*RRA*",  AbsoluteX,   absolute_x_modify<Rra>;
+    0x80 => "*DOP*",  Immediate,   immediate_read<Nop>;
+    0x81 => "STA",    IndirectX,   indirect_x_store<Sta>;
+    0x82 => "*DOP*",  Immediate,   immediate_read<Nop>;
+    0x83 => "*AAX*",  IndirectX,   indirect_x_store<Aax>;
+    0x84 => "STY",    ZeroPage,    zeropage_store<Sty>;
+    0x85 => "STA",    ZeroPage,    zeropage_store<Sta>;
+    0x86 => "STX",    ZeroPage,    zeropage_store<Stx>;
+    0x87 => "*AAX*",  ZeroPage,    zeropage_store<Aax>;
+    0x88 => "DEY",    Implied,     single_byte<Dey>;
+    0x89 => "*DOP*",  Immediate,   immediate_read<Nop>;
+    0x8A => "TXA",    Implied,     single_byte<Txa>;
+    0x8B => "*XAA*",  Immediate,   immediate_read<Xaa>;
+    0x8C => "STY",    Absolute,    absolute_store<Sty>;
+    0x8D => "STA",    Absolute,    absolute_store<Sta>;
+    0x8E => "STX",    Absolute,    absolute_store<Stx>;
+    0x8F => "*AAX*",  Absolute,    absolute_store<Aax>;
+    0x90 => "BCC",    Relative,    branch<Bcc>;
+    0x91 => "STA",    IndirectY,   indirect_y_store<Sta>;
+    0x92 => "*KIL*",  Implied,     jam;
+    0x93 => "*AXA*",  IndirectY,   indirect_y_store<Axa>;
+    0x94 => "STY",    ZeroPageX,   zeropage_x_store<Sty>;
+    0x95 => "STA",    ZeroPageX,   zeropage_x_store<Sta>;
+    0x96 => "STX",    ZeroPageY,   zeropage_y_store<Stx>;
+    0x97 => "*AAX*",  ZeroPageY,   zeropage_y_store<Aax>;
+    0x98 => "TYA",    Implied,     single_byte<Tya>;
+    0x99 => "STA",    AbsoluteY,   absolute_y_store<Sta>;
+    0x9A => "TXS",    Implied,     single_byte<Txs>;
+    0x9B => "*XAS*",  AbsoluteY,   absolute_y_store<Xas>;
+    0x9C => "*SYA*",  AbsoluteX,   absolute_x_store<Sya>;
+    0x9D => "STA",    AbsoluteX,   absolute_x_store<Sta>;
+    0x9E => "*SXA*",  AbsoluteY,   absolute_y_store<Sxa>;
+    0x9F => "*AXA*",  AbsoluteY,   absolute_y_store<Axa>;
+    0xA0 => "LDY",    Immediate,   immediate_read<Ldy>;
+    0xA1 => "LDA",    IndirectX,   indirect_x_read<Lda>;
+    0xA2 => "LDX",    Immediate,   immediate_read<Ldx>;
+    0xA3 => "*LAX*",  IndirectX,   indirect_x_read<Lax>;
+    0xA4 => "LDY",    ZeroPage,    zeropage_read<Ldy>;
+    0xA5 => "LDA",    ZeroPage,    zeropage_read<Lda>;
+    0xA6 => "LDX",    ZeroPage,    zeropage_read<Ldx>;
+    0xA7 => "*LAX*",  ZeroPage,    zeropage_read<Lax>;
+    0xA8 => "TAY",    Implied,     single_byte<Tay>;
+    0xA9 => "LDA",    Immediate,   immediate_read<Lda>;
+    0xAA => "TAX",    Implied,     single_byte<Tax>;
+    0xAB => "*ATX*",  Immediate,   immediate_read<Atx>;
+    0xAC => "LDY",    Absolute,    absolute_read<Ldy>;
+    0xAD => "LDA",    Absolute,    absolute_read<Lda>;
+    0xAE => "LDX",    Absolute,    absolute_read<Ldx>;
+    0xAF => "*LAX*",  Absolute,    absolute_read<Lax>;
+    0xB0 => "BCS",    Relative,    branch<Bcs>;
+    0xB1 => "LDA",    IndirectY,   indirect_y_read<Lda>;
+    0xB2 => "*KIL*",  Implied,     jam;
+    0xB3 => "*LAX*",  IndirectY,   indirect_y_read<Lax>;
+    0xB4 => "LDY",    ZeroPageX,   zeropage_x_read<Ldy>;
+    0xB5 => "LDA",    ZeroPageX,   zeropage_x_read<Lda>;
+    0xB6 => "LDX",    ZeroPageY,   zeropage_y_read<Ldx>;
+    0xB7 => "*LAX*",  ZeroPageY,   zeropage_y_read<Lax>;
+    0xB8 => "CLV",    Implied,     single_byte<Clv>;
+    0xB9 => "LDA",    AbsoluteY,   absolute_y_read<Lda>;
+    0xBA => "TSX",    Implied,     single_byte<Tsx>;
+    0xBB => "*LAR*",  AbsoluteY,   absolute_y_read<Lar>;
+    0xBC => "LDY",    AbsoluteX,   absolute_x_read<Ldy>;
+    0xBD => "LDA",    AbsoluteX,   absolute_x_read<Lda>;
+    0xBE => "LDX",    AbsoluteY,   absolute_y_read<Ldx>;
+    0xBF => "*LAX*",  AbsoluteY,   absolute_y_read<Lax>;
+    0xC0 => "CPY",    Immediate,   immediate_read<Cpy>;
+    0xC1 => "CMP",    IndirectX,   indirect_x_read<Cmp>;
+    0xC2 => "*DOP*",  Immediate,   immediate_read<Nop>;
+    0xC3 => "*DCP*",  IndirectX,   undoc_indirect_x<Dcp>;
+    0xC4 => "CPY",    ZeroPage,    zeropage_read<Cpy>;
+    0xC5 => "CMP",    ZeroPage,    zeropage_read<Cmp>;
+    0xC6 => "DEC",    ZeroPage,    zeropage_modify<Dec>;
+    0xC7 => "*DCP*",  ZeroPage,    zeropage_modify<Dcp>;
+    0xC8 => "INY",    Implied,     single_byte<Iny>;
+    0xC9 => "CMP",    Immediate,   immediate_read<Cmp>;
+    0xCA => "DEX",    Implied,     single_byte<Dex>;
+    0xCB => "*AXS*",  Immediate,   immediate_read<Axs>;
+    0xCC => "CPY",    Absolute,    absolute_read<Cpy>;
+    0xCD => "CMP",    Absolute,    absolute_read<Cmp>;
+    0xCE => "DEC",    Absolute,    absolute_modify<Dec>;
+    0xCF => "*DCP*",  Absolute,    absolute_modify<Dcp>;
+    0xD0 => "BNE",    Relative,    branch<Bne>;
+    0xD1 => "CMP",    IndirectY,   indirect_y_read<Cmp>;
+    0xD2 => "*KIL*",  Implied,     jam;
+    0xD3 => "*DCP*",  IndirectY,   undoc_indirect_y<Dcp>;
+    0xD4 => "*DOP*",  ZeroPageX,   zeropage_x_read<Nop>;
+    0xD5 => "CMP",    ZeroPageX,   zeropage_x_read<Cmp>;
+    0xD6 => "DEC",    ZeroPageX,   zeropage_x_modify<Dec>;
+    0xD7 => "*DCP*",  ZeroPageX,   zeropage_x_modify<Dcp>;
+    0xD8 => "CLD",    Implied,     single_byte<Cld>;
+    0xD9 => "CMP",    AbsoluteY,   absolute_y_read<Cmp>;
+    0xDA => "*NOP*",  Implied,     single_byte<Nop>;
+    0xDB => "*DCP*",  AbsoluteY,   undoc_absolute_y<Dcp>;
+    0xDC => "*TOP*",  AbsoluteX,   absolute_x_read<Nop>;
+    0xDD => "CMP",    AbsoluteX,   absolute_x_read<Cmp>;
+    0xDE => "DEC",    AbsoluteX,   absolute_x_modify<Dec>;
+    0xDF => "*DCP*",  AbsoluteX,   absolute_x_modify<Dcp>;
+    0xE0 => "CPX",    Immediate,   immediate_read<Cpx>;
+    0xE1 => "SBC",    IndirectX,   indirect_x_read<SbcNoDec>;
+    0xE2 => "*DOP*",  Immediate,   immediate_read<Nop>;
+    0xE3 => "*ISC*",  IndirectX,   undoc_indirect_x<Isc>;
+    0xE4 => "CPX",    ZeroPage,    zeropage_read<Cpx>;
+    0xE5 => "SBC",    ZeroPage,    zeropage_read<SbcNoDec>;
+    0xE6 => "INC",    ZeroPage,    zeropage_modify<Inc>;
+    0xE7 => "*ISC*",  ZeroPage,    zeropage_modify<Isc>;
+    0xE8 => "INX",    Implied,     single_byte<Inx>;
+    0xE9 => "SBC",    Immediate,   immediate_read<SbcNoDec>;
+    0xEA => "NOP",    Implied,     single_byte<Nop>;
+    0xEB => "*SBC*",  Immediate,   immediate_read<SbcNoDec>;
+    0xEC => "CPX",    Absolute,    absolute_read<Cpx>;
+    0xED => "SBC",    Absolute,    absolute_read<SbcNoDec>;
+    0xEE => "INC",    Absolute,    absolute_modify<Inc>;
+    0xEF => "*ISC*",  Absolute,    absolute_modify<Isc>;
+    0xF0 => "BEQ",    Relative,    branch<Beq>;
+    0xF1 => "SBC",    IndirectY,   indirect_y_read<SbcNoDec>;
+    0xF2 => "*KIL*",  Implied,     jam;
+    0xF3 => "*ISC*",  IndirectY,   undoc_indirect_y<Isc>;
+    0xF4 => "*DOP*",  ZeroPageX,   zeropage_x_read<Nop>;
+    0xF5 => "SBC",    ZeroPageX,   zeropage_x_read<SbcNoDec>;
+    0xF6 => "INC",    ZeroPageX,   zeropage_x_modify<Inc>;
+    0xF7 => "*ISC*",  ZeroPageX,   zeropage_x_modify<Isc>;
+    0xF8 => "SED",    Implied,     single_byte<Sed>;
+    0xF9 => "SBC",    AbsoluteY,   absolute_y_read<SbcNoDec>;
+    0xFA => "*NOP*",  Implied,     single_byte<Nop>;
+    0xFB => "*ISC*",  AbsoluteY,   undoc_absolute_y<Isc>;
+    0xFC => "*TOP*",  AbsoluteX,   absolute_x_read<Nop>;
+    0xFD => "SBC",    AbsoluteX,   absolute_x_read<SbcNoDec>;
+    0xFE => "INC",    AbsoluteX,   absolute_x_modify<Inc>;
+    0xFF => "*ISC*",  AbsoluteX,   absolute_x_modify<Isc>;
+}
@@ -0,0 +1,433 @@
+use super::{Ctrl, Pinout};
+use super::core::*;
+use super::bus::FastBus;
+use super::dispatch::{AddressingMode, Operation, OPCODES};
+use super::operations::poll_interrupts;
+
+/*
+Instruction level execution
+
+Performs a whole instruction per call instead of one cycle per tick. Register, memory and
+cycle results match the cycle accurate micro-ops, including the cycle each instruction polls
+for interrupts, so the two paths can be switched at any instruction boundary.
+Dummy reads and writes are skipped and the RDY line is ignored.
+
+An instruction boundary is the state left by first_cycle!: the next opcode is already in
+the instruction register with a timing step of 0, and pc points past it.
+*/
+
+const fn to_address(hb: u8, lb: u8) -> u16 {
+    (hb as u16) << 8 | (lb as u16)
+}
+
+struct Exec<'a, F: FastBus> {
+    cpu: &'a mut Context,
+    bus: &'a mut F,
+    // interrupt lines held low for the whole instruction
+    irq: bool,
+    nmi: bool,
+    // cycles elapsed in the current instruction
+    cycles: u32,
+    // operand bytes already decoded by the caller, read from the bus otherwise
+    decoded: Option<[u8; 2]>,
+    operand_count: usize,
+}
+
+impl<'a, F: FastBus> Exec<'a, F> {
+    // the interrupt lines are latched at the end of every cycle like on the micro-ops
+    #[inline]
+    fn end_cycle(&mut self) {
+        self.cycles += 1;
+        self.cpu.latch_interrupts(self.irq, self.nmi);
+    }
+
+    #[inline]
+    fn read(&mut self, address: u16) -> u8 {
+        let data = self.bus.read(address);
+        self.end_cycle();
+        data
+    }
+
+    #[inline]
+    fn write(&mut self, address: u16, data: u8) {
+        self.bus.write(address, data);
+        self.end_cycle();
+    }
+
+    // cycles that only produce dummy bus accesses
+    #[inline]
+    fn idle(&mut self, cycles: u32) {
+        for _ in 0..cycles {
+            self.end_cycle();
+        }
+    }
+
+    // read the next operand byte at pc without moving pc
+    #[inline]
+    fn operand(&mut self) -> u8 {
+        let data = match self.decoded {
+            Some(bytes) => bytes[self.operand_count],
+            None => self.bus.read(u16::from(self.cpu.pc)),
+        };
+        self.operand_count += 1;
+        self.end_cycle();
+        data
+    }
+
+    // pull the flags, they are latched with the i flag of the same cycle
+    #[inline]
+    fn pull_flags(&mut self, address: u16, pull: fn(u8) -> FlagsRegister) {
+        let p = self.bus.read(address);
+        self.cpu.p = pull(p);
+        self.end_cycle();
+    }
+
+    #[inline]
+    fn fetch_operand(&mut self) -> u8 {
+        let data = self.operand();
+        self.cpu.pc.increment();
+        data
+    }
+
+    // act on the lines latched at the end of the penultimate cycle, polls are made after the
+    // last cycle of the instruction has ended
+    #[inline]
+    fn poll(&mut self) -> bool {
+        poll_interrupts(self.cpu, self.cpu.prev_latch);
+        self.cpu.ints != InterruptState::None
+    }
+
+    // first cycle of the next instruction
+    #[inline]
+    fn fetch_opcode(&mut self) {
+        let opcode = self.read(u16::from(self.cpu.pc));
+        self.cpu.first_cycle = true;
+        self.cpu.ir.reset(opcode);
+        self.cpu.ops.reset();
+        self.cpu.pc.increment();
+    }
+
+    // the last cycle overlaps the next opcode fetch, an interrupt still takes the cycle
+    #[inline]
+    fn finish_last(&mut self) {
+        if self.poll() {
+            self.idle(1);
+        }
+        else {
+            self.fetch_opcode();
+        }
+    }
+
+    // poll on the cycle before the opcode fetch
+    #[inline]
+    fn finish_penultimate(&mut self) {
+        if !self.poll() {
+            self.fetch_opcode();
+        }
+        // on the micro-ops this poll is made before the end of the last cycle, latch the nmi
+        // again now the poll may have cleared it. the lines are held so there is no new edge
+        else {
+            self.cpu.latch.nmi = self.cpu.nmi_detected;
+        }
+    }
+
+    fn effective_address(&mut self, mode: AddressingMode, page_penalty: bool) -> u16 {
+        match mode {
+            AddressingMode::ZeroPage => {
+                to_address(0, self.fetch_operand())
+            }
+            AddressingMode::ZeroPageX => {
+                let bal = self.fetch_operand();
+                self.idle(1);
+                to_address(0, bal.wrapping_add(self.cpu.x))
+            }
+            AddressingMode::ZeroPageY => {
+                let bal = self.fetch_operand();
+                self.idle(1);
+                to_address(0, bal.wrapping_add(self.cpu.y))
+            }
+            AddressingMode::Absolute => {
+                let adl = self.fetch_operand();
+                let adh = self.fetch_operand();
+                to_address(adh, adl)
+            }
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => {
+                let index = if mode == AddressingMode::AbsoluteX { self.cpu.x } else { self.cpu.y };
+                let bal = self.fetch_operand();
+                let bah = self.fetch_operand();
+                self.cpu.ops.bah = bah;
+                let (adl, carry) = bal.overflowing_add(index);
+                if carry || !page_penalty { self.idle(1); }
+                to_address(bah.wrapping_add(carry as u8), adl)
+            }
+            AddressingMode::IndirectX => {
+                let bal = self.fetch_operand().wrapping_add(self.cpu.x);
+                self.idle(1);
+                let adl = self.read(to_address(0, bal));
+                let adh = self.read(to_address(0, bal.wrapping_add(1)));
+                to_address(adh, adl)
+            }
+            AddressingMode::IndirectY => {
+                let ial = self.fetch_operand();
+                let bal = self.read(to_address(0, ial));
+                let bah = self.read(to_address(0, ial.wrapping_add(1)));
+                self.cpu.ops.bah = bah;
+                let (adl, carry) = bal.overflowing_add(self.cpu.y);
+                if carry || !page_penalty { self.idle(1); }
+                to_address(bah.wrapping_add(carry as u8), adl)
+            }
+            _ => unreachable!("{:?} has no effective address", mode),
+        }
+    }
+
+    fn read_op(&mut self, mode: AddressingMode, execute: fn(&mut Context)) {
+        self.cpu.ops.dl = match mode {
+            AddressingMode::Immediate => self.fetch_operand(),
+            _ => {
+                let address = self.effective_address(mode, true);
+                self.read(address)
+            }
+        };
+
+        execute(self.cpu);
+        self.finish_last();
+    }
+
+    fn store_op(&mut self, mode: AddressingMode, execute: fn(&mut Context)) {
+        let address = self.effective_address(mode, false);
+
+        // the unstable stores may change the high byte of the target address
+        self.cpu.ops.adl = address as u8;
+        self.cpu.ops.adh = (address >> 8) as u8;
+        execute(self.cpu);
+        self.write(to_address(self.cpu.ops.adh, self.cpu.ops.adl), self.cpu.ops.dl);
+        self.finish_penultimate();
+    }
+
+    fn modify_op(&mut self, mode: AddressingMode, execute: fn(&mut Context)) {
+        let address = self.effective_address(mode, false);
+        self.cpu.ops.dl = self.read(address);
+        // the unmodified value is written back first
+        self.idle(1);
+        execute(self.cpu);
+        self.write(address, self.cpu.ops.dl);
+        self.finish_penultimate();
+    }
+
+    fn branch_op(&mut self, execute: fn(&mut Context)) {
+        let offset = self.fetch_operand();
+
+        execute(self.cpu);
+        if !self.cpu.ops.branch_taken {
+            self.finish_last();
+            return;
+        }
+
+        // an interrupt first latched on the operand cycle is ignored
+        if self.cpu.latch.irq && !self.cpu.prev_latch.irq { self.cpu.latch.irq = false; }
+        if self.cpu.latch.nmi && !self.cpu.prev_latch.nmi { self.cpu.latch.nmi = false; }
+
+        let pc = u16::from(self.cpu.pc);
+        let target = pc.wrapping_add(offset as i8 as u16);
+        self.cpu.pc.pcl = target as u8;
+        self.idle(1);
+
+        if (target & 0xFF00) != (pc & 0xFF00) {
+            self.cpu.pc.pch = (target >> 8) as u8;
+            self.idle(1);
+            self.finish_penultimate();
+        }
+        // without a page cross the poll shares a cycle with the opcode fetch
+        else {
+            self.finish_last();
+        }
+    }
+
+    fn implied_op(&mut self, execute: fn(&mut Context)) {
+        self.idle(1);
+        execute(self.cpu);
+        self.finish_last();
+    }
+
+    // only the cycle that locks up the cpu, the jammed bus pattern is left to the micro-ops
+    fn jam_op(&mut self) {
+        self.idle(1);
+        self.cpu.ir.increment();
+        self.cpu.jammed = true;
+    }
+
+    fn stack_op(&mut self, operation: Operation) {
+        match operation {
+            Operation::Php => {
+                self.idle(1);
+                let p = u8::from(self.cpu.p);
+                self.write(to_address(0x1, self.cpu.sp), p);
+                self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+            }
+            Operation::Pha => {
+                self.idle(1);
+                self.write(to_address(0x1, self.cpu.sp), self.cpu.a);
+                self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+            }
+            Operation::Plp => {
+                self.idle(2);
+                self.cpu.sp = self.cpu.sp.wrapping_add(1);
+                self.pull_flags(to_address(0x1, self.cpu.sp), FlagsRegister::from);
+            }
+            Operation::Pla => {
+                self.idle(2);
+                self.cpu.sp = self.cpu.sp.wrapping_add(1);
+                self.cpu.a = self.read(to_address(0x1, self.cpu.sp));
+                self.cpu.p.zero = self.cpu.a == 0;
+                self.cpu.p.negative = (self.cpu.a & 0x80) == 0x80;
+            }
+            Operation::Jsr => {
+                let adl = self.fetch_operand();
+                self.idle(1);
+                self.write(to_address(0x1, self.cpu.sp), self.cpu.pc.pch);
+                self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+                self.write(to_address(0x1, self.cpu.sp), self.cpu.pc.pcl);
+                self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+                // read after the pushes, with the stack in page 1 they can overwrite it
+                let adh = self.read(u16::from(self.cpu.pc));
+                self.cpu.pc = ProgramCounter::from(to_address(adh, adl));
+            }
+            Operation::Rts => {
+                self.idle(2);
+                self.cpu.sp = self.cpu.sp.wrapping_add(1);
+                self.cpu.pc.pcl = self.read(to_address(0x1, self.cpu.sp));
+                self.cpu.sp = self.cpu.sp.wrapping_add(1);
+                self.cpu.pc.pch = self.read(to_address(0x1, self.cpu.sp));
+                self.idle(1);
+                self.cpu.pc.increment();
+            }
+            Operation::Rti => {
+                self.idle(2);
+                self.cpu.sp = self.cpu.sp.wrapping_add(1);
+                self.pull_flags(to_address(0x1, self.cpu.sp), FlagsRegister::pull);
+                self.cpu.sp = self.cpu.sp.wrapping_add(1);
+                self.cpu.pc.pcl = self.read(to_address(0x1, self.cpu.sp));
+                self.cpu.sp = self.cpu.sp.wrapping_add(1);
+                self.cpu.pc.pch = self.read(to_address(0x1, self.cpu.sp));
+            }
+            _ => unreachable!("{:?} is not a stack operation", operation),
+        }
+
+        self.finish_penultimate();
+    }
+
+    fn jmp_op(&mut self, mode: AddressingMode) {
+        let adl = self.fetch_operand();
+        let adh = self.operand();
+
+        self.cpu.pc = match mode {
+            AddressingMode::Indirect => {
+                // the pointer high byte is not incremented across a page
+                let pcl = self.read(to_address(adh, adl));
+                let pch = self.read(to_address(adh, adl.wrapping_add(1)));
+                ProgramCounter::from(to_address(pch, pcl))
+            }
+            _ => ProgramCounter::from(to_address(adh, adl)),
+        };
+
+        self.finish_penultimate();
+    }
+}
+
+// returns true if the opcode in the instruction register can be run by execute,
+// brk, interrupts and reset all run through the brk slot on the micro-ops
+pub fn is_supported(cpu: &Context) -> bool {
+    cpu.ir.tm == 0 && cpu.ir.opcode != 0x00
+}
+
+// execute the instruction in the instruction register, returns the cycles taken
+pub fn execute<F: FastBus>(cpu: &mut Context, bus: &mut F, pinout: Pinout) -> u32 {
+    let opcode = &OPCODES[cpu.ir.opcode as usize];
+    run(cpu, bus, pinout, None, opcode.operation, opcode.mode, opcode.execute)
+}
+
+// an instruction specialised for its operation and addressing mode, the operand bytes
+// are decoded ahead of time instead of read from the bus
+pub type Handler<F> = fn(&mut Context, &mut F, Pinout, [u8; 2], fn(&mut Context)) -> u32;
+
+macro_rules! handlers {
+    ($operation:expr, $mode:expr, $($o:ident, $m:ident;)*) => {
+        match ($operation, $mode) {
+            $(
+                (Operation::$o, AddressingMode::$m) => |cpu, bus, pinout, operands, execute| {
+                    run(cpu, bus, pinout, Some(operands), Operation::$o, AddressingMode::$m, execute)
+                },
+            )*
+            (operation, mode) => unreachable!("{:?} {:?} has no handler", operation, mode),
+        }
+    }
+}
+
+pub fn handler<F: FastBus>(opcode: u8) -> Handler<F> {
+    let opcode = &OPCODES[opcode as usize];
+    handlers!(opcode.operation, opcode.mode,
+        Implied, Implied;
+        Implied, Accumulator;
+        Read, Immediate;
+        Read, ZeroPage;
+        Read, ZeroPageX;
+        Read, ZeroPageY;
+        Read, Absolute;
+        Read, AbsoluteX;
+        Read, AbsoluteY;
+        Read, IndirectX;
+        Read, IndirectY;
+        Store, ZeroPage;
+        Store, ZeroPageX;
+        Store, ZeroPageY;
+        Store, Absolute;
+        Store, AbsoluteX;
+        Store, AbsoluteY;
+        Store, IndirectX;
+        Store, IndirectY;
+        Modify, ZeroPage;
+        Modify, ZeroPageX;
+        Modify, Absolute;
+        Modify, AbsoluteX;
+        Modify, AbsoluteY;
+        Modify, IndirectX;
+        Modify, IndirectY;
+        Branch, Relative;
+        Jmp, Absolute;
+        Jmp, Indirect;
+        Jsr, Absolute;
+        Rts, Implied;
+        Rti, Implied;
+        Php, Implied;
+        Pha, Implied;
+        Plp, Implied;
+        Pla, Implied;
+        Jam, Implied;
+    )
+}
+
+#[inline(always)]
+fn run<F: FastBus>(cpu: &mut Context, bus: &mut F, pinout: Pinout, decoded: Option<[u8; 2]>,
+    operation: Operation, mode: AddressingMode, execute: fn(&mut Context)) -> u32 {
+    cpu.first_cycle = false;
+
+    let irq = !pinout.ctrl.contains(Ctrl::IRQ);
+    let nmi = !pinout.ctrl.contains(Ctrl::NMI);
+    let mut exec = Exec { cpu, bus, irq, nmi, cycles: 0, decoded, operand_count: 0 };
+    match operation {
+        Operation::Implied => exec.implied_op(execute),
+        Operation::Read => exec.read_op(mode, execute),
+        Operation::Store => exec.store_op(mode, execute),
+        Operation::Modify => exec.modify_op(mode, execute),
+        Operation::Branch => exec.branch_op(execute),
+        Operation::Jmp => exec.jmp_op(mode),
+        Operation::Php | Operation::Pha | Operation::Plp | Operation::Pla |
+        Operation::Jsr | Operation::Rts | Operation::Rti => exec.stack_op(operation),
+        Operation::Jam => exec.jam_op(),
+        Operation::Brk => unreachable!("brk is run through the micro-ops"),
+    }
+
+    let cycles = exec.cycles;
+    cpu.cycle += cycles as u64;
+    cycles
+}
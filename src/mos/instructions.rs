@@ -10,6 +10,19 @@ fn set_negative(x: u8) -> bool {
     if (x & 0x80) == 0x80 { true } else { false }
 }
 
+// SHA/SHX/SHY/TAS store a register anded with the high byte of the base address + 1,
+// adh differs from bah when the index crossed a page
+#[inline]
+fn unstable_store(cpu: &mut Context, register: u8) {
+    let value = if cpu.ops.rdy_stall == true && cpu.unstable.shx_rdy_drops_and == true { register }
+        else { register & cpu.ops.bah.wrapping_add(1) };
+
+    if cpu.ops.adh != cpu.ops.bah && cpu.unstable.shx_page_cross_corrupts_address == true {
+        cpu.ops.adh = value;
+    }
+    cpu.ops.dl = value;
+}
+
 pub trait Instruction {
     fn execute(cpu: &mut Context);
 }
@@ -34,6 +47,8 @@ impl Instruction for AdcNoDec {
     }
 }
 
+// decimal mode is disabled on the 2A03, kept for a stock 6502 core
+#[allow(dead_code)]
 pub struct Adc {}
 impl Instruction for Adc {
     fn execute(cpu: &mut Context) {
@@ -439,6 +454,8 @@ impl Instruction for SbcNoDec {
     }
 }
 
+// decimal mode is disabled on the 2A03, kept for a stock 6502 core
+#[allow(dead_code)]
 pub struct Sbc {}
 impl Instruction for Sbc {
     fn execute(cpu: &mut Context) {
@@ -661,7 +678,7 @@ impl Instruction for Asr {
 pub struct Atx {}
 impl Instruction for Atx {
     fn execute(cpu: &mut Context) {
-        cpu.a &= cpu.ops.dl;
+        cpu.a = (cpu.a | cpu.unstable.lax_magic) & cpu.ops.dl;
         cpu.x = cpu.a;
 
         cpu.p.zero = set_zero(cpu.a);
@@ -672,10 +689,7 @@ impl Instruction for Atx {
 pub struct Axa {}
 impl Instruction for Axa {
     fn execute(cpu: &mut Context) {
-        cpu.ops.dl = cpu.a & cpu.x & (cpu.ops.adh.wrapping_add(1));
-
-        cpu.p.zero = set_zero(cpu.a);
-        cpu.p.negative = set_negative(cpu.a);
+        unstable_store(cpu, cpu.a & cpu.x);
     }
 }
 
@@ -719,16 +733,6 @@ impl Instruction for Isc {
     }
 }
 
-pub struct Kil {}
-impl Instruction for Kil {
-    fn execute(cpu: &mut Context) {
-        let mut addr = u16::from(cpu.pc);
-       // halt pc, lock up cpu
-        addr -= 1;
-        cpu.pc = ProgramCounter::from(addr);
-    }
-}
-
 pub struct Lar {}
 impl Instruction for Lar {
     fn execute(cpu: &mut Context) {
@@ -832,23 +836,24 @@ impl Instruction for Sre {
 pub struct Sxa {}
 impl Instruction for Sxa {
     fn execute(cpu: &mut Context) {
-        cpu.ops.dl = cpu.x & (cpu.ops.adh + 1);  
+        unstable_store(cpu, cpu.x);
     }
 }
 
 pub struct Sya {}
 impl Instruction for Sya {
     fn execute(cpu: &mut Context) {
-        cpu.ops.dl = cpu.y & (cpu.ops.adh + 1);  
+        unstable_store(cpu, cpu.y);
     }
 }
 
 pub struct Xaa {}
 impl Instruction for Xaa {
     fn execute(cpu: &mut Context) {
-        cpu.a = cpu.x;
-        // TODO: A is anded with some unkown immediate value
-        cpu.a &= cpu.ops.dl;
+        cpu.a = (cpu.a | cpu.unstable.xaa_magic) & cpu.x & cpu.ops.dl;
+
+        cpu.p.zero = set_zero(cpu.a);
+        cpu.p.negative = set_negative(cpu.a);
     }
 }
 
@@ -856,6 +861,6 @@ pub struct Xas {}
 impl Instruction for Xas {
     fn execute(cpu: &mut Context) {
         cpu.sp = cpu.x & cpu.a;
-        cpu.ops.dl = cpu.sp & (cpu.ops.adh + 1);
+        unstable_store(cpu, cpu.sp);
     }
 }
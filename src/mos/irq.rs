@@ -0,0 +1,71 @@
+use super::{Ctrl, Pinout};
+
+/*
+Shared /IRQ line
+
+/IRQ is open collector, any device can pull it low and it only reads high once every device
+has released it. Each device asserts and releases its own source and the combined line is
+driven onto the pinout before every tick. The pull up is left to tick.
+*/
+
+bitflags! {
+    pub struct IrqSource: u16 {
+        const APU_FRAME = 0b0000000000000001;   // apu frame counter
+        const DMC =       0b0000000000000010;   // apu delta modulation channel
+        const MAPPER =    0b0000000000000100;   // cartridge mapper irq counter
+        const CARTRIDGE = 0b0000000000001000;   // other cartridge hardware, expansion audio
+        const VIA1 =      0b0000000000010000;
+        const VIA2 =      0b0000000000100000;
+        const CIA =       0b0000000001000000;
+        const EXTERNAL =  0b0000000010000000;   // expansion port and anything else on the board
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct IrqLine {
+    sources: IrqSource,
+}
+
+impl IrqLine {
+    pub fn new() -> IrqLine {
+        IrqLine {
+            sources: IrqSource::empty(),
+        }
+    }
+
+    pub fn assert(&mut self, source: IrqSource) {
+        self.sources.insert(source);
+    }
+
+    pub fn release(&mut self, source: IrqSource) {
+        self.sources.remove(source);
+    }
+
+    pub fn set(&mut self, source: IrqSource, asserted: bool) {
+        self.sources.set(source, asserted);
+    }
+
+    // true while any source holds the line low
+    pub fn is_asserted(&self) -> bool {
+        !self.sources.is_empty()
+    }
+
+    // the sources currently holding the line low
+    pub fn asserting(&self) -> IrqSource {
+        self.sources
+    }
+
+    // pull the irq pin low if any source is asserting, other drivers of the pin are kept
+    pub fn drive(&self, mut pinout: Pinout) -> Pinout {
+        if self.is_asserted() {
+            pinout.ctrl.remove(Ctrl::IRQ);
+        }
+        pinout
+    }
+}
+
+impl Default for IrqLine {
+    fn default() -> IrqLine {
+        IrqLine::new()
+    }
+}
@@ -0,0 +1,298 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use super::{Ctrl, Pinout};
+use super::bus::Bus;
+use super::rp2a03::Rp2a03;
+
+/*
+Program images
+
+Intel HEX and Motorola S-records are text files of records, each with its own checksum.
+Intel HEX reaches past 64k with extended segment (type 02) and extended linear (type 04)
+records and gives the start address with type 03 or 05, S-records carry 16, 24 or 32 bit
+addresses in S1-S3 and the start address in S7-S9. Data past $FFFF is an error either way.
+
+A .prg is a two byte little endian load address followed by the data, a raw binary is only
+the data and the caller says where it goes. Neither has a start address.
+
+write_to stores the program through Bus::poke so rom can be loaded too, addresses a bus will
+not poke get a plain bus write. The start address goes to the cpu through the reset vector
+or straight into the program counter.
+*/
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    // neither the extension nor the first character says which format a file is in
+    UnknownFormat,
+    // lines count from 1, binary files report line 0
+    Syntax { line: usize },
+    Checksum { line: usize, expected: u8, found: u8 },
+    UnsupportedRecord { line: usize, kind: u8 },
+    AddressRange { line: usize, address: u32 },
+    // an Intel HEX file without an end of file record
+    MissingEnd,
+    // a .prg without its load address
+    TooShort,
+    NoStartAddress,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::Io(ref err) => write!(f, "could not read the program: {}", err),
+            LoadError::UnknownFormat => write!(f, "program is not Intel HEX, S-record or .prg"),
+            LoadError::Syntax { line } => write!(f, "line {}: malformed record", line),
+            LoadError::Checksum { line, expected, found } => write!(f, "line {}: checksum is ${:02X}, expected ${:02X}", line, found, expected),
+            LoadError::UnsupportedRecord { line, kind } => write!(f, "line {}: record type {} is not supported", line, kind),
+            LoadError::AddressRange { line, address } => write!(f, "line {}: address ${:X} is past $FFFF", line, address),
+            LoadError::MissingEnd => write!(f, "Intel HEX file has no end of file record"),
+            LoadError::TooShort => write!(f, ".prg file has no load address"),
+            LoadError::NoStartAddress => write!(f, "program has no start address"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            LoadError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> LoadError {
+        LoadError::Io(err)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Segment {
+    pub address: u16,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Program {
+    // in file order, records following each other are merged
+    pub segments: Vec<Segment>,
+    pub start: Option<u16>,
+}
+
+impl Program {
+    pub fn from_intel_hex(text: &str) -> Result<Program, LoadError> {
+        let mut program = Program::default();
+        let mut base = 0u32;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let bytes = line.strip_prefix(':').ok_or(LoadError::Syntax { line: line_number }).and_then(|record| hex_bytes(record, line_number))?;
+            if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+                return Err(LoadError::Syntax { line: line_number });
+            }
+
+            // the bytes and the checksum add up to zero
+            let (record, found) = bytes.split_at(bytes.len() - 1);
+            let expected = record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)).wrapping_neg();
+            if expected != found[0] {
+                return Err(LoadError::Checksum { line: line_number, expected, found: found[0] });
+            }
+
+            let offset = (record[1] as u32) << 8 | record[2] as u32;
+            let data = &record[4..];
+            match record[3] {
+                0x00 => program.push(base + offset, data, line_number)?,
+                0x01 => return Ok(program),
+                0x02 if data.len() == 2 => base = ((data[0] as u32) << 8 | data[1] as u32) << 4,
+                0x04 if data.len() == 2 => base = ((data[0] as u32) << 8 | data[1] as u32) << 16,
+                // cs:ip or a linear address
+                0x03 if data.len() == 4 => {
+                    let segment = (data[0] as u32) << 8 | data[1] as u32;
+                    let pointer = (data[2] as u32) << 8 | data[3] as u32;
+                    program.start = Some(start_address((segment << 4) + pointer, line_number)?);
+                }
+                0x05 if data.len() == 4 => {
+                    program.start = Some(start_address(be32(data), line_number)?);
+                }
+                0x02..=0x05 => return Err(LoadError::Syntax { line: line_number }),
+                kind => return Err(LoadError::UnsupportedRecord { line: line_number, kind }),
+            }
+        }
+        Err(LoadError::MissingEnd)
+    }
+
+    pub fn from_srecord(text: &str) -> Result<Program, LoadError> {
+        let mut program = Program::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut chars = line.chars();
+            let kind = match (chars.next(), chars.next().and_then(|c| c.to_digit(10))) {
+                (Some('S'), Some(kind)) => kind as u8,
+                _ => return Err(LoadError::Syntax { line: line_number }),
+            };
+            let bytes = hex_bytes(&line[2..], line_number)?;
+            if bytes.len() < 2 || bytes.len() != bytes[0] as usize + 1 {
+                return Err(LoadError::Syntax { line: line_number });
+            }
+
+            // ones complement of the count, address and data
+            let (record, found) = bytes.split_at(bytes.len() - 1);
+            let expected = !record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+            if expected != found[0] {
+                return Err(LoadError::Checksum { line: line_number, expected, found: found[0] });
+            }
+
+            let address_len = match kind {
+                0 | 1 | 5 | 9 => 2,
+                2 | 6 | 8 => 3,
+                3 | 7 => 4,
+                kind => return Err(LoadError::UnsupportedRecord { line: line_number, kind }),
+            };
+            let fields = &record[1..];
+            if fields.len() < address_len {
+                return Err(LoadError::Syntax { line: line_number });
+            }
+            let address = fields[..address_len].iter().fold(0u32, |address, b| address << 8 | *b as u32);
+            let data = &fields[address_len..];
+            match kind {
+                1..=3 => program.push(address, data, line_number)?,
+                7..=9 => program.start = Some(start_address(address, line_number)?),
+                // header and record counts
+                _ => {}
+            }
+        }
+        Ok(program)
+    }
+
+    pub fn from_prg(data: &[u8]) -> Result<Program, LoadError> {
+        if data.len() < 2 {
+            return Err(LoadError::TooShort);
+        }
+        Program::from_raw((data[1] as u16) << 8 | data[0] as u16, &data[2..])
+    }
+
+    pub fn from_raw(address: u16, data: &[u8]) -> Result<Program, LoadError> {
+        let mut program = Program::default();
+        program.push(address as u32, data, 0)?;
+        Ok(program)
+    }
+
+    // the format is picked by extension, text files without a known one by their first character
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Program, LoadError> {
+        let path = path.as_ref();
+        let data = fs::read(path)?;
+        let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("prg") => return Program::from_prg(&data),
+            Some("hex") | Some("ihx") | Some("ihex") => return Program::from_intel_hex(&text(data)?),
+            Some("s19") | Some("s28") | Some("s37") | Some("srec") | Some("mot") => return Program::from_srecord(&text(data)?),
+            _ => {}
+        }
+
+        match data.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b':') => Program::from_intel_hex(&text(data)?),
+            Some(b'S') => Program::from_srecord(&text(data)?),
+            _ => Err(LoadError::UnknownFormat),
+        }
+    }
+
+    pub fn load_raw<P: AsRef<Path>>(path: P, address: u16) -> Result<Program, LoadError> {
+        let data = fs::read(path)?;
+        Program::from_raw(address, &data)
+    }
+
+    fn push(&mut self, address: u32, data: &[u8], line: usize) -> Result<(), LoadError> {
+        if address as usize + data.len() > 0x10000 {
+            // the last address, a malformed record may put it past the u32 range
+            let last = address.saturating_add((data.len() as u32).saturating_sub(1));
+            return Err(LoadError::AddressRange { line, address: last });
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let address = address as u16;
+        if let Some(last) = self.segments.last_mut() {
+            if last.address as usize + last.data.len() == address as usize {
+                last.data.extend_from_slice(data);
+                return Ok(());
+            }
+        }
+        self.segments.push(Segment { address, data: data.to_vec() });
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.iter().map(|segment| segment.data.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn write_to<B: Bus>(&self, bus: &mut B) {
+        for segment in self.segments.iter() {
+            for (offset, data) in segment.data.iter().enumerate() {
+                let address = segment.address.wrapping_add(offset as u16);
+                if !bus.poke(address, *data) {
+                    let mut pinout = Pinout::new();
+                    pinout.ctrl.set(Ctrl::RW, false);
+                    pinout.address = address;
+                    pinout.data = *data;
+                    bus.write(pinout);
+                }
+            }
+        }
+    }
+
+    // the start address into $FFFC-$FFFD for the next reset
+    pub fn set_reset_vector<B: Bus>(&self, bus: &mut B) -> Result<(), LoadError> {
+        let start = self.start.ok_or(LoadError::NoStartAddress)?;
+        let vector = Program { segments: vec![Segment { address: 0xFFFC, data: start.to_le_bytes().to_vec() }], start: None };
+        vector.write_to(bus);
+        Ok(())
+    }
+
+    // run from the start address from the next tick, see Rp2a03::set_pc
+    pub fn jump<B: Bus>(&self, cpu: &mut Rp2a03, bus: &mut B) -> Result<Pinout, LoadError> {
+        let start = self.start.ok_or(LoadError::NoStartAddress)?;
+        Ok(cpu.set_pc(start, bus))
+    }
+}
+
+fn text(data: Vec<u8>) -> Result<String, LoadError> {
+    String::from_utf8(data).map_err(|_| LoadError::UnknownFormat)
+}
+
+fn hex_bytes(text: &str, line: usize) -> Result<Vec<u8>, LoadError> {
+    if text.len() & 0x01 != 0 || !text.is_ascii() {
+        return Err(LoadError::Syntax { line });
+    }
+    (0..text.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| LoadError::Syntax { line }))
+        .collect()
+}
+
+fn be32(data: &[u8]) -> u32 {
+    (data[0] as u32) << 24 | (data[1] as u32) << 16 | (data[2] as u32) << 8 | data[3] as u32
+}
+
+fn start_address(address: u32, line: usize) -> Result<u16, LoadError> {
+    if address > 0xFFFF {
+        return Err(LoadError::AddressRange { line, address });
+    }
+    Ok(address as u16)
+}
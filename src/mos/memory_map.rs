@@ -0,0 +1,327 @@
+use super::Pinout;
+use super::bus::Bus;
+
+/*
+Memory map with open bus
+
+Built once from a list of regions, each an address range backed by ram, rom, a device or
+another bus. Ram and rom are mirrored through their range by a mask on the offset into the
+region, 2k of ram over $0000-$1FFF is mirrored four times. Device handlers and buses get the
+full pinout with the address as the cpu put it out and decode mirrors themselves.
+
+Where regions overlap the one with the higher priority answers, on equal priority the one
+added last. Overlaps are resolved when the map is built into a page table, so finding the
+region for an address is two lookups. Pages covered by a single region point at it, pages
+split between regions get a table of their own.
+
+When nothing drives the data bus the cpu reads whatever was left on it, normally the last
+byte read or written. Pinout::data carries that value between cycles and is what floating
+bits read as. A region can leave some bits floating, like bit 5 of $4015 or the upper bits
+of the controller ports on the nes, only the driven bits come from the region. Addresses no
+region covers float completely.
+
+The value left on the bus decays once nothing has driven it for a while. Decay is off by
+default, when set the floating bits read as 0 once the given number of bus accesses have
+passed since the bus was last driven.
+*/
+
+// data bits the region drives on a read, the others float
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Drive {
+    Full,
+    Partial(u8),
+    Floating,
+}
+
+impl Drive {
+    fn mask(&self) -> u8 {
+        match *self {
+            Drive::Full => 0xFF,
+            Drive::Partial(mask) => mask,
+            Drive::Floating => 0x00,
+        }
+    }
+}
+
+// read and write handlers get the whole pinout, a read returns it with the data set
+pub type Handler = Box<dyn FnMut(Pinout) -> Pinout>;
+
+enum Backing {
+    Ram(Vec<u8>),
+    Rom(Vec<u8>),
+    Device { read: Handler, write: Handler },
+    Bus(Box<dyn Bus>),
+    Unmapped,
+}
+
+struct Region {
+    start: u16,
+    end: u16,
+    // applied to the offset into the region
+    mirror: usize,
+    priority: i32,
+    backing: Backing,
+    drive: Drive,
+}
+
+impl Region {
+    fn offset(&self, address: u16) -> usize {
+        let offset = (address - self.start) as usize & self.mirror;
+        match self.backing {
+            // a rom that is not a power of two in size repeats as a whole
+            Backing::Rom(ref data) => offset % data.len(),
+            _ => offset,
+        }
+    }
+}
+
+const NO_REGION: u16 = u16::MAX;
+
+enum Page {
+    Region(u16),
+    Split(Box<[u16; 256]>),
+}
+
+pub struct MemoryMapBuilder {
+    regions: Vec<Region>,
+    decay: Option<u64>,
+}
+
+impl MemoryMapBuilder {
+    fn add(mut self, start: u16, end: u16, mirror: usize, backing: Backing) -> MemoryMapBuilder {
+        assert!(start <= end, "region start must not be past its end");
+        let drive = match backing {
+            Backing::Unmapped => Drive::Floating,
+            _ => Drive::Full,
+        };
+        self.regions.push(Region { start, end, mirror, priority: 0, backing, drive });
+        self
+    }
+
+    fn last(&mut self) -> &mut Region {
+        self.regions.last_mut().expect("no region has been added")
+    }
+
+    // zero filled ram of size bytes mirrored through start..=end, the size must be a power of two
+    pub fn ram(self, start: u16, end: u16, size: usize) -> MemoryMapBuilder {
+        assert!(size.is_power_of_two() && size <= 0x10000, "ram size must be a power of two up to 64k");
+        self.add(start, end, size - 1, Backing::Ram(vec![0; size]))
+    }
+
+    // rom mirrored through start..=end, writes are ignored
+    pub fn rom(self, start: u16, end: u16, data: Vec<u8>) -> MemoryMapBuilder {
+        assert!(!data.is_empty(), "rom must not be empty");
+        self.add(start, end, usize::MAX, Backing::Rom(data))
+    }
+
+    pub fn device<R, W>(self, start: u16, end: u16, read: R, write: W) -> MemoryMapBuilder
+    where R: FnMut(Pinout) -> Pinout + 'static, W: FnMut(Pinout) -> Pinout + 'static {
+        self.add(start, end, usize::MAX, Backing::Device { read: Box::new(read), write: Box::new(write) })
+    }
+
+    // hand start..=end to another bus, peek and poke are passed through
+    pub fn bus<B: Bus + 'static>(self, start: u16, end: u16, bus: B) -> MemoryMapBuilder {
+        self.add(start, end, usize::MAX, Backing::Bus(Box::new(bus)))
+    }
+
+    // nothing answers in start..=end, reads see the open bus. used to punch holes in other regions
+    pub fn unmapped(self, start: u16, end: u16) -> MemoryMapBuilder {
+        self.add(start, end, usize::MAX, Backing::Unmapped)
+    }
+
+    // mask applied to the offset into the last region added
+    pub fn mirror(mut self, mask: u16) -> MemoryMapBuilder {
+        self.last().mirror = mask as usize;
+        self
+    }
+
+    // the last region added answers over overlapping regions of lower priority, 0 by default
+    pub fn priority(mut self, priority: i32) -> MemoryMapBuilder {
+        self.last().priority = priority;
+        self
+    }
+
+    // bits driven on a read by the last region added
+    pub fn drive(mut self, drive: Drive) -> MemoryMapBuilder {
+        self.last().drive = drive;
+        self
+    }
+
+    // floating bits read as 0 after this many bus accesses without the bus being driven
+    pub fn decay(mut self, accesses: u64) -> MemoryMapBuilder {
+        self.decay = Some(accesses);
+        self
+    }
+
+    pub fn build(self) -> MemoryMap {
+        assert!(self.regions.len() < NO_REGION as usize, "too many regions");
+
+        // resolve every address to the region answering it
+        let mut owners = vec![NO_REGION; 0x10000];
+        for (address, owner) in owners.iter_mut().enumerate() {
+            let address = address as u16;
+            let mut best: Option<usize> = None;
+            for (index, region) in self.regions.iter().enumerate() {
+                if region.start > address || address > region.end {
+                    continue;
+                }
+                match best {
+                    Some(b) if self.regions[b].priority > region.priority => {}
+                    _ => best = Some(index),
+                }
+            }
+            if let Some(index) = best {
+                *owner = index as u16;
+            }
+        }
+
+        let pages = owners.chunks(256).map(|page| {
+            if page.iter().all(|owner| *owner == page[0]) {
+                Page::Region(page[0])
+            }
+            else {
+                let mut table = Box::new([NO_REGION; 256]);
+                table.copy_from_slice(page);
+                Page::Split(table)
+            }
+        }).collect();
+
+        MemoryMap {
+            regions: self.regions,
+            pages,
+            idle: 0,
+            decay: self.decay,
+        }
+    }
+}
+
+pub struct MemoryMap {
+    regions: Vec<Region>,
+    pages: Vec<Page>,
+    // bus accesses since the data bus was last driven
+    idle: u64,
+    decay: Option<u64>,
+}
+
+impl MemoryMap {
+    pub fn builder() -> MemoryMapBuilder {
+        MemoryMapBuilder {
+            regions: Vec::new(),
+            decay: None,
+        }
+    }
+
+    // floating bits read as 0 after this many bus accesses without the bus being driven
+    pub fn set_decay(&mut self, accesses: Option<u64>) {
+        self.decay = accesses;
+    }
+
+    #[inline]
+    fn index(&self, address: u16) -> Option<usize> {
+        let index = match self.pages[(address >> 8) as usize] {
+            Page::Region(index) => index,
+            Page::Split(ref table) => table[(address & 0xFF) as usize],
+        };
+
+        if index == NO_REGION { None } else { Some(index as usize) }
+    }
+
+    // the value floating bits read as
+    fn open_bus(&self, data: u8) -> u8 {
+        match self.decay {
+            Some(accesses) if self.idle >= accesses => 0x00,
+            _ => data,
+        }
+    }
+}
+
+impl Bus for MemoryMap {
+    fn read(&mut self, mut pinout: Pinout) -> Pinout {
+        let open_bus = self.open_bus(pinout.data);
+
+        let drive = match self.index(pinout.address) {
+            Some(index) => {
+                let region = &mut self.regions[index];
+                let offset = region.offset(pinout.address);
+                pinout = match region.backing {
+                    Backing::Ram(ref mem) | Backing::Rom(ref mem) => { pinout.data = mem[offset]; pinout },
+                    Backing::Device { ref mut read, .. } => read(pinout),
+                    Backing::Bus(ref mut bus) => bus.read(pinout),
+                    Backing::Unmapped => pinout,
+                };
+                region.drive.mask()
+            }
+            None => 0x00,
+        };
+
+        pinout.data = (pinout.data & drive) | (open_bus & !drive);
+        if drive == 0x00 {
+            self.idle += 1;
+        }
+        else {
+            self.idle = 0;
+        }
+        pinout
+    }
+
+    fn write(&mut self, pinout: Pinout) -> Pinout {
+        // the cpu drives the bus whether anything listens or not
+        self.idle = 0;
+
+        match self.index(pinout.address) {
+            Some(index) => {
+                let region = &mut self.regions[index];
+                let offset = region.offset(pinout.address);
+                match region.backing {
+                    Backing::Ram(ref mut mem) => { mem[offset] = pinout.data; pinout },
+                    Backing::Device { ref mut write, .. } => write(pinout),
+                    Backing::Bus(ref mut bus) => bus.write(pinout),
+                    Backing::Rom(_) | Backing::Unmapped => pinout,
+                }
+            }
+            None => pinout,
+        }
+    }
+
+    // every bus region sees every cycle, not only the ones addressing it
+    fn cycle(&mut self, mut pinout: Pinout) -> Pinout {
+        for region in self.regions.iter_mut() {
+            if let Backing::Bus(ref mut bus) = region.backing {
+                pinout = bus.cycle(pinout);
+            }
+        }
+        pinout
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        let region = &self.regions[self.index(address)?];
+        match region.backing {
+            Backing::Ram(ref mem) | Backing::Rom(ref mem) => Some(mem[region.offset(address)]),
+            Backing::Bus(ref bus) => bus.peek(address),
+            // a device read may have side effects
+            Backing::Device { .. } | Backing::Unmapped => None,
+        }
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        let index = match self.index(address) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let region = &mut self.regions[index];
+        let offset = region.offset(address);
+        match region.backing {
+            Backing::Ram(ref mut mem) | Backing::Rom(ref mut mem) => { mem[offset] = data; true },
+            Backing::Bus(ref mut bus) => bus.poke(address, data),
+            Backing::Device { .. } | Backing::Unmapped => false,
+        }
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match self.regions[self.index(address)?].backing {
+            Backing::Bus(ref bus) => bus.prg_rom_offset(address),
+            _ => None,
+        }
+    }
+}
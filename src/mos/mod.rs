@@ -1,8 +1,36 @@
+// the cpu core spells out flag logic explicitly (`x == false`, `if c { true } else { false }`)
+// to mirror the datasheet descriptions, keep clippy from rewriting that style there
+#[allow(clippy::bool_comparison, clippy::assign_op_pattern, clippy::identity_op, clippy::let_and_return)]
 mod core;
+mod dispatch;
+mod fast;
+#[allow(clippy::bool_comparison, clippy::needless_bool, clippy::assign_op_pattern)]
 mod instructions;
+#[allow(clippy::bool_comparison, clippy::needless_bool)]
 mod operations;
+pub mod apu;
+pub mod asm;
+pub mod block;
 pub mod bus;
+pub mod cartridge;
+pub mod cdl;
+pub mod clock;
+pub mod disasm;
+pub mod irq;
+pub mod loader;
+pub mod memory_map;
+pub mod nsf;
+pub mod power;
+pub mod profiler;
 pub mod rp2a03;
+pub mod rp2a07;
+pub mod stats;
+pub mod symbols;
+pub mod timing;
+pub mod unstable;
+
+pub use self::core::InterruptState;
+pub use self::dispatch::AddressingMode;
 
 use bitflags;
 
@@ -43,12 +71,13 @@ bitflags! {
         const NMI =  0b00001000;    // /NMI non maskable interrupt
         const RDY =  0b00010000;    // RDY cpu is ready /RDY cpu is not ready and is paused during next read cycle
         const HALT = 0b00100000;    // /HALT cpu is halted, only on Atari "SALLY" cpu
+        const RES =  0b01000000;    // /RES reset, the reset sequence runs once the pin is released
     }
 }
 
 impl Default for Ctrl {
     fn default() -> Ctrl {
-        Ctrl::RW | Ctrl::IRQ | Ctrl::NMI | Ctrl::RDY | Ctrl::HALT
+        Ctrl::RW | Ctrl::IRQ | Ctrl::NMI | Ctrl::RDY | Ctrl::HALT | Ctrl::RES
     }
 }
 
@@ -74,4 +103,10 @@ impl Pinout {
             ctrl: Default::default(),
         }
     }
+}
+
+impl Default for Pinout {
+    fn default() -> Pinout {
+        Pinout::new()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,588 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use super::{Ctrl, Pinout};
+use super::apu::Apu;
+use super::bus::Bus;
+use super::cartridge::{ExpansionAudio, ExpansionChip};
+use super::clock::Region;
+use super::power::PowerOnProfile;
+use super::rp2a03::Rp2a03;
+
+/*
+NSF and NSFe music files
+
+An NSF is a 128 byte header and the program data, an NSFe the same fields in tagged chunks
+with track names and times. Both hold 6502 code with an init routine called once per track
+with the track in A and the region in X, and a play routine called at the rate from the
+header, 60hz or 50hz for most tunes.
+
+The player plays the part of the hardware players. Its own code sits at $4100-$4114 and the
+vectors at $FFFA-$FFFF always point into it: reset calls init, then every play period an nmi
+calls play, as long as the last call has returned. Tunes with bank switching see 4k banks of
+the data selected through $5FF8-$5FFF, the data before the load address in its first bank is
+padded with zero.
+
+The VRC6, Sunsoft 5B and Namco 163 sound chips the header enables are run beside the apu and
+mixed into its output with Apu::mix. Writes to the VRC7, FDS and MMC5 are ignored and the irq
+of NSF2 is not supported.
+*/
+
+// µs per second
+const MICROSECONDS: u64 = 1_000_000;
+const STUB: u16 = 0x4100;
+const INIT_ENTRY: u16 = STUB;
+const IDLE: u16 = STUB + 0x0A;
+const PLAY_ENTRY: u16 = STUB + 0x0D;
+const IRQ_ENTRY: u16 = STUB + 0x14;
+// a write here tells the player a routine returned
+const RETURNED: u16 = 0x4120;
+
+#[derive(Debug)]
+pub enum NsfError {
+    Io(io::Error),
+    TooShort,
+    BadMagic,
+    // a required NSFe chunk is missing
+    MissingChunk([u8; 4]),
+    // an NSFe chunk marked as required that is not known
+    UnknownChunk([u8; 4]),
+    // data below $8000 without bank switching
+    BadLoadAddress(u16),
+    BadTrack { track: u8, songs: u8 },
+}
+
+impl fmt::Display for NsfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NsfError::Io(ref err) => write!(f, "could not read the nsf: {}", err),
+            NsfError::TooShort => write!(f, "nsf is shorter than its header"),
+            NsfError::BadMagic => write!(f, "file does not start with NESM<EOF> or NSFE"),
+            NsfError::MissingChunk(id) => write!(f, "nsfe has no {} chunk", String::from_utf8_lossy(&id)),
+            NsfError::UnknownChunk(id) => write!(f, "nsfe requires the unknown chunk {}", String::from_utf8_lossy(&id)),
+            NsfError::BadLoadAddress(address) => write!(f, "load address ${:04X} is below $8000", address),
+            NsfError::BadTrack { track, songs } => write!(f, "track {} is out of range, the nsf has {} tracks", track + 1, songs),
+        }
+    }
+}
+
+impl std::error::Error for NsfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            NsfError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NsfError {
+    fn from(err: io::Error) -> NsfError {
+        NsfError::Io(err)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NsfFormat {
+    Nsf,
+    Nsfe,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Nsf {
+    pub format: NsfFormat,
+    pub songs: u8,
+    // zero based
+    pub starting_song: u8,
+    pub load_address: u16,
+    pub init_address: u16,
+    pub play_address: u16,
+    pub title: String,
+    pub artist: String,
+    pub copyright: String,
+    // play period in µs
+    pub ntsc_speed: u16,
+    pub pal_speed: u16,
+    // initial banks for $8000-$FFFF, None without bank switching
+    pub banks: Option<[u8; 8]>,
+    pub pal: bool,
+    pub dual_region: bool,
+    // bit 0 VRC6, 1 VRC7, 2 FDS, 3 MMC5, 4 N163, 5 Sunsoft 5B
+    pub expansion: u8,
+    // NSFe only, empty or one per track, times and fades in ms
+    pub track_labels: Vec<String>,
+    pub track_times: Vec<Option<u32>>,
+    pub track_fades: Vec<Option<u32>>,
+    pub data: Vec<u8>,
+}
+
+impl Nsf {
+    pub fn from_bytes(data: &[u8]) -> Result<Nsf, NsfError> {
+        if data.len() >= 4 && &data[0..4] == b"NSFE" {
+            return Nsf::parse_nsfe(&data[4..]);
+        }
+        if data.len() < 0x80 {
+            return Err(if data.starts_with(b"NESM\x1A") || data.len() < 5 { NsfError::TooShort } else { NsfError::BadMagic });
+        }
+        if &data[0..5] != b"NESM\x1A" {
+            return Err(NsfError::BadMagic);
+        }
+
+        let banks = bank_init(&data[0x70..0x78]);
+        // NSF2 gives the program length to leave room for metadata after it
+        let length = le24(&data[0x7D..0x80]);
+        let program = &data[0x80..];
+        let program = if data[5] >= 2 && length > 0 { &program[..length.min(program.len())] } else { program };
+
+        let nsf = Nsf {
+            format: NsfFormat::Nsf,
+            songs: data[6],
+            starting_song: data[7].saturating_sub(1),
+            load_address: le16(&data[0x08..]),
+            init_address: le16(&data[0x0A..]),
+            play_address: le16(&data[0x0C..]),
+            title: string(&data[0x0E..0x2E]),
+            artist: string(&data[0x2E..0x4E]),
+            copyright: string(&data[0x4E..0x6E]),
+            ntsc_speed: le16(&data[0x6E..]),
+            pal_speed: le16(&data[0x78..]),
+            banks,
+            pal: data[0x7A] & 0x01 != 0,
+            dual_region: data[0x7A] & 0x02 != 0,
+            expansion: data[0x7B],
+            track_labels: Vec::new(),
+            track_times: Vec::new(),
+            track_fades: Vec::new(),
+            data: program.to_vec(),
+        };
+        nsf.check()?;
+        Ok(nsf)
+    }
+
+    // chunks are a length, a four byte id and the data, an id starting in upper case must be
+    // understood to play the file
+    fn parse_nsfe(mut chunks: &[u8]) -> Result<Nsf, NsfError> {
+        let mut nsf = Nsf {
+            format: NsfFormat::Nsfe,
+            songs: 1,
+            starting_song: 0,
+            load_address: 0,
+            init_address: 0,
+            play_address: 0,
+            title: String::new(),
+            artist: String::new(),
+            copyright: String::new(),
+            ntsc_speed: 16639,
+            pal_speed: 19997,
+            banks: None,
+            pal: false,
+            dual_region: false,
+            expansion: 0,
+            track_labels: Vec::new(),
+            track_times: Vec::new(),
+            track_fades: Vec::new(),
+            data: Vec::new(),
+        };
+        let mut info = false;
+        let mut program = false;
+
+        loop {
+            if chunks.len() < 8 {
+                return Err(NsfError::TooShort);
+            }
+            let length = le32(chunks) as usize;
+            let id = [chunks[4], chunks[5], chunks[6], chunks[7]];
+            let body = chunks.get(8..8 + length).ok_or(NsfError::TooShort)?;
+            chunks = &chunks[8 + length..];
+
+            match &id {
+                b"INFO" => {
+                    if body.len() < 8 {
+                        return Err(NsfError::TooShort);
+                    }
+                    nsf.load_address = le16(body);
+                    nsf.init_address = le16(&body[2..]);
+                    nsf.play_address = le16(&body[4..]);
+                    nsf.pal = body[6] & 0x01 != 0;
+                    nsf.dual_region = body[6] & 0x02 != 0;
+                    nsf.expansion = body[7];
+                    nsf.songs = body.get(8).cloned().unwrap_or(1);
+                    nsf.starting_song = body.get(9).cloned().unwrap_or(0);
+                    info = true;
+                }
+                b"DATA" => {
+                    nsf.data = body.to_vec();
+                    program = true;
+                }
+                b"NEND" => break,
+                b"BANK" => {
+                    let mut banks = [0; 8];
+                    banks[..body.len().min(8)].copy_from_slice(&body[..body.len().min(8)]);
+                    nsf.banks = Some(banks);
+                }
+                b"RATE" => {
+                    if body.len() >= 2 {
+                        nsf.ntsc_speed = le16(body);
+                    }
+                    if body.len() >= 4 {
+                        nsf.pal_speed = le16(&body[2..]);
+                    }
+                }
+                b"auth" => {
+                    let mut strings = strings(body).into_iter();
+                    nsf.title = strings.next().unwrap_or_default();
+                    nsf.artist = strings.next().unwrap_or_default();
+                    nsf.copyright = strings.next().unwrap_or_default();
+                }
+                b"tlbl" => nsf.track_labels = strings(body),
+                b"time" => nsf.track_times = body.chunks_exact(4).map(milliseconds).collect(),
+                b"fade" => nsf.track_fades = body.chunks_exact(4).map(milliseconds).collect(),
+                _ if id[0].is_ascii_uppercase() => return Err(NsfError::UnknownChunk(id)),
+                _ => {}
+            }
+        }
+
+        if !info {
+            return Err(NsfError::MissingChunk(*b"INFO"));
+        }
+        if !program {
+            return Err(NsfError::MissingChunk(*b"DATA"));
+        }
+        nsf.check()?;
+        Ok(nsf)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Nsf, NsfError> {
+        let data = fs::read(path)?;
+        Nsf::from_bytes(&data)
+    }
+
+    fn check(&self) -> Result<(), NsfError> {
+        // banked data is placed by the low 12 bits of the load address alone
+        if self.load_address < 0x8000 && self.banks.is_none() {
+            return Err(NsfError::BadLoadAddress(self.load_address));
+        }
+        Ok(())
+    }
+
+    // the region the tune was written for, dual region tunes play as ntsc
+    pub fn region(&self) -> Region {
+        if self.pal && !self.dual_region { Region::Pal } else { Region::Ntsc }
+    }
+
+    // play period in cpu cycles
+    pub fn play_period(&self, region: Region) -> u64 {
+        let speed = match region {
+            Region::Pal => if self.pal_speed == 0 { 19997 } else { self.pal_speed },
+            _ => if self.ntsc_speed == 0 { 16639 } else { self.ntsc_speed },
+        };
+        speed as u64 * region.cpu_clock() as u64 / MICROSECONDS
+    }
+}
+
+fn le16(data: &[u8]) -> u16 {
+    data[0] as u16 | (data[1] as u16) << 8
+}
+
+fn le24(data: &[u8]) -> usize {
+    data[0] as usize | (data[1] as usize) << 8 | (data[2] as usize) << 16
+}
+
+fn le32(data: &[u8]) -> u32 {
+    le24(data) as u32 | (data[3] as u32) << 24
+}
+
+// negative times are unknown
+fn milliseconds(data: &[u8]) -> Option<u32> {
+    let time = le32(data) as i32;
+    if time < 0 { None } else { Some(time as u32) }
+}
+
+// a zero padded header field
+fn string(data: &[u8]) -> String {
+    let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).into_owned()
+}
+
+// zero terminated strings one after the other
+fn strings(data: &[u8]) -> Vec<String> {
+    let data = data.strip_suffix(&[0]).unwrap_or(data);
+    data.split(|b| *b == 0).map(string).collect()
+}
+
+// banks that are all zero mean no bank switching
+fn bank_init(data: &[u8]) -> Option<[u8; 8]> {
+    if data.iter().all(|b| *b == 0) {
+        return None;
+    }
+    let mut banks = [0; 8];
+    banks.copy_from_slice(data);
+    Some(banks)
+}
+
+struct NsfBus {
+    ram: [u8; 0x800],
+    prg_ram: [u8; 0x2000],
+    // the program in 4k banks
+    rom: Vec<u8>,
+    banks: [usize; 8],
+    bank_switching: bool,
+    apu: Apu,
+    expansion: Vec<ExpansionAudio>,
+    stub: [u8; 0x15],
+    returned: bool,
+}
+
+impl NsfBus {
+    fn new(nsf: &Nsf, region: Region, track: u8) -> NsfBus {
+        let (rom, banks) = match nsf.banks {
+            Some(init) => {
+                let padding = (nsf.load_address & 0x0FFF) as usize;
+                let mut rom = vec![0; padding];
+                rom.extend_from_slice(&nsf.data);
+                rom.resize((rom.len() + 0x0FFF) & !0x0FFF, 0);
+                let mut banks = [0; 8];
+                for (bank, init) in banks.iter_mut().zip(init.iter()) {
+                    *bank = *init as usize * 0x1000 % rom.len();
+                }
+                (rom, banks)
+            }
+            None => {
+                let mut rom = vec![0; 0x8000];
+                let start = (nsf.load_address - 0x8000) as usize;
+                let length = nsf.data.len().min(0x8000 - start);
+                rom[start..start + length].copy_from_slice(&nsf.data[..length]);
+                (rom, [0x0000, 0x1000, 0x2000, 0x3000, 0x4000, 0x5000, 0x6000, 0x7000])
+            }
+        };
+
+        let init = nsf.init_address.to_le_bytes();
+        let play = nsf.play_address.to_le_bytes();
+        let idle = IDLE.to_le_bytes();
+        let returned = RETURNED.to_le_bytes();
+        let pal = if region == Region::Pal { 1 } else { 0 };
+        let stub = [
+            // init entry: LDA #track, LDX #region, JSR init, STA returned
+            0xA9, track, 0xA2, pal, 0x20, init[0], init[1], 0x8D, returned[0], returned[1],
+            // idle: JMP idle
+            0x4C, idle[0], idle[1],
+            // play entry: JSR play, STA returned, RTI
+            0x20, play[0], play[1], 0x8D, returned[0], returned[1], 0x40,
+            // irq entry: RTI
+            0x40,
+        ];
+
+        NsfBus {
+            ram: [0; 0x800],
+            prg_ram: [0; 0x2000],
+            rom,
+            banks,
+            bank_switching: nsf.banks.is_some(),
+            apu: Apu::new(region),
+            expansion: expansion_chips(nsf.expansion).into_iter().map(ExpansionAudio::new).collect(),
+            stub,
+            returned: false,
+        }
+    }
+
+    // the apu with the expansion chips mixed in
+    fn output(&self) -> f32 {
+        let expansion: f32 = self.expansion.iter().map(|chip| chip.audio()).sum();
+        self.apu.mix(expansion)
+    }
+}
+
+// the chips of the header's expansion bits that are emulated
+fn expansion_chips(expansion: u8) -> Vec<ExpansionChip> {
+    let chips = [(0x01, ExpansionChip::Vrc6), (0x10, ExpansionChip::N163), (0x20, ExpansionChip::Sunsoft5b)];
+    chips.iter().filter(|(bit, _)| expansion & bit != 0).map(|(_, chip)| *chip).collect()
+}
+
+impl Bus for NsfBus {
+    fn read(&mut self, mut pinout: Pinout) -> Pinout {
+        if pinout.address == 0x4015 {
+            pinout.data = self.apu.read_status();
+        }
+        else if let Some(data) = self.expansion.iter_mut().find_map(|chip| chip.read(pinout.address)) {
+            pinout.data = data;
+        }
+        else if let Some(data) = self.peek(pinout.address) {
+            pinout.data = data;
+        }
+        pinout
+    }
+
+    fn write(&mut self, pinout: Pinout) -> Pinout {
+        let (address, data) = (pinout.address, pinout.data);
+        for chip in self.expansion.iter_mut() {
+            chip.write(address, data);
+        }
+        match address {
+            0x0000..=0x1FFF => self.ram[(address & 0x07FF) as usize] = data,
+            0x4000..=0x4017 => self.apu.write(address, data),
+            RETURNED => self.returned = true,
+            0x5FF8..=0x5FFF if self.bank_switching => {
+                self.banks[(address - 0x5FF8) as usize] = data as usize * 0x1000 % self.rom.len();
+            }
+            0x6000..=0x7FFF => self.prg_ram[(address & 0x1FFF) as usize] = data,
+            _ => {}
+        }
+        pinout
+    }
+
+    fn cycle(&mut self, pinout: Pinout) -> Pinout {
+        self.apu.clock();
+        for chip in self.expansion.iter_mut() {
+            chip.cycle();
+        }
+        if let Some(address) = self.apu.dmc_request() {
+            let data = self.peek(address).unwrap_or(0);
+            self.apu.dmc_fill(data);
+        }
+        pinout
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        let vector = |entry: u16| Some(entry.to_le_bytes()[(address & 0x01) as usize]);
+        match address {
+            0x0000..=0x1FFF => Some(self.ram[(address & 0x07FF) as usize]),
+            0x4015 => Some(self.apu.peek_status()),
+            0x4100..=0x4114 => Some(self.stub[(address - STUB) as usize]),
+            0x6000..=0x7FFF => Some(self.prg_ram[(address & 0x1FFF) as usize]),
+            0xFFFA..=0xFFFB => vector(PLAY_ENTRY),
+            0xFFFC..=0xFFFD => vector(INIT_ENTRY),
+            0xFFFE..=0xFFFF => vector(IRQ_ENTRY),
+            0x8000..=0xFFF9 => {
+                let bank = self.banks[((address - 0x8000) >> 12) as usize];
+                Some(self.rom[bank + (address & 0x0FFF) as usize])
+            }
+            _ => None,
+        }
+    }
+}
+
+pub struct NsfPlayer {
+    cpu: Rp2a03,
+    pinout: Pinout,
+    bus: NsfBus,
+    region: Region,
+    play_period: u64,
+    next_play: u64,
+    // init or play has been called and not returned
+    busy: bool,
+    cycles: u64,
+    // cpu cycles owed to the next sample
+    sample_time: f64,
+    // dc blocking filter, last input and output
+    filter: (f32, f32),
+}
+
+impl NsfPlayer {
+    // track is zero based, the region defaults to the one the tune was written for
+    pub fn new(nsf: &Nsf, track: u8, region: Option<Region>) -> Result<NsfPlayer, NsfError> {
+        if track >= nsf.songs {
+            return Err(NsfError::BadTrack { track, songs: nsf.songs });
+        }
+        let region = region.unwrap_or_else(|| nsf.region());
+        let mut bus = NsfBus::new(nsf, region, track);
+
+        // the apu state the nsf spec promises to init
+        for address in 0x4000..0x4014 {
+            bus.apu.write(address, 0x00);
+        }
+        bus.apu.write(0x4015, 0x0F);
+        bus.apu.write(0x4017, 0x40);
+
+        let (cpu, pinout) = Rp2a03::from_power_on_region(region, PowerOnProfile::default());
+        let play_period = nsf.play_period(region);
+        Ok(NsfPlayer {
+            cpu,
+            pinout,
+            bus,
+            region,
+            play_period,
+            next_play: play_period,
+            busy: true,
+            cycles: 0,
+            sample_time: 0.0,
+            filter: (0.0, 0.0),
+        })
+    }
+
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    pub fn apu(&self) -> &Apu {
+        &self.bus.apu
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    // the tune's memory as the cpu sees it, see Bus::peek
+    pub fn peek(&self, address: u16) -> Option<u8> {
+        self.bus.peek(address)
+    }
+
+    // one cpu cycle, play is called through nmi once the previous call returned
+    pub fn tick(&mut self) {
+        if self.bus.returned {
+            self.bus.returned = false;
+            self.busy = false;
+        }
+        if !self.busy && self.cycles >= self.next_play {
+            self.pinout.ctrl.remove(Ctrl::NMI);
+            self.busy = true;
+            // a play routine running long skips the periods it missed
+            self.next_play = (self.next_play + self.play_period).max(self.cycles + 1);
+        }
+        self.pinout = self.cpu.tick(&mut self.bus, self.pinout);
+        self.cycles += 1;
+    }
+
+    // fill samples at sample_rate, each the average of the apu and expansion audio output over
+    // its cpu cycles
+    pub fn render(&mut self, sample_rate: u32, samples: &mut [f32]) {
+        let cycles_per_sample = self.region.cpu_clock() as f64 / sample_rate as f64;
+        for sample in samples.iter_mut() {
+            self.sample_time += cycles_per_sample;
+            let mut sum = 0.0;
+            let mut count = 0;
+            while self.sample_time >= 1.0 {
+                self.tick();
+                sum += self.bus.output();
+                count += 1;
+                self.sample_time -= 1.0;
+            }
+            let input = if count == 0 { self.filter.0 } else { sum / count as f32 };
+            let output = input - self.filter.0 + 0.995 * self.filter.1;
+            self.filter = (input, output);
+            *sample = output;
+        }
+    }
+}
+
+// 16 bit mono pcm, samples are clamped to -1.0 to 1.0
+pub fn write_wav<W: Write>(mut writer: W, sample_rate: u32, samples: &[f32]) -> io::Result<()> {
+    let data_len = samples.len() as u32 * 2;
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_len).to_le_bytes())?;
+    writer.write_all(b"WAVEfmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    // pcm, one channel
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * 2).to_le_bytes())?;
+    writer.write_all(&2u16.to_le_bytes())?;
+    writer.write_all(&16u16.to_le_bytes())?;
+    writer.write_all(b"data")?;
+    writer.write_all(&data_len.to_le_bytes())?;
+    for sample in samples {
+        let sample = (sample.clamp(-1.0, 1.0) * 32767.0) as i16;
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+    Ok(())
+}
@@ -1,22 +1,31 @@
 use super::{Ctrl, Pinout};
 use super::core::*;
 use super::instructions::Instruction;
-use super::bus::Bus;
+use super::bus::{Bus, Sequence};
+use super::dispatch::RESET_TM;
 
 const fn to_address(hb: u8, lb: u8) -> u16 {
     (hb as u16) << 8 | (lb as u16) 
 }
 
-fn poll_interrupts(cpu: &mut Context, pinout: Pinout) {
-    // nmi is edge detected, only needs to be held one cycle to set flag
-    if cpu.nmi_detected == true {
+/*
+The interrupt lines and the i flag are sampled at the end of every cycle, an instruction
+acts on what was latched at the end of its penultimate cycle. A CLI, SEI or PLP changes
+the i flag on the last cycle so the change is only seen by the next instruction, RTI
+pulls the flags early enough to take effect immediately.
+
+Read and implied instructions finish on the opcode fetch of the next instruction, their
+poll is one cycle later than the others and uses the latch from the cycle before.
+*/
+pub fn poll_interrupts(cpu: &mut Context, latch: InterruptLatch) {
+    if latch.nmi == true {
         cpu.ints = InterruptState::Nmi;
         cpu.ops.reset();
         cpu.ir.reset(0x00);
         cpu.nmi_detected = false;
     }
     // irq is level detected and must be held every cycle until handled
-    else if pinout.ctrl.contains(Ctrl::IRQ) == false && cpu.p.interrupt_disable == false {
+    else if latch.irq == true {
         cpu.ints = InterruptState::Irq;
         cpu.ops.reset();
         cpu.ir.reset(0x00);
@@ -57,8 +66,8 @@ macro_rules! second_cycle {
 }
 
 macro_rules! last_cycle {
-    ($cpu:ident, $pinout:ident) => {
-        poll_interrupts($cpu, $pinout);
+    ($cpu:ident, $pinout:ident, $latch:ident) => {
+        poll_interrupts($cpu, $cpu.$latch);
         if $cpu.ints != InterruptState::None {
             return $pinout;
         }
@@ -92,47 +101,75 @@ macro_rules! write_cycle {
 //===================================================
 // Reset
 //====================================================
+// RES held low, the cpu is put back at the start of the reset sequence every cycle
+pub fn rst_hold<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
+    // a power on reset that has not started yet stays cold
+    if cpu.ir.opcode != 0x00 || cpu.ir.tm != RESET_TM {
+        cpu.reset = ResetKind::Warm;
+    }
+
+    cpu.ir.opcode = 0x00;
+    cpu.ir.tm = RESET_TM;
+    cpu.ints = InterruptState::None;
+    cpu.ops.reset();
+    cpu.first_cycle = false;
+    cpu.jammed = false;
+
+    pinout.ctrl.set(Ctrl::SYNC, false);
+    pinout.ctrl.set(Ctrl::RW, true);
+    pinout.address = u16::from(cpu.pc);
+    pinout = bus.read(pinout);
+    pinout
+}
+
 pub fn rst_c0<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
-    cpu.a = 0xAA;
-    cpu.p = FlagsRegister::from(0x24);
-    cpu.pc = ProgramCounter::from(0x00FF);
-    read_cycle!(cpu, bus, pinout, 0x00FF);
+    // registers hold their power on values or are kept from before the reset
+    if cpu.reset == ResetKind::Cold {
+        cpu.pc = ProgramCounter::from(0x00FF);
+    }
+    cpu.p.interrupt_disable = true;
+
+    read_cycle!(cpu, bus, pinout, u16::from(cpu.pc));
 
     pinout
 }
 
 pub fn rst_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
-    read_cycle!(cpu, bus, pinout, 0x00FF);
+    read_cycle!(cpu, bus, pinout, u16::from(cpu.pc));
 
     pinout
 }
 
 pub fn rst_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
-    read_cycle!(cpu, bus, pinout, 0x00FF);
+    read_cycle!(cpu, bus, pinout, u16::from(cpu.pc));
 
     pinout
 }
 
+// the three pushes of the interrupt sequence with writes suppressed
 pub fn rst_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
-    read_cycle!(cpu, bus, pinout, 0x0100);
+    read_cycle!(cpu, bus, pinout, to_address(0x01, cpu.sp));
+    cpu.sp = cpu.sp.wrapping_sub(1);
 
     pinout
 }
 
 pub fn rst_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
-    read_cycle!(cpu, bus, pinout, 0x01FF);
+    read_cycle!(cpu, bus, pinout, to_address(0x01, cpu.sp));
+    cpu.sp = cpu.sp.wrapping_sub(1);
 
     pinout
 }
 
 pub fn rst_c5<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
-    read_cycle!(cpu, bus, pinout, 0x01FE);
-    cpu.sp = 0xFD;
+    read_cycle!(cpu, bus, pinout, to_address(0x01, cpu.sp));
+    cpu.sp = cpu.sp.wrapping_sub(1);
 
     pinout
 }
 
 pub fn rst_c6<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
+    bus.vector(Sequence::Reset);
     read_cycle!(cpu, bus, pinout, 0xFFFC);
     cpu.ops.adl = cpu.ops.dl;
 
@@ -151,7 +188,9 @@ pub fn rst_c8<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     cpu.pc.pcl = cpu.ops.adl;
     cpu.pc.pch = cpu.ops.adh;
     // kludge to match nestest.log cycle timing after reset
-    cpu.cycle = 6;
+    if cpu.reset == ResetKind::Cold {
+        cpu.cycle = 6;
+    }
     first_cycle!(cpu, bus, pinout);
 
     pinout
@@ -164,12 +203,14 @@ pub fn brk_c0<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     // read instruction byte (discarded)
     second_cycle!(cpu, bus, pinout);
+    // brk skips the byte after the opcode, an interrupt returns to the opcode it replaced
+    if cpu.ints == InterruptState::None { cpu.pc.increment(); }
     pinout
 }
 
 pub fn brk_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     // write pch to stack
-    write_cycle!(cpu, bus, pinout, to_address(0, cpu.sp), cpu.pc.pch);
+    write_cycle!(cpu, bus, pinout, to_address(0x1, cpu.sp), cpu.pc.pch);
     // decrement sp
     cpu.sp = cpu.sp.wrapping_sub(1);
     pinout
@@ -177,22 +218,24 @@ pub fn brk_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
 
 pub fn brk_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     // write pcl  to stack
-    write_cycle!(cpu, bus, pinout, to_address(0, cpu.sp), cpu.pc.pcl);
+    write_cycle!(cpu, bus, pinout, to_address(0x1, cpu.sp), cpu.pc.pcl);
     // decrement sp
     cpu.sp = cpu.sp.wrapping_sub(1);
-    // check for hijack
-    cpu.ints = match cpu.ints {
-        InterruptState::None if cpu.nmi_detected == true => { InterruptState::BrkHijack },
-        InterruptState::Irq if cpu.nmi_detected == true => { InterruptState::IrqHijack },
-        _ => cpu.ints,
-    };
-
-    cpu.nmi_detected = false;
-
     pinout
 }
 
 pub fn brk_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
+    // an nmi latched by the end of the fourth cycle hijacks the vector fetch, it is handled here
+    // and will not be taken again
+    if cpu.latch.nmi == true {
+        cpu.ints = match cpu.ints {
+            InterruptState::None => InterruptState::BrkHijack,
+            InterruptState::Irq => InterruptState::IrqHijack,
+            _ => cpu.ints,
+        };
+        cpu.nmi_detected = false;
+    }
+
     // write status reg to stack
     let status_reg = match cpu.ints {
         // if no interupts, must be brk instruction
@@ -203,7 +246,7 @@ pub fn brk_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
         InterruptState::Nmi => cpu.p.push_with_b_clear(),
     };
 
-    write_cycle!(cpu, bus, pinout, to_address(0, cpu.sp), status_reg);
+    write_cycle!(cpu, bus, pinout, to_address(0x1, cpu.sp), status_reg);
 
     // decrement sp
     cpu.sp = cpu.sp.wrapping_sub(1);
@@ -218,6 +261,13 @@ pub fn brk_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
         InterruptState::Irq => to_address(0xFF, 0xFE),
         InterruptState::Nmi | InterruptState::BrkHijack | InterruptState::IrqHijack => to_address(0xFF, 0xFA),
     };
+    bus.vector(match cpu.ints {
+        InterruptState::None => Sequence::Brk,
+        InterruptState::Irq => Sequence::Irq,
+        InterruptState::Nmi => Sequence::Nmi,
+        InterruptState::BrkHijack => Sequence::BrkHijack,
+        InterruptState::IrqHijack => Sequence::IrqHijack,
+    });
 
     read_cycle!(cpu, bus, pinout, addr);
     cpu.pc.pcl = cpu.ops.dl;
@@ -228,7 +278,6 @@ pub fn brk_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
 
 pub fn brk_c5<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
-    cpu.ints = InterruptState::None;
     // set to_address to fetch pch
     let addr = match cpu.ints {
         InterruptState::None => to_address(0xFF, 0xFF),
@@ -238,6 +287,7 @@ pub fn brk_c5<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
 
     read_cycle!(cpu, bus, pinout, addr);
     cpu.pc.pch = cpu.ops.dl;
+    cpu.ints = InterruptState::None;
     pinout
 }
 
@@ -247,6 +297,42 @@ pub fn brk_c6<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     pinout
 }
 
+//===================================================
+// Jam
+//====================================================
+pub fn jam_c0<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
+    if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
+    // read byte after opcode (discarded), pc is never moved again
+    second_cycle!(cpu, bus, pinout);
+    // interrupts are no longer polled, only a reset leaves this state
+    cpu.jammed = true;
+    pinout
+}
+
+pub fn jam_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
+    read_cycle!(cpu, bus, pinout, 0xFFFF);
+    pinout
+}
+
+pub fn jam_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
+    read_cycle!(cpu, bus, pinout, 0xFFFE);
+    pinout
+}
+
+pub fn jam_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
+    read_cycle!(cpu, bus, pinout, 0xFFFE);
+    pinout
+}
+
+pub fn jam_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
+    // address bus is held at $FFFF, the timing step no longer advances
+    pinout.ctrl.set(Ctrl::RW, true);
+    pinout.address = 0xFFFF;
+    pinout = bus.read(pinout);
+    cpu.ops.dl = pinout.data;
+    pinout
+}
+
 //==========================================================
 // single byte instructions
 //===========================================================
@@ -259,7 +345,7 @@ pub fn single_byte_c0<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout
 pub fn single_byte_c1<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -278,7 +364,7 @@ pub fn immediate_read_c0<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pin
 pub fn immediate_read_c1<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -305,7 +391,7 @@ pub fn zeropage_read_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pino
 pub fn zeropage_read_c2<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -342,7 +428,7 @@ pub fn absolute_read_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pino
 pub fn absolute_read_c3<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -390,7 +476,7 @@ pub fn indirect_x_read_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pi
 pub fn indirect_x_read_c5<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -438,7 +524,7 @@ pub fn absolute_x_read_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pi
 pub fn absolute_x_read_c4<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -486,7 +572,7 @@ pub fn absolute_y_read_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pi
 pub fn absolute_y_read_c4<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -519,7 +605,7 @@ pub fn zeropage_x_read_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pi
 pub fn zeropage_x_read_c3<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -552,7 +638,7 @@ pub fn zeropage_y_read_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pi
 pub fn zeropage_y_read_c3<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -609,7 +695,7 @@ pub fn indirect_y_read_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pi
 pub fn indirect_y_read_c5<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     T::execute(cpu);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, prev_latch);
     // if no interrupt do first cycle
     first_cycle!(cpu, bus, pinout);
     pinout
@@ -630,7 +716,7 @@ pub fn zeropage_store_c0<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pin
 pub fn zeropage_store_c1<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     T::execute(cpu);
     write_cycle!(cpu, bus, pinout, to_address(0, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -665,7 +751,7 @@ pub fn absolute_store_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pin
 pub fn absolute_store_c2<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     T::execute(cpu);
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -715,7 +801,7 @@ pub fn indirect_x_store_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 pub fn indirect_x_store_c4<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     T::execute(cpu);
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -748,7 +834,8 @@ pub fn absolute_x_store_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 }
 
 pub fn absolute_x_store_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
-    if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
+    // remembered for the unstable stores
+    if pinout.ctrl.contains(Ctrl::RDY) == false { cpu.ops.rdy_stall = true; return pinout; }
     let bal = cpu.ops.bal.overflowing_add(cpu.x);
     cpu.ops.adl = bal.0;
     cpu.ops.adh = cpu.ops.bah.wrapping_add(bal.1 as u8);
@@ -761,7 +848,7 @@ pub fn absolute_x_store_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 pub fn absolute_x_store_c3<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     T::execute(cpu);
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -794,7 +881,8 @@ pub fn absolute_y_store_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 }
 
 pub fn absolute_y_store_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
-    if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
+    // remembered for the unstable stores
+    if pinout.ctrl.contains(Ctrl::RDY) == false { cpu.ops.rdy_stall = true; return pinout; }
     let bal = cpu.ops.bal.overflowing_add(cpu.y);
     cpu.ops.adl = bal.0;
     cpu.ops.adh = cpu.ops.bah.wrapping_add(bal.1 as u8);
@@ -807,7 +895,7 @@ pub fn absolute_y_store_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 pub fn absolute_y_store_c3<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     T::execute(cpu);
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -840,7 +928,7 @@ pub fn zeropage_x_store_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 pub fn zeropage_x_store_c2<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     T::execute(cpu);
     write_cycle!(cpu, bus, pinout, to_address(0, cpu.ops.bal.wrapping_add(cpu.x)), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -873,7 +961,7 @@ pub fn zeropage_y_store_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 pub fn zeropage_y_store_c2<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     T::execute(cpu);
     write_cycle!(cpu, bus, pinout, to_address(0, cpu.ops.bal.wrapping_add(cpu.y)), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -913,11 +1001,15 @@ pub fn indirect_y_store_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 }
 
 pub fn indirect_y_store_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
-    if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
-    cpu.ops.adl = cpu.ops.bal.wrapping_add(cpu.y);
+    // remembered for the unstable stores
+    if pinout.ctrl.contains(Ctrl::RDY) == false { cpu.ops.rdy_stall = true; return pinout; }
+    let adl = cpu.ops.bal.overflowing_add(cpu.y);
+    cpu.ops.adl = adl.0;
+    // the dummy read is made before the carry is added to the high byte
     cpu.ops.adh = cpu.ops.bah;
     // data discarded
     read_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl));
+    cpu.ops.adh = cpu.ops.bah.wrapping_add(adl.1 as u8);
 
     pinout
 }
@@ -925,7 +1017,7 @@ pub fn indirect_y_store_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 pub fn indirect_y_store_c4<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     T::execute(cpu);
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -966,7 +1058,7 @@ pub fn zeropage_modify_c2<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B
 pub fn zeropage_modify_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     // write modified data
     write_cycle!(cpu, bus, pinout, to_address(0, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -1016,7 +1108,7 @@ pub fn absolute_modify_c3<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B
 pub fn absolute_modify_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     // write modified data
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -1066,7 +1158,7 @@ pub fn zeropage_x_modify_c3<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut
 pub fn zeropage_x_modify_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     // write modified data
     write_cycle!(cpu, bus, pinout, to_address(0, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -1103,7 +1195,7 @@ pub fn absolute_x_modify_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout:
     // data discarded
     let adl =  cpu.ops.bal.overflowing_add(cpu.x);
     cpu.ops.adl = adl.0;
-    cpu.ops.adh = cpu.ops.bah + (adl.1 as u8);
+    cpu.ops.adh = cpu.ops.bah.wrapping_add(adl.1 as u8);
     read_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl));
 
     pinout
@@ -1127,7 +1219,7 @@ pub fn absolute_x_modify_c4<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut
 pub fn absolute_x_modify_c5<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     // write modified data
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
 
     pinout
 }
@@ -1153,7 +1245,7 @@ pub fn php_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     write_cycle!(cpu, bus, pinout, to_address(0x1, cpu.sp), cpu.ops.dl);
     // decrement stack pointer
     cpu.sp = cpu.sp.wrapping_sub(1);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1178,7 +1270,7 @@ pub fn pha_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     write_cycle!(cpu, bus, pinout, to_address(0x1, cpu.sp), cpu.ops.dl);
     // decrement stack pointer
     cpu.sp = cpu.sp.wrapping_sub(1);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1211,7 +1303,7 @@ pub fn plp_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     read_cycle!(cpu, bus, pinout, to_address(0x1, cpu.sp));
     cpu.p = FlagsRegister::from(cpu.ops.dl);
 
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1247,7 +1339,7 @@ pub fn pla_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     cpu.p.zero = if cpu.a == 0 { true } else {false };
     cpu.p.negative = if (cpu.a & 0x80) == 0x80 { true } else { false };
 
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1298,7 +1390,7 @@ pub fn jsr_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     cpu.pc.pcl = cpu.ops.adl;
     cpu.pc.pch = cpu.ops.adh;
 
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1351,7 +1443,7 @@ pub fn rti_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     read_cycle!(cpu, bus, pinout, to_address(1, cpu.sp));
     cpu.pc.pch = cpu.ops.dl;
 
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1381,7 +1473,7 @@ pub fn jmp_absolute_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinou
     cpu.pc.pcl = cpu.ops.adl;
     cpu.pc.pch = cpu.ops.adh;
 
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1427,7 +1519,7 @@ pub fn jmp_indirect_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinou
     cpu.pc.pcl =cpu.ops.adl;
     cpu.pc.pch = cpu.ops.adh;
 
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1475,7 +1567,7 @@ pub fn rts_c4<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pin
     // data discarded
     read_cycle!(cpu, bus, pinout, u16::from(cpu.pc));
     cpu.pc.increment();
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1494,8 +1586,6 @@ pub fn branch_c0<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) ->
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
     second_cycle!(cpu, bus, pinout);
     cpu.ops.offset = cpu.ops.dl;
-    // branch always checks for interrupts on this cycle
-    last_cycle!(cpu, pinout);
     cpu.pc.increment();
     pinout
 }
@@ -1505,11 +1595,17 @@ pub fn branch_c1<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut B, mut pin
     T::execute(cpu);
     // fetch next opcode 
     if cpu.ops.branch_taken == false {
+        last_cycle!(cpu, pinout, prev_latch);
         // first cycle of next instruction
         first_cycle!(cpu, bus, pinout);
     }
     // add offset to pcl
     else {
+        // a taken branch ignores an interrupt first latched on the operand cycle, without
+        // a page cross it is delayed until after the next instruction
+        if cpu.latch.irq == true && cpu.prev_latch.irq == false { cpu.latch.irq = false; }
+        if cpu.latch.nmi == true && cpu.prev_latch.nmi == false { cpu.latch.nmi = false; }
+
         // offset is signed check if negative
         if cpu.ops.offset > 0x7F {
             cpu.ops.offset_neg = true;
@@ -1536,10 +1632,10 @@ pub fn branch_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) ->
         if cpu.ops.offset_neg == true { cpu.pc.pch = cpu.pc.pch.wrapping_sub(1); }
         else { cpu.pc.pch = cpu.pc.pch.wrapping_add(1); }
         read_cycle!(cpu, bus, pinout, u16::from(cpu.pc));
-        last_cycle!(cpu, pinout);
+        last_cycle!(cpu, pinout, latch);
     }
     else {
-        last_cycle!(cpu, pinout);
+        last_cycle!(cpu, pinout, prev_latch);
         // if no interrupt do first cycle
         first_cycle!(cpu, bus, pinout);
     }
@@ -1574,14 +1670,14 @@ pub fn undoc_indirect_x_c1<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: P
 
 pub fn undoc_indirect_x_c2<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
-    read_cycle!(cpu, bus, pinout, to_address(0, cpu.ops.bal + cpu.x));
+    read_cycle!(cpu, bus, pinout, to_address(0, cpu.ops.bal.wrapping_add(cpu.x)));
     cpu.ops.adl = cpu.ops.dl;
     pinout
 }
 
 pub fn undoc_indirect_x_c3<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     if pinout.ctrl.contains(Ctrl::RDY) == false { return pinout; }
-    read_cycle!(cpu, bus, pinout, to_address(0, cpu.ops.bal + cpu.x + 1));
+    read_cycle!(cpu, bus, pinout, to_address(0, cpu.ops.bal.wrapping_add(cpu.x).wrapping_add(1)));
     cpu.ops.adh = cpu.ops.dl;
     pinout
 }
@@ -1602,7 +1698,7 @@ pub fn undoc_indirect_x_c5<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut
 pub fn undoc_indirect_x_c6<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     // write modified data back
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1668,7 +1764,7 @@ pub fn undoc_indirect_y_c5<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut
 pub fn undoc_indirect_y_c6<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     // write modified data back
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
@@ -1726,7 +1822,7 @@ pub fn undoc_absolute_y_c4<B: Bus, T: Instruction>(cpu: &mut Context, bus: &mut
 pub fn undoc_absolute_y_c5<B: Bus>(cpu: &mut Context, bus: &mut B, mut pinout: Pinout) -> Pinout {
     // write modified data back
     write_cycle!(cpu, bus, pinout, to_address(cpu.ops.adh, cpu.ops.adl), cpu.ops.dl);
-    last_cycle!(cpu, pinout);
+    last_cycle!(cpu, pinout, latch);
     pinout
 }
 
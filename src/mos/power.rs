@@ -0,0 +1,103 @@
+/*
+Power on state
+
+Real chips power up with indeterminate registers and RAM. A profile picks the values seen
+by the cold reset sequence and can fill RAM the same way, so software that depends on
+uninitialized state can be caught.
+
+Only A, X, Y and P are taken from a profile. SP is 0 at power on on every chip and ends
+at 0xFD after the three suppressed pushes of the reset sequence, the I flag is always set
+by the reset sequence.
+*/
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PowerOnRegisters {
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub p: u8,
+}
+
+// a power on state captured from real hardware, ram repeats the pattern
+#[derive(Debug, PartialEq)]
+pub struct PowerOnSample {
+    pub registers: PowerOnRegisters,
+    pub ram_pattern: &'static [u8],
+}
+
+// nes front loader as described on the nesdev wiki, ram in blocks of four 0x00 and four 0xFF
+pub const NES_FRONT_LOADER: PowerOnSample = PowerOnSample {
+    registers: PowerOnRegisters { a: 0x00, x: 0x00, y: 0x00, p: 0x34 },
+    ram_pattern: &[0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF],
+};
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum PowerOnProfile {
+    // every register and ram byte is 0
+    Zero,
+    // the state nestest.log expects, ram is 0
+    #[default]
+    Nestest,
+    // registers and ram from a seeded generator, the same seed gives the same state
+    Random(u64),
+    Sample(&'static PowerOnSample),
+}
+
+impl PowerOnProfile {
+    pub fn registers(&self) -> PowerOnRegisters {
+        match *self {
+            PowerOnProfile::Zero => PowerOnRegisters { a: 0x00, x: 0x00, y: 0x00, p: 0x00 },
+            PowerOnProfile::Nestest => PowerOnRegisters { a: 0xAA, x: 0x00, y: 0x00, p: 0x24 },
+            PowerOnProfile::Random(seed) => {
+                let mut rng = SplitMix64::new(seed);
+                let bytes = rng.next_u64().to_le_bytes();
+                PowerOnRegisters { a: bytes[0], x: bytes[1], y: bytes[2], p: bytes[3] }
+            }
+            PowerOnProfile::Sample(sample) => sample.registers,
+        }
+    }
+
+    // fill ram as it would be found at power on
+    pub fn fill(&self, ram: &mut [u8]) {
+        match *self {
+            PowerOnProfile::Zero | PowerOnProfile::Nestest => {
+                for byte in ram.iter_mut() { *byte = 0; }
+            }
+            PowerOnProfile::Random(seed) => {
+                // a different stream than the registers
+                let mut rng = SplitMix64::new(seed ^ 0xA5A5_A5A5_A5A5_A5A5);
+                for chunk in ram.chunks_mut(8) {
+                    let bytes = rng.next_u64().to_le_bytes();
+                    chunk.copy_from_slice(&bytes[..chunk.len()]);
+                }
+            }
+            PowerOnProfile::Sample(sample) => {
+                if sample.ram_pattern.is_empty() {
+                    for byte in ram.iter_mut() { *byte = 0; }
+                    return;
+                }
+                for (byte, pattern) in ram.iter_mut().zip(sample.ram_pattern.iter().cycle()) {
+                    *byte = *pattern;
+                }
+            }
+        }
+    }
+}
+
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,381 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use super::{Ctrl, Pinout};
+use super::bus::{Bus, Sequence};
+use super::symbols::SymbolTable;
+
+/*
+Profiler
+
+ProfilerBus sits between the cpu and the system bus like CdlBus and counts the cycles of the
+cycle accurate path through Bus::cycle. Every cycle goes to the instruction whose opcode was
+fetched last, SYNC high, and to the subroutine on top of the call stack.
+
+The call stack is followed from the bus. JSR enters the subroutine named by its operand on
+its last cycle, an interrupt enters its handler when the vector has been read and a reset
+starts over with the reset handler. The core says through Bus::vector which sequence is
+about to read a vector, reads of the vectors by anything else are only reads. RTS and RTI
+return on the cycle they pull the high byte of the return address, up to the frame that
+pushed it from the same stack address. Returns that match no frame, like RTS used as a
+jump, leave the stack as it is. So JSR and the
+interrupt sequence are mostly counted in the caller, RTS and RTI in the callee.
+
+Frames are kept as a tree of call paths, each path with the cycles spent on it. Exclusive
+cycles of a subroutine are its own paths, inclusive cycles every path it is on, counted once
+however deep it recurses. Interrupt cycles are those of paths through a handler of the kind.
+Cycles before the first opcode fetch are not on any path.
+
+Folded stacks, one path per line as "reset;main;sub 1234", are what flamegraph.pl and
+inferno read. Names come from a symbol table when there is one.
+*/
+
+const STACK: u16 = 0x0100;
+const JSR: u8 = 0x20;
+const RTI: u8 = 0x40;
+const RTS: u8 = 0x60;
+
+// how a subroutine was entered
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Entry {
+    // running before the profiler saw a reset
+    Start,
+    Reset,
+    Call,
+    Nmi,
+    Irq,
+    Brk,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PcProfile {
+    pub address: u16,
+    pub cycles: u64,
+    pub executions: u64,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Subroutine {
+    pub address: u16,
+    pub calls: u64,
+    pub inclusive: u64,
+    pub exclusive: u64,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct InterruptProfile {
+    pub count: u64,
+    pub cycles: u64,
+}
+
+// a call path, the subroutine at its end and how it was entered
+struct Node {
+    address: u16,
+    entry: Entry,
+    parent: Option<usize>,
+    children: BTreeMap<(u16, Entry), usize>,
+    cycles: u64,
+    entries: u64,
+}
+
+struct Frame {
+    node: usize,
+    // where the high byte of the return address was pushed
+    stack: Option<u16>,
+}
+
+pub struct Profiler {
+    nodes: Vec<Node>,
+    roots: BTreeMap<(u16, Entry), usize>,
+    frames: Vec<Frame>,
+    pc_cycles: Vec<u64>,
+    pc_executions: Vec<u64>,
+    cycles: u64,
+
+    // the instruction being executed
+    pc: Option<u16>,
+    opcode: u8,
+    // the sequence whose vector is being read
+    vector: Option<Sequence>,
+    // operand or vector bytes read so far
+    low: u8,
+    stack_reads: u8,
+    // the last three stack writes, oldest first
+    pushes: [u16; 3],
+}
+
+impl Profiler {
+    pub fn new() -> Profiler {
+        Profiler {
+            nodes: Vec::new(),
+            roots: BTreeMap::new(),
+            frames: Vec::new(),
+            pc_cycles: vec![0; 0x10000],
+            pc_executions: vec![0; 0x10000],
+            cycles: 0,
+            pc: None,
+            opcode: 0,
+            vector: None,
+            low: 0,
+            stack_reads: 0,
+            pushes: [0; 3],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Profiler::new();
+    }
+
+    // cycles counted, including those before the first opcode fetch
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    // the subroutines on the call stack, outermost first
+    pub fn call_stack(&self) -> Vec<u16> {
+        self.frames.iter().map(|frame| self.nodes[frame.node].address).collect()
+    }
+
+    // instructions that took cycles, most cycles first
+    pub fn hot_spots(&self) -> Vec<PcProfile> {
+        let mut spots: Vec<PcProfile> = (0..=0xFFFF).filter(|address| self.pc_cycles[*address] != 0)
+            .map(|address| PcProfile { address: address as u16, cycles: self.pc_cycles[address], executions: self.pc_executions[address] })
+            .collect();
+        spots.sort_by(|a, b| b.cycles.cmp(&a.cycles).then(a.address.cmp(&b.address)));
+        spots
+    }
+
+    // most inclusive cycles first
+    pub fn subroutines(&self) -> Vec<Subroutine> {
+        let mut subroutines: BTreeMap<u16, Subroutine> = BTreeMap::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            let subroutine = subroutines.entry(node.address).or_insert(Subroutine { address: node.address, calls: 0, inclusive: 0, exclusive: 0 });
+            subroutine.calls += node.entries;
+            subroutine.exclusive += node.cycles;
+
+            let mut path: Vec<u16> = self.path(index).map(|node| node.address).collect();
+            path.sort_unstable();
+            path.dedup();
+            for address in path {
+                let subroutine = subroutines.entry(address).or_insert(Subroutine { address, calls: 0, inclusive: 0, exclusive: 0 });
+                subroutine.inclusive += node.cycles;
+            }
+        }
+        let mut subroutines: Vec<Subroutine> = subroutines.into_values().collect();
+        subroutines.sort_by(|a, b| b.inclusive.cmp(&a.inclusive).then(a.address.cmp(&b.address)));
+        subroutines
+    }
+
+    pub fn subroutine(&self, address: u16) -> Option<Subroutine> {
+        self.subroutines().into_iter().find(|subroutine| subroutine.address == address)
+    }
+
+    pub fn interrupts(&self, entry: Entry) -> InterruptProfile {
+        let mut profile = InterruptProfile::default();
+        for (index, node) in self.nodes.iter().enumerate() {
+            if node.entry == entry {
+                profile.count += node.entries;
+            }
+            if self.path(index).any(|node| node.entry == entry) {
+                profile.cycles += node.cycles;
+            }
+        }
+        profile
+    }
+
+    pub fn write_folded<W: Write>(&self, mut writer: W, symbols: Option<&SymbolTable>) -> io::Result<()> {
+        let name = |address: u16| symbols.and_then(|symbols| symbols.label(address, None)).unwrap_or_else(|| format!("${:04X}", address));
+        for (index, node) in self.nodes.iter().enumerate() {
+            if node.cycles == 0 {
+                continue;
+            }
+            let mut path: Vec<String> = self.path(index).map(|node| name(node.address)).collect();
+            path.reverse();
+            writeln!(writer, "{} {}", path.join(";"), node.cycles)?;
+        }
+        writer.flush()
+    }
+
+    // the node and its parents, innermost first
+    fn path(&self, index: usize) -> impl Iterator<Item = &Node> {
+        let mut next = Some(index);
+        std::iter::from_fn(move || {
+            let node = &self.nodes[next?];
+            next = node.parent;
+            Some(node)
+        })
+    }
+
+    fn enter(&mut self, address: u16, entry: Entry, stack: Option<u16>) {
+        let parent = self.frames.last().map(|frame| frame.node);
+        let key = (address, entry);
+        let existing = match parent {
+            Some(parent) => self.nodes[parent].children.get(&key).copied(),
+            None => self.roots.get(&key).copied(),
+        };
+        let node = existing.unwrap_or_else(|| {
+            self.nodes.push(Node { address, entry, parent, children: BTreeMap::new(), cycles: 0, entries: 0 });
+            let node = self.nodes.len() - 1;
+            match parent {
+                Some(parent) => self.nodes[parent].children.insert(key, node),
+                None => self.roots.insert(key, node),
+            };
+            node
+        });
+        self.nodes[node].entries += 1;
+        self.frames.push(Frame { node, stack });
+    }
+
+    fn leave(&mut self, stack: u16) {
+        if let Some(index) = self.frames.iter().rposition(|frame| frame.stack == Some(stack)) {
+            self.frames.truncate(index);
+        }
+    }
+
+    fn read(&mut self, pinout: Pinout) {
+        let address = pinout.address;
+        if pinout.ctrl.contains(Ctrl::SYNC) {
+            if self.frames.is_empty() {
+                self.enter(address, Entry::Start, None);
+            }
+            self.pc = Some(address);
+            self.pc_executions[address as usize] += 1;
+            self.opcode = pinout.data;
+            self.stack_reads = 0;
+            return;
+        }
+
+        if let Some(sequence) = self.vector {
+            if address & 1 == 0 {
+                self.low = pinout.data;
+                return;
+            }
+            self.vector = None;
+            let handler = (pinout.data as u16) << 8 | self.low as u16;
+            match sequence {
+                Sequence::Reset => {
+                    self.frames.clear();
+                    self.enter(handler, Entry::Reset, None);
+                }
+                Sequence::Brk => self.enter(handler, Entry::Brk, Some(self.pushes[0])),
+                Sequence::Irq => self.enter(handler, Entry::Irq, Some(self.pushes[0])),
+                Sequence::Nmi | Sequence::BrkHijack | Sequence::IrqHijack => self.enter(handler, Entry::Nmi, Some(self.pushes[0])),
+            }
+            return;
+        }
+
+        let pc = self.pc.unwrap_or(0);
+        match (self.opcode, address) {
+            (JSR, _) if address == pc.wrapping_add(1) => self.low = pinout.data,
+            // the last cycle, the return address has been pushed
+            (JSR, _) if address == pc.wrapping_add(2) => {
+                let target = (pinout.data as u16) << 8 | self.low as u16;
+                self.enter(target, Entry::Call, Some(self.pushes[1]));
+            }
+            (RTS, _) | (RTI, _) if address & 0xFF00 == STACK => {
+                // a dummy read, then the status for RTI, then the return address
+                self.stack_reads += 1;
+                let high = if self.opcode == RTS { 3 } else { 4 };
+                if self.stack_reads == high {
+                    self.leave(address);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn write(&mut self, pinout: Pinout) {
+        if pinout.address & 0xFF00 == STACK {
+            self.pushes = [self.pushes[1], self.pushes[2], pinout.address];
+        }
+    }
+
+    fn vector(&mut self, sequence: Sequence) {
+        self.vector = Some(sequence);
+        // the opcode fetched before an interrupt is thrown away and fetched again after RTI
+        if let (Some(pc), Sequence::Irq | Sequence::Nmi | Sequence::IrqHijack) = (self.pc, sequence) {
+            self.pc_executions[pc as usize] -= 1;
+        }
+    }
+
+    fn cycle(&mut self) {
+        self.cycles += 1;
+        if let Some(pc) = self.pc {
+            self.pc_cycles[pc as usize] += 1;
+        }
+        if let Some(frame) = self.frames.last() {
+            self.nodes[frame.node].cycles += 1;
+        }
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Profiler {
+        Profiler::new()
+    }
+}
+
+pub struct ProfilerBus<B> {
+    bus: B,
+    profiler: Profiler,
+}
+
+impl<B: Bus> ProfilerBus<B> {
+    pub fn new(bus: B) -> ProfilerBus<B> {
+        ProfilerBus { bus, profiler: Profiler::new() }
+    }
+
+    pub fn bus(&self) -> &B {
+        &self.bus
+    }
+
+    pub fn bus_mut(&mut self) -> &mut B {
+        &mut self.bus
+    }
+
+    pub fn profiler(&self) -> &Profiler {
+        &self.profiler
+    }
+
+    pub fn profiler_mut(&mut self) -> &mut Profiler {
+        &mut self.profiler
+    }
+
+    pub fn into_inner(self) -> (B, Profiler) {
+        (self.bus, self.profiler)
+    }
+}
+
+impl<B: Bus> Bus for ProfilerBus<B> {
+    fn read(&mut self, pinout: Pinout) -> Pinout {
+        let pinout = self.bus.read(pinout);
+        self.profiler.read(pinout);
+        pinout
+    }
+
+    fn write(&mut self, pinout: Pinout) -> Pinout {
+        self.profiler.write(pinout);
+        self.bus.write(pinout)
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        self.bus.peek(address)
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        self.bus.poke(address, data)
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        self.bus.prg_rom_offset(address)
+    }
+
+    fn cycle(&mut self, pinout: Pinout) -> Pinout {
+        self.profiler.cycle();
+        self.bus.cycle(pinout)
+    }
+
+    fn vector(&mut self, sequence: Sequence) {
+        self.profiler.vector(sequence);
+        self.bus.vector(sequence)
+    }
+}
@@ -0,0 +1,46 @@
+use std::ops::{Deref, DerefMut};
+use super::Pinout;
+use super::clock::Region;
+use super::power::PowerOnProfile;
+use super::rp2a03::Rp2a03;
+
+/*
+2A07, the pal nes cpu
+
+The same cpu core as the 2A03 with the pal apu tables, everything else is the 2A03 it
+derefs to. It is clocked with a divider of 16 from a 26.6 MHz crystal.
+*/
+
+pub struct Rp2a07 {
+    cpu: Rp2a03,
+}
+
+impl Rp2a07 {
+    // the cpu starts in the cold reset sequence
+    pub fn from_power_on() -> (Rp2a07, Pinout) {
+        Rp2a07::from_power_on_with(PowerOnProfile::default())
+    }
+
+    pub fn from_power_on_with(profile: PowerOnProfile) -> (Rp2a07, Pinout) {
+        let (cpu, pinout) = Rp2a03::from_power_on_region(Region::Pal, profile);
+        (Rp2a07 { cpu }, pinout)
+    }
+
+    pub fn into_inner(self) -> Rp2a03 {
+        self.cpu
+    }
+}
+
+impl Deref for Rp2a07 {
+    type Target = Rp2a03;
+
+    fn deref(&self) -> &Rp2a03 {
+        &self.cpu
+    }
+}
+
+impl DerefMut for Rp2a07 {
+    fn deref_mut(&mut self) -> &mut Rp2a03 {
+        &mut self.cpu
+    }
+}
@@ -0,0 +1,223 @@
+use super::InterruptState;
+use super::dispatch::{AddressingMode, Operation, OPCODES};
+
+/*
+Execution statistics
+
+Kept by Rp2a03 on every execution path. Each instruction is counted when it ends, by opcode and
+by the cycles it took over the base count of its opcode: 0 to 2, one for a page crossed by an
+indexed read, one for a branch taken and another for the page it crossed into. Cycles the cpu
+was held by RDY are not part of an instruction. A KIL is counted when it jams.
+
+Everything else is worked out from those counts when asked for. Dummy accesses are the reads
+and writes the cycle accurate core makes without using the result, like the read of the byte
+after an implied opcode, the read at the unfixed address of an indexed access and the write of
+the unmodified value by a read-modify-write. Interrupt sequences add the dummy read of their
+first cycle, reset sequences are only counted.
+*/
+
+// most cycles an instruction can take over its base count
+const MAX_EXTRA: usize = 2;
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct BranchStats {
+    pub taken: u64,
+    pub not_taken: u64,
+}
+
+impl BranchStats {
+    // taken over all executions, 0 when the branch never ran
+    pub fn taken_ratio(&self) -> f64 {
+        match self.taken + self.not_taken {
+            0 => 0.0,
+            total => self.taken as f64 / total as f64,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct InterruptStats {
+    pub nmi: u64,
+    pub irq: u64,
+    pub brk: u64,
+    pub reset: u64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Stats {
+    // executions of each opcode by the cycles over its base count
+    executions: [[u64; MAX_EXTRA + 1]; 256],
+    interrupts: InterruptStats,
+    // brk sequences taken over by an nmi, counted as both
+    hijacked: u64,
+}
+
+impl Stats {
+    pub fn new() -> Stats {
+        Stats { executions: [[0; MAX_EXTRA + 1]; 256], interrupts: InterruptStats::default(), hijacked: 0 }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Stats::new();
+    }
+
+    pub fn executions(&self, opcode: u8) -> u64 {
+        self.executions[opcode as usize].iter().sum()
+    }
+
+    pub fn instructions(&self) -> u64 {
+        (0..=0xFF).map(|opcode| self.executions(opcode)).sum()
+    }
+
+    pub fn executions_by_mode(&self, mode: AddressingMode) -> u64 {
+        (0..=0xFF).filter(|opcode| OPCODES[*opcode as usize].mode == mode).map(|opcode| self.executions(opcode)).sum()
+    }
+
+    // the undocumented opcodes that ran and how often
+    pub fn undocumented(&self) -> Vec<(u8, u64)> {
+        (0..=0xFF).filter(|opcode| OPCODES[*opcode as usize].mnemonic.starts_with('*'))
+            .map(|opcode| (opcode, self.executions(opcode)))
+            .filter(|(_, executions)| *executions != 0)
+            .collect()
+    }
+
+    pub fn interrupts(&self) -> InterruptStats {
+        self.interrupts
+    }
+
+    // page crossing cycles of indexed reads and branches
+    pub fn page_cross_cycles(&self) -> u64 {
+        self.by_extra(|operation, mode, extra| match (operation, mode) {
+            (Operation::Branch, _) => extra.saturating_sub(1),
+            (Operation::Read, AddressingMode::AbsoluteX) | (Operation::Read, AddressingMode::AbsoluteY)
+                | (Operation::Read, AddressingMode::IndirectY) => extra,
+            _ => 0,
+        })
+    }
+
+    pub fn dummy_reads(&self) -> u64 {
+        let sequences = self.interrupts.nmi + self.interrupts.irq - self.hijacked;
+        self.by_extra(|operation, mode, extra| dummy_accesses(operation, mode, extra).0) + sequences
+    }
+
+    pub fn dummy_writes(&self) -> u64 {
+        self.by_extra(|operation, mode, extra| dummy_accesses(operation, mode, extra).1)
+    }
+
+    pub fn branch(&self, opcode: u8) -> BranchStats {
+        let executions = &self.executions[opcode as usize];
+        BranchStats { taken: executions[1] + executions[2], not_taken: executions[0] }
+    }
+
+    // all branch opcodes together
+    pub fn branches(&self) -> BranchStats {
+        (0..=0xFF).filter(|opcode| OPCODES[*opcode as usize].operation == Operation::Branch)
+            .map(|opcode| self.branch(opcode))
+            .fold(BranchStats::default(), |total, branch| BranchStats { taken: total.taken + branch.taken, not_taken: total.not_taken + branch.not_taken })
+    }
+
+    // an instruction that took cycles, BRK taken from the brk slot
+    pub(super) fn instruction(&mut self, opcode: u8, cycles: u32) {
+        let descriptor = &OPCODES[opcode as usize];
+        let extra = cycles.saturating_sub(base_cycles(descriptor.operation, descriptor.mode)) as usize;
+        self.executions[opcode as usize][extra.min(MAX_EXTRA)] += 1;
+    }
+
+    pub(super) fn jam(&mut self, opcode: u8) {
+        self.executions[opcode as usize][0] += 1;
+    }
+
+    // the brk slot as it was run, BRK itself with None
+    pub(super) fn sequence(&mut self, ints: InterruptState) {
+        match ints {
+            InterruptState::None => self.brk(),
+            InterruptState::BrkHijack => {
+                self.brk();
+                self.interrupts.nmi += 1;
+                self.hijacked += 1;
+            }
+            InterruptState::Nmi | InterruptState::IrqHijack => self.interrupts.nmi += 1,
+            InterruptState::Irq => self.interrupts.irq += 1,
+        }
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.interrupts.reset += 1;
+    }
+
+    fn brk(&mut self) {
+        self.interrupts.brk += 1;
+        self.executions[0x00][0] += 1;
+    }
+
+    fn by_extra<C: Fn(Operation, AddressingMode, u64) -> u64>(&self, count: C) -> u64 {
+        let mut total = 0;
+        for (opcode, executions) in self.executions.iter().enumerate() {
+            let descriptor = &OPCODES[opcode];
+            for (extra, executions) in executions.iter().enumerate() {
+                total += count(descriptor.operation, descriptor.mode, extra as u64) * executions;
+            }
+        }
+        total
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Stats {
+        Stats::new()
+    }
+}
+
+// cycles without a page crossed or a branch taken
+fn base_cycles(operation: Operation, mode: AddressingMode) -> u32 {
+    match (operation, mode) {
+        (Operation::Implied, _) | (Operation::Branch, _) => 2,
+        (Operation::Read, AddressingMode::Immediate) => 2,
+        (Operation::Read, AddressingMode::ZeroPage) | (Operation::Store, AddressingMode::ZeroPage) => 3,
+        (Operation::Read, AddressingMode::IndirectX) | (Operation::Store, AddressingMode::IndirectX) => 6,
+        (Operation::Read, AddressingMode::IndirectY) => 5,
+        (Operation::Read, _) => 4,
+        (Operation::Store, AddressingMode::IndirectY) => 6,
+        (Operation::Store, AddressingMode::AbsoluteX) | (Operation::Store, AddressingMode::AbsoluteY) => 5,
+        (Operation::Store, _) => 4,
+        (Operation::Modify, AddressingMode::ZeroPage) => 5,
+        (Operation::Modify, AddressingMode::ZeroPageX) | (Operation::Modify, AddressingMode::Absolute) => 6,
+        (Operation::Modify, AddressingMode::IndirectX) | (Operation::Modify, AddressingMode::IndirectY) => 8,
+        (Operation::Modify, _) => 7,
+        (Operation::Jmp, AddressingMode::Indirect) => 5,
+        (Operation::Jmp, _) => 3,
+        (Operation::Php, _) | (Operation::Pha, _) => 3,
+        (Operation::Plp, _) | (Operation::Pla, _) => 4,
+        (Operation::Brk, _) => 7,
+        (Operation::Jsr, _) | (Operation::Rts, _) | (Operation::Rti, _) => 6,
+        (Operation::Jam, _) => 0,
+    }
+}
+
+// dummy reads and writes of an instruction taking extra cycles over its base count
+fn dummy_accesses(operation: Operation, mode: AddressingMode, extra: u64) -> (u64, u64) {
+    let indexed = match mode {
+        AddressingMode::ZeroPageX | AddressingMode::ZeroPageY | AddressingMode::IndirectX => 1,
+        AddressingMode::AbsoluteX | AddressingMode::AbsoluteY | AddressingMode::IndirectY => 1,
+        _ => 0,
+    };
+    match operation {
+        // the byte after the opcode
+        Operation::Implied | Operation::Brk | Operation::Php | Operation::Pha => (1, 0),
+        // reads only fix up a crossed page with a dummy read
+        Operation::Read => match mode {
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY | AddressingMode::IndirectY => (extra, 0),
+            _ => (indexed, 0),
+        },
+        Operation::Store => (indexed, 0),
+        Operation::Modify => (indexed, 1),
+        // the next opcode while the branch is taken, then the wrong page
+        Operation::Branch => (extra, 0),
+        // the stack before it is written or pulled from
+        Operation::Jsr => (1, 0),
+        Operation::Plp | Operation::Pla | Operation::Rti => (2, 0),
+        // and the return address before it is incremented
+        Operation::Rts => (3, 0),
+        Operation::Jmp | Operation::Jam => (0, 0),
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/*
+Symbol tables
+
+Labels and source lines imported from ca65/ld65 .dbg files, VICE label files, FCEUX .nl files
+and Mesen .mlb files, for the disassembler, trace lines and call stacks to show label+offset
+and file:line instead of raw addresses.
+
+Rom labels are bank aware. A bank is a 16k slice of prg rom counted from 0 like FCEUX counts
+them and a banked symbol is kept as its offset in the bank, so it matches whether the mapper
+has the bank at $8000 or $C000. Lookups with a bank only see that bank's rom labels, without
+one every bank is searched. Ram, registers and unbanked programs use plain cpu addresses.
+
+An address without a label of its own is shown as the closest label before it, within 256
+bytes and inside the label's size when the file gives one.
+*/
+
+// prg rom bank size the banks are counted in
+pub const BANK_SIZE: u16 = 0x4000;
+// furthest a label+offset may reach
+const MAX_OFFSET: u16 = 0x100;
+
+#[derive(Debug)]
+pub enum SymbolError {
+    Io(io::Error),
+    // lines count from 1
+    Syntax { line: usize },
+    // the extension does not say which format a file is in
+    UnknownFormat,
+}
+
+impl fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SymbolError::Io(ref err) => write!(f, "could not read the symbol file: {}", err),
+            SymbolError::Syntax { line } => write!(f, "line {}: malformed symbol", line),
+            SymbolError::UnknownFormat => write!(f, "symbol file is not .dbg, .nl, .mlb or a VICE label file"),
+        }
+    }
+}
+
+impl std::error::Error for SymbolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            SymbolError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SymbolError {
+    fn from(err: io::Error) -> SymbolError {
+        SymbolError::Io(err)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Label {
+    name: String,
+    size: Option<u16>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct SourceLine {
+    file: usize,
+    line: u32,
+    size: u16,
+}
+
+// entries by cpu address, and by bank and offset in the bank for rom
+#[derive(Debug, PartialEq, Clone)]
+struct Index<T> {
+    plain: BTreeMap<u16, T>,
+    banked: BTreeMap<(u32, u16), T>,
+}
+
+impl<T> Index<T> {
+    fn new() -> Index<T> {
+        Index { plain: BTreeMap::new(), banked: BTreeMap::new() }
+    }
+
+    // entries from the first file imported win
+    fn insert(&mut self, address: u16, bank: Option<u32>, entry: T) {
+        match bank {
+            Some(bank) => { self.banked.entry((bank, address % BANK_SIZE)).or_insert(entry); }
+            None => { self.plain.entry(address).or_insert(entry); }
+        }
+    }
+
+    // the closest entry at or before address as its cpu address
+    fn nearest(&self, address: u16, bank: Option<u32>) -> Option<(u16, &T)> {
+        let mut best = self.plain.range(..=address).next_back().map(|(start, entry)| (*start, entry));
+
+        if address >= 0x8000 {
+            let offset = address % BANK_SIZE;
+            let banked = match bank {
+                Some(bank) => self.banked.range((bank, 0)..=(bank, offset)).next_back(),
+                None => self.banked.iter()
+                    .filter(|((_, start), _)| *start <= offset)
+                    .max_by_key(|((_, start), _)| *start),
+            };
+            if let Some(((_, start), entry)) = banked {
+                let start = address - (offset - start);
+                if best.map(|(plain, _)| start >= plain).unwrap_or(true) {
+                    best = Some((start, entry));
+                }
+            }
+        }
+        best
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SymbolTable {
+    labels: Index<Label>,
+    lines: Index<SourceLine>,
+    files: Vec<String>,
+}
+
+impl SymbolTable {
+    pub fn new() -> SymbolTable {
+        SymbolTable { labels: Index::new(), lines: Index::new(), files: Vec::new() }
+    }
+
+    // the format is picked by extension, FCEUX files take their bank from the name, game.nes.3.nl
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SymbolError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_ascii_lowercase();
+        let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("dbg") => self.import_ca65_dbg(&text),
+            Some("mlb") => self.import_mesen_mlb(&text),
+            Some("nl") => {
+                let bank = name.trim_end_matches(".nl").rsplit('.').next().and_then(|bank| u32::from_str_radix(bank, 16).ok());
+                self.import_fceux_nl(&text, bank)
+            }
+            Some("lbl") | Some("vs") | Some("labels") => self.import_vice(&text),
+            _ => Err(SymbolError::UnknownFormat),
+        }
+    }
+
+    pub fn add_label(&mut self, name: &str, address: u16, bank: Option<u32>) {
+        self.labels.insert(address, bank, Label { name: name.to_string(), size: None });
+    }
+
+    pub fn add_source_line(&mut self, file: &str, line: u32, address: u16, size: u16, bank: Option<u32>) {
+        let file = self.file_id(file);
+        self.lines.insert(address, bank, SourceLine { file, line, size });
+    }
+
+    fn file_id(&mut self, file: &str) -> usize {
+        match self.files.iter().position(|f| f == file) {
+            Some(id) => id,
+            None => {
+                self.files.push(file.to_string());
+                self.files.len() - 1
+            }
+        }
+    }
+
+    // the label at address or before it with the offset from it
+    pub fn lookup(&self, address: u16, bank: Option<u32>) -> Option<(&str, u16)> {
+        let (start, label) = self.labels.nearest(address, bank)?;
+        let offset = address - start;
+        let limit = label.size.unwrap_or(MAX_OFFSET).clamp(1, MAX_OFFSET);
+        if offset >= limit {
+            return None;
+        }
+        Some((&label.name, offset))
+    }
+
+    // label or label+offset
+    pub fn label(&self, address: u16, bank: Option<u32>) -> Option<String> {
+        self.lookup(address, bank).map(|(name, offset)| {
+            if offset == 0 { name.to_string() } else { format!("{}+{}", name, offset) }
+        })
+    }
+
+    // the label, or the address as $XXXX
+    pub fn format_address(&self, address: u16, bank: Option<u32>) -> String {
+        self.label(address, bank).unwrap_or_else(|| format!("${:04X}", address))
+    }
+
+    // file and line of the source that assembled to address
+    pub fn source(&self, address: u16, bank: Option<u32>) -> Option<(&str, u32)> {
+        let (start, line) = self.lines.nearest(address, bank)?;
+        if address - start >= line.size.max(1) {
+            return None;
+        }
+        Some((&self.files[line.file], line.line))
+    }
+
+    // "label+offset file:line" with whatever is known, empty when nothing is
+    pub fn describe(&self, address: u16, bank: Option<u32>) -> String {
+        let label = self.label(address, bank);
+        let source = self.source(address, bank).map(|(file, line)| format!("{}:{}", file, line));
+        match (label, source) {
+            (Some(label), Some(source)) => format!("{} {}", label, source),
+            (Some(text), None) | (None, Some(text)) => text,
+            (None, None) => String::new(),
+        }
+    }
+
+    // ld65 debug info, labels and equates from sym lines and source lines through their spans
+    pub fn import_ca65_dbg(&mut self, text: &str) -> Result<(), SymbolError> {
+        struct Segment { start: u32, bank: Option<u32> }
+        let mut files = BTreeMap::new();
+        let mut segments = BTreeMap::new();
+        let mut spans = BTreeMap::new();
+        let mut lines = Vec::new();
+        let mut symbols = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (kind, fields) = line.split_once(|c: char| c.is_whitespace()).ok_or(SymbolError::Syntax { line: line_number })?;
+            let fields = dbg_fields(fields.trim(), line_number)?;
+            let field = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+            let number = |key: &str| field(key).map(|v| dbg_number(v, line_number)).transpose();
+            let id = || number("id").and_then(|id| id.ok_or(SymbolError::Syntax { line: line_number }));
+
+            match kind {
+                "file" => { files.insert(id()?, field("name").unwrap_or("").to_string()); }
+                "seg" => {
+                    let start = number("start")?.unwrap_or(0);
+                    // ines images start their prg rom after the 16 byte header
+                    let bank = match (number("ooffs")?, field("oname")) {
+                        (Some(offset), Some(name)) if start >= 0x8000 && name.to_ascii_lowercase().ends_with(".nes") => {
+                            Some(offset.saturating_sub(16) / BANK_SIZE as u32)
+                        }
+                        _ => None,
+                    };
+                    segments.insert(id()?, Segment { start, bank });
+                }
+                "span" => {
+                    let seg = number("seg")?.ok_or(SymbolError::Syntax { line: line_number })?;
+                    spans.insert(id()?, (seg, number("start")?.unwrap_or(0), number("size")?.unwrap_or(1)));
+                }
+                "line" => {
+                    if let (Some(file), Some(line), Some(span)) = (number("file")?, number("line")?, field("span")) {
+                        for span in span.split('+') {
+                            lines.push((file, line, dbg_number(span, line_number)?));
+                        }
+                    }
+                }
+                "sym" => {
+                    if let (Some(name), Some(value)) = (field("name"), number("val")?) {
+                        if field("type") != Some("imp") && value <= 0xFFFF {
+                            symbols.push((name.to_string(), value as u16, number("seg")?, number("size")?, field("type") == Some("lab")));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (name, value, seg, size, is_label) in symbols {
+            let bank = if is_label { seg.and_then(|seg| segments.get(&seg)).and_then(|seg| seg.bank) } else { None };
+            self.labels.insert(value, bank, Label { name, size: size.map(|size| size.min(0xFFFF) as u16) });
+        }
+        for (file, line, span) in lines {
+            let (segment, start, size) = match spans.get(&span).and_then(|(seg, start, size)| segments.get(seg).map(|seg| (seg, *start, *size))) {
+                Some(span) => span,
+                None => continue,
+            };
+            let address = segment.start + start;
+            if address > 0xFFFF {
+                continue;
+            }
+            let file = match files.get(&file) {
+                Some(name) => self.file_id(name),
+                None => continue,
+            };
+            self.lines.insert(address as u16, segment.bank, SourceLine { file, line, size: size.min(0xFFFF) as u16 });
+        }
+        Ok(())
+    }
+
+    // "al C:080d .label", other monitor commands in the file are skipped
+    pub fn import_vice(&mut self, text: &str) -> Result<(), SymbolError> {
+        for (index, line) in text.lines().enumerate() {
+            let mut words = line.split_whitespace();
+            if words.next() != Some("al") {
+                continue;
+            }
+            let syntax = SymbolError::Syntax { line: index + 1 };
+            let (address, name) = match (words.next(), words.next()) {
+                (Some(address), Some(name)) => (address, name),
+                _ => return Err(syntax),
+            };
+            let address = address.rsplit(':').next().unwrap_or(address);
+            let address = u16::from_str_radix(address, 16).map_err(|_| syntax)?;
+            self.add_label(name.trim_start_matches('.'), address, None);
+        }
+        Ok(())
+    }
+
+    // "$C000#Reset#comment" or "$0200/100#oam#" for a range, bank is the file's bank or None
+    // for the ram file
+    pub fn import_fceux_nl(&mut self, text: &str, bank: Option<u32>) -> Result<(), SymbolError> {
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let syntax = || SymbolError::Syntax { line: index + 1 };
+            let mut fields = line.splitn(3, '#');
+            let address = fields.next().and_then(|a| a.strip_prefix('$')).ok_or_else(syntax)?;
+            let name = fields.next().ok_or_else(syntax)?;
+            if name.is_empty() {
+                continue;
+            }
+
+            let (address, size) = match address.split_once('/') {
+                Some((address, size)) => (address, Some(u16::from_str_radix(size, 16).map_err(|_| syntax())?)),
+                None => (address, None),
+            };
+            let address = u16::from_str_radix(address, 16).map_err(|_| syntax())?;
+            let bank = if address >= 0x8000 { bank } else { None };
+            self.labels.insert(address, bank, Label { name: name.to_string(), size });
+        }
+        Ok(())
+    }
+
+    // "NesPrgRom:1234:label:comment", the memory type picks how the address is read. prg rom
+    // addresses are rom offsets, work and save ram offsets from $6000
+    pub fn import_mesen_mlb(&mut self, text: &str) -> Result<(), SymbolError> {
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let syntax = || SymbolError::Syntax { line: index + 1 };
+            let mut fields = line.splitn(4, ':');
+            let kind = fields.next().ok_or_else(syntax)?;
+            let range = fields.next().ok_or_else(syntax)?;
+            let name = fields.next().unwrap_or("");
+            if name.is_empty() {
+                continue;
+            }
+
+            let (start, end) = match range.split_once('-') {
+                Some((start, end)) => (start, Some(end)),
+                None => (range, None),
+            };
+            let start = u32::from_str_radix(start, 16).map_err(|_| syntax())?;
+            let size = match end {
+                Some(end) => Some((u32::from_str_radix(end, 16).map_err(|_| syntax())?.saturating_sub(start) + 1).min(0xFFFF) as u16),
+                None => None,
+            };
+
+            let (address, bank) = match kind {
+                "NesPrgRom" | "P" => (0x8000 | (start % BANK_SIZE as u32), Some(start / BANK_SIZE as u32)),
+                "NesInternalRam" | "R" | "NesRegisters" | "G" => (start, None),
+                "NesWorkRam" | "W" | "NesSaveRam" | "S" => (0x6000 + start, None),
+                // chr, nametables and other ppu side memory
+                _ => continue,
+            };
+            if address > 0xFFFF {
+                return Err(syntax());
+            }
+            self.labels.insert(address as u16, bank, Label { name: name.to_string(), size });
+        }
+        Ok(())
+    }
+}
+
+impl Default for SymbolTable {
+    fn default() -> SymbolTable {
+        SymbolTable::new()
+    }
+}
+
+// key=value pairs split on commas outside of quotes
+fn dbg_fields(text: &str, line: usize) -> Result<Vec<(String, String)>, SymbolError> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in text.chars().chain(std::iter::once(',')) {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                if !current.is_empty() {
+                    let (key, value) = current.split_once('=').ok_or(SymbolError::Syntax { line })?;
+                    fields.push((key.to_string(), value.to_string()));
+                    current.clear();
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    Ok(fields)
+}
+
+fn dbg_number(text: &str, line: usize) -> Result<u32, SymbolError> {
+    let parsed = match text.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    parsed.map_err(|_| SymbolError::Syntax { line })
+}
@@ -0,0 +1,57 @@
+use super::clock::Region;
+
+/*
+Region dependent apu timing
+
+The 2A03 and 2A07 share the cpu core but the apu counts in cpu cycles, so the 2A07 divides
+its faster crystal with different noise and dmc period tables and frame sequencer steps to
+land on roughly the same pitches and a 50hz frame. The Dendy clones run the ntsc apu tables.
+
+Periods and steps are in cpu cycles. A frame sequencer step falls on the cycle given, the
+four step sequence raises the frame irq on its last step and the one before and after it.
+*/
+
+#[derive(Debug, PartialEq)]
+pub struct ApuTiming {
+    pub noise_periods: [u16; 16],
+    pub dmc_rates: [u16; 16],
+    // quarter and half frame clocks, the last entry ends the sequence
+    pub four_step: [u32; 4],
+    pub five_step: [u32; 4],
+    // cycles in each sequence, the counter restarts after this many
+    pub four_step_period: u32,
+    pub five_step_period: u32,
+}
+
+pub const NTSC_APU: ApuTiming = ApuTiming {
+    noise_periods: [4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068],
+    dmc_rates: [428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 84, 72, 54],
+    four_step: [7457, 14913, 22371, 29829],
+    five_step: [7457, 14913, 22371, 37281],
+    four_step_period: 29830,
+    five_step_period: 37282,
+};
+
+pub const PAL_APU: ApuTiming = ApuTiming {
+    noise_periods: [4, 8, 14, 30, 60, 88, 118, 148, 188, 236, 354, 472, 708, 944, 1890, 3778],
+    dmc_rates: [398, 354, 316, 298, 276, 236, 210, 198, 176, 148, 132, 118, 98, 78, 66, 50],
+    four_step: [8313, 16627, 24939, 33253],
+    five_step: [8313, 16627, 24939, 41565],
+    four_step_period: 33254,
+    five_step_period: 41566,
+};
+
+// the same on every region
+pub const LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14,
+    12, 16, 24, 18, 48, 20, 96, 22, 192, 24, 72, 26, 16, 28, 32, 30,
+];
+
+impl ApuTiming {
+    pub fn for_region(region: Region) -> &'static ApuTiming {
+        match region {
+            Region::Ntsc | Region::Dendy => &NTSC_APU,
+            Region::Pal => &PAL_APU,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+/*
+Unstable undocumented opcodes
+
+A few undocumented opcodes depend on analog effects that differ between chips and even
+between runs on the same chip. Their behaviour is selected here instead of hard coded.
+
+XAA (0x8B)      A = (A | xaa_magic) & X & #imm
+LAX #imm (0xAB) A = X = (A | lax_magic) & #imm
+
+SHA (0x93, 0x9F), SHX (0x9E), SHY (0x9C) and TAS (0x9B) store a register anded with the
+high byte of the base address + 1. When RDY stalls the cycle before the write the and is
+dropped, and when the index crosses a page the stored value replaces the high byte of the
+target address.
+
+The default is rp2a03(). Before these profiles LAX #imm anded A with the operand, the same as
+a lax_magic of 0x00, and XAA ignored A like an xaa_magic of 0xFF. The default now loads the
+operand directly for LAX #imm.
+*/
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct UnstableOpcodeConfig {
+    pub xaa_magic: u8,
+    pub lax_magic: u8,
+    // SHA/SHX/SHY/TAS store the whole register when RDY stalled the cycle before the write
+    pub shx_rdy_drops_and: bool,
+    // SHA/SHX/SHY/TAS write to (value, adl) when the index crosses a page
+    pub shx_page_cross_corrupts_address: bool,
+}
+
+impl UnstableOpcodeConfig {
+    // nmos 6502 as simulated by visual6502, the behaviour the SingleStepTests suites expect
+    pub fn mos6502() -> UnstableOpcodeConfig {
+        UnstableOpcodeConfig {
+            xaa_magic: 0xEE,
+            lax_magic: 0xEE,
+            shx_rdy_drops_and: true,
+            shx_page_cross_corrupts_address: true,
+        }
+    }
+
+    // values commonly reported for the nes cpu, LAX #imm loads the operand directly
+    pub fn rp2a03() -> UnstableOpcodeConfig {
+        UnstableOpcodeConfig {
+            xaa_magic: 0xFF,
+            lax_magic: 0xFF,
+            shx_rdy_drops_and: true,
+            shx_page_cross_corrupts_address: true,
+        }
+    }
+
+    // synthetic profile for tests, not a chip revision. both magic constants are 0x00 so XAA
+    // and LAX #imm and with A
+    pub fn zero_magic() -> UnstableOpcodeConfig {
+        UnstableOpcodeConfig {
+            xaa_magic: 0x00,
+            lax_magic: 0x00,
+            shx_rdy_drops_and: true,
+            shx_page_cross_corrupts_address: true,
+        }
+    }
+}
+
+impl Default for UnstableOpcodeConfig {
+    fn default() -> UnstableOpcodeConfig {
+        UnstableOpcodeConfig::rp2a03()
+    }
+}